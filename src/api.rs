@@ -0,0 +1,801 @@
+use std::{
+    collections::HashMap,
+    fs::{self, OpenOptions},
+    io::{BufRead, BufReader, Read, Write},
+    net::{Ipv4Addr, TcpListener, TcpStream},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, RecvTimeoutError, Sender},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use unknproject_core::hacks::find_hack;
+
+use crate::{
+    cli::{InjectArgs, InjectCommand},
+    inject::InjectionEvent,
+    utils::{
+        hash::random_token,
+        history::{InjectionMethod, InjectionSource},
+    },
+    MyApp,
+};
+
+/// Largest request body we accept; every request is a small JSON object.
+const MAX_BODY_SIZE: usize = 64 * 1024;
+
+/// What a script asked for. Everything but the event stream is answered by the window,
+/// so API injections run through the same queue and dialogs as clicks.
+pub enum ApiRequest {
+    Hacks,
+    Status,
+    Download { hack: String },
+    Inject(InjectArgs),
+}
+
+pub struct ApiCall {
+    pub request: ApiRequest,
+    pub reply: Sender<ApiResponse>,
+}
+
+pub struct ApiResponse {
+    pub status: u16,
+    pub body: Value,
+}
+
+impl ApiResponse {
+    pub fn ok(body: Value) -> Self {
+        Self { status: 200, body }
+    }
+
+    pub fn accepted(body: Value) -> Self {
+        Self { status: 202, body }
+    }
+
+    pub fn error(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: json!({ "error": message.into() }),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct DownloadBody {
+    hack: String,
+}
+
+#[derive(Deserialize)]
+struct InjectBody {
+    hack: Option<String>,
+    dll_path: Option<PathBuf>,
+    process: Option<String>,
+    method: Option<InjectionMethod>,
+    #[serde(default)]
+    wait: bool,
+}
+
+impl InjectBody {
+    fn into_args(self) -> Result<InjectArgs, String> {
+        let command = match (self.hack, self.dll_path) {
+            (Some(query), None) => InjectCommand::Hack {
+                query,
+                process: self.process,
+            },
+            (None, Some(dll_path)) => {
+                if !dll_path.is_file() {
+                    return Err(format!("{} does not exist.", dll_path.display()));
+                }
+                InjectCommand::File {
+                    dll_path,
+                    process: self
+                        .process
+                        .ok_or("Injecting a dll_path needs a process.")?,
+                }
+            }
+            _ => return Err("Pass either hack or dll_path.".to_string()),
+        };
+
+        Ok(InjectArgs {
+            command,
+            method: self.method,
+            wait: self.wait,
+            force: false,
+            source: InjectionSource::Api,
+        })
+    }
+}
+
+/// Opt-in HTTP API on 127.0.0.1 for scripts and stream-deck macros. Every request needs
+/// an `Authorization: Bearer <token>` header with the token from the config directory.
+pub struct ApiServer {
+    stop: Arc<AtomicBool>,
+    subscribers: Arc<Mutex<Vec<Sender<String>>>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ApiServer {
+    fn token_path() -> PathBuf {
+        dirs::config_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("unknproject")
+            .join("api_token")
+    }
+
+    /// Reads the token, creating one the first time the API is used.
+    pub fn token() -> Result<String, String> {
+        read_or_create_token(&Self::token_path())
+    }
+
+    pub fn start(
+        port: u16,
+        calls: Sender<ApiCall>,
+        status_message: Arc<Mutex<String>>,
+        ctx: egui::Context,
+    ) -> Result<Self, String> {
+        let token = Self::token()?;
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+            .map_err(|e| format!("Failed to start the API on port {}: {}", port, e))?;
+        let api = Self::listen(listener, token, calls, status_message, ctx)?;
+
+        log::info!("Control API listening on http://127.0.0.1:{}/api/", port);
+        Ok(api)
+    }
+
+    fn listen(
+        listener: TcpListener,
+        token: String,
+        calls: Sender<ApiCall>,
+        status_message: Arc<Mutex<String>>,
+        ctx: egui::Context,
+    ) -> Result<Self, String> {
+        // Polled so the server can be stopped from the settings without a restart.
+        listener
+            .set_nonblocking(true)
+            .map_err(|e| format!("Failed to start the API: {}", e))?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let subscribers = Arc::new(Mutex::new(Vec::new()));
+        let connection = Connection {
+            token,
+            calls,
+            status_message,
+            subscribers: Arc::clone(&subscribers),
+            stop: Arc::clone(&stop),
+            ctx,
+        };
+
+        let thread_stop = Arc::clone(&stop);
+        let thread = thread::spawn(move || {
+            while !thread_stop.load(Ordering::SeqCst) {
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        let connection = connection.clone();
+                        thread::spawn(move || connection.handle(stream));
+                    }
+                    Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                        thread::sleep(Duration::from_millis(100));
+                    }
+                    Err(e) => log::warn!("API connection failed: {}", e),
+                }
+            }
+        });
+
+        Ok(Self {
+            stop,
+            subscribers,
+            thread: Some(thread),
+        })
+    }
+
+    /// Sends `event` to every open `/api/events` stream.
+    pub fn publish(&self, event: &InjectionEvent) {
+        let (name, data) = match event {
+            InjectionEvent::Succeeded(name) => ("succeeded", json!({ "name": name })),
+            InjectionEvent::Failed(message) => ("failed", json!({ "message": message })),
+            InjectionEvent::Crashed(name, message) => {
+                ("crashed", json!({ "name": name, "message": message }))
+            }
+            InjectionEvent::Recorded(record) => ("recorded", json!(record)),
+            InjectionEvent::Duplicate(request, reason) => (
+                "awaiting_confirmation",
+                json!({ "name": request.name, "reason": reason }),
+            ),
+            InjectionEvent::ModuleLoaded(module) => (
+                "module_loaded",
+                json!({
+                    "name": module.name,
+                    "path": module.path,
+                    "process": module.process,
+                    "pid": module.pid,
+                    "method": module.method,
+                }),
+            ),
+            InjectionEvent::Ejected(module, result) => (
+                "module_ejected",
+                json!({
+                    "name": module.name,
+                    "process": module.process,
+                    "pid": module.pid,
+                    "error": result.as_ref().err(),
+                }),
+            ),
+        };
+
+        let message = server_sent_event(name, &data);
+        self.subscribers
+            .lock()
+            .unwrap()
+            .retain(|subscriber| subscriber.send(message.clone()).is_ok());
+    }
+}
+
+impl Drop for ApiServer {
+    /// Waits for the listener to close, so the port is free again right away.
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            thread.join().ok();
+        }
+        log::info!("Control API stopped");
+    }
+}
+
+/// Only the current user may read the token, it's all that stands between other local
+/// accounts and injecting into this user's games.
+fn read_or_create_token(path: &Path) -> Result<String, String> {
+    if let Ok(token) = fs::read_to_string(path) {
+        if !token.trim().is_empty() {
+            return Ok(token.trim().to_string());
+        }
+    }
+
+    let token = random_token()?;
+    let write = || -> std::io::Result<()> {
+        let mut options = OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(path)?;
+        // The mode only applies to new files, not to an empty one left behind.
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            file.set_permissions(fs::Permissions::from_mode(0o600))?;
+        }
+        file.write_all(token.as_bytes())
+    };
+    write().map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(token)
+}
+
+/// Compares without returning at the first difference, so response times don't tell how
+/// much of a guessed token was right.
+fn tokens_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
+
+fn server_sent_event(name: &str, data: &Value) -> String {
+    format!("event: {}\ndata: {}\n\n", name, data)
+}
+
+struct HttpRequest {
+    method: String,
+    path: String,
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+fn read_request(stream: &TcpStream) -> Result<HttpRequest, String> {
+    let mut reader = BufReader::new(stream);
+
+    let mut line = String::new();
+    reader.read_line(&mut line).map_err(|e| e.to_string())?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err("malformed request line".to_string());
+    };
+    let path = target.split('?').next().unwrap_or(target).to_string();
+    let method = method.to_string();
+
+    let mut headers = HashMap::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).map_err(|e| e.to_string())?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+        }
+    }
+
+    let length = headers
+        .get("content-length")
+        .map(|length| length.parse::<usize>())
+        .transpose()
+        .map_err(|_| "invalid Content-Length".to_string())?
+        .unwrap_or(0);
+    if length > MAX_BODY_SIZE {
+        return Err("request body too large".to_string());
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).map_err(|e| e.to_string())?;
+
+    Ok(HttpRequest {
+        method,
+        path,
+        headers,
+        body,
+    })
+}
+
+fn write_response(mut stream: &TcpStream, response: &ApiResponse) {
+    let reason = match response.status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    };
+    let body = response.body.to_string();
+
+    let written = write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        reason,
+        body.len(),
+        body
+    );
+    if let Err(e) = written {
+        log::debug!("Failed to answer API request: {}", e);
+    }
+}
+
+/// What each connection thread needs to answer a request.
+#[derive(Clone)]
+struct Connection {
+    token: String,
+    calls: Sender<ApiCall>,
+    status_message: Arc<Mutex<String>>,
+    subscribers: Arc<Mutex<Vec<Sender<String>>>>,
+    stop: Arc<AtomicBool>,
+    ctx: egui::Context,
+}
+
+impl Connection {
+    fn handle(self, stream: TcpStream) {
+        if stream.set_nonblocking(false).is_err()
+            || stream
+                .set_read_timeout(Some(Duration::from_secs(5)))
+                .is_err()
+        {
+            return;
+        }
+
+        let request = match read_request(&stream) {
+            Ok(request) => request,
+            Err(e) => {
+                write_response(&stream, &ApiResponse::error(400, e));
+                return;
+            }
+        };
+
+        let authorized = request
+            .headers
+            .get("authorization")
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|token| tokens_match(token.trim(), &self.token));
+        if !authorized {
+            log::warn!("Rejected unauthorized API request to {}", request.path);
+            write_response(&stream, &ApiResponse::error(401, "Missing or wrong token."));
+            return;
+        }
+
+        log::debug!("API request {} {}", request.method, request.path);
+        let response = match (request.method.as_str(), request.path.as_str()) {
+            ("GET", "/api/events") => return self.stream_events(stream),
+            ("GET", "/api/hacks") => self.call(ApiRequest::Hacks),
+            ("GET", "/api/status") => self.call(ApiRequest::Status),
+            ("POST", "/api/download") => {
+                match serde_json::from_slice::<DownloadBody>(&request.body) {
+                    Ok(body) => self.call(ApiRequest::Download { hack: body.hack }),
+                    Err(e) => ApiResponse::error(400, e.to_string()),
+                }
+            }
+            ("POST", "/api/inject") => match serde_json::from_slice::<InjectBody>(&request.body)
+                .map_err(|e| e.to_string())
+                .and_then(InjectBody::into_args)
+            {
+                Ok(args) => self.call(ApiRequest::Inject(args)),
+                Err(e) => ApiResponse::error(400, e),
+            },
+            (_, "/api/events" | "/api/hacks" | "/api/status" | "/api/download" | "/api/inject") => {
+                ApiResponse::error(405, "Method not allowed.")
+            }
+            _ => ApiResponse::error(404, "Unknown endpoint."),
+        };
+
+        write_response(&stream, &response);
+    }
+
+    /// Hands the request to the window and waits for its answer.
+    fn call(&self, request: ApiRequest) -> ApiResponse {
+        let (reply, response) = mpsc::channel();
+        if self.calls.send(ApiCall { request, reply }).is_err() {
+            return ApiResponse::error(503, "The loader is shutting down.");
+        }
+        self.ctx.request_repaint();
+
+        response
+            .recv_timeout(Duration::from_secs(10))
+            .unwrap_or_else(|_| ApiResponse::error(503, "The loader window didn't answer."))
+    }
+
+    /// Streams injection events and status changes as server-sent events until the
+    /// client disconnects or the API is turned off.
+    fn stream_events(self, mut stream: TcpStream) {
+        let (sender, events) = mpsc::channel();
+        self.subscribers.lock().unwrap().push(sender);
+
+        if write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n"
+        )
+        .is_err()
+        {
+            return;
+        }
+
+        let mut status = None;
+        let mut last_write = Instant::now();
+        while !self.stop.load(Ordering::SeqCst) {
+            let message = match events.recv_timeout(Duration::from_millis(200)) {
+                Ok(message) => Some(message),
+                Err(RecvTimeoutError::Timeout) => None,
+                Err(RecvTimeoutError::Disconnected) => return,
+            };
+
+            let current = self.status_message.lock().unwrap().clone();
+            let status_changed = status.as_ref() != Some(&current);
+            let mut output = String::new();
+            if status_changed {
+                output += &server_sent_event("status", &json!({ "message": current }));
+                status = Some(current);
+            }
+            if let Some(message) = message {
+                output += &message;
+            }
+            // A comment now and then notices clients that went away.
+            if output.is_empty() && last_write.elapsed() >= Duration::from_secs(15) {
+                output = ": keep-alive\n\n".to_string();
+            }
+
+            if !output.is_empty() {
+                if stream.write_all(output.as_bytes()).is_err() || stream.flush().is_err() {
+                    return;
+                }
+                last_write = Instant::now();
+            }
+        }
+    }
+}
+
+impl MyApp {
+    /// Starts or stops the API to match the config.
+    pub fn apply_api_settings(&mut self, ctx: &egui::Context) {
+        self.api = None;
+        if !self.app.config.api_enabled {
+            return;
+        }
+
+        match ApiServer::start(
+            self.app.config.api_port,
+            self.communication.api_sender.clone(),
+            Arc::clone(&self.communication.status_message),
+            ctx.clone(),
+        ) {
+            Ok(api) => self.api = Some(api),
+            Err(e) => {
+                log::error!("{}", e);
+                self.toasts.error(e);
+            }
+        }
+    }
+
+    pub fn handle_api_calls(&mut self, ctx: &egui::Context) {
+        while let Ok(call) = self.communication.api_receiver.try_recv() {
+            let response = match call.request {
+                ApiRequest::Hacks => ApiResponse::ok(Value::Array(
+                    self.app
+                        .hacks
+                        .iter()
+                        .map(|hack| {
+                            let mut value = json!(hack);
+                            value["downloaded"] = Value::Bool(hack.file_path.exists());
+                            value["favorite"] =
+                                Value::Bool(self.app.config.favorites.contains(&hack.name));
+                            value
+                        })
+                        .collect(),
+                )),
+                ApiRequest::Status => ApiResponse::ok(json!({
+                    "in_progress": self.communication.inject_in_progress.load(Ordering::SeqCst),
+                    "status": self.communication.status_message.lock().unwrap().clone(),
+                    "selected_hack": self.app.selected_hack.as_ref().map(|hack| &hack.name),
+                    "awaiting_confirmation": self.ui.pending_injection.is_some(),
+                    "last_injection": self.app.history.records.last(),
+                })),
+                ApiRequest::Download { hack } => match find_hack(&self.app.hacks, &hack) {
+                    None => ApiResponse::error(404, format!("No hack named {}.", hack)),
+                    Some(hack) if hack.file_path.exists() => {
+                        ApiResponse::ok(json!({ "status": "downloaded", "path": hack.file_path }))
+                    }
+                    Some(hack) => {
+                        self.spawn_download(hack.clone(), ctx.clone());
+                        ApiResponse::accepted(json!({ "status": "downloading" }))
+                    }
+                },
+                ApiRequest::Inject(inject) => {
+                    if self.communication.inject_in_progress.load(Ordering::SeqCst) {
+                        ApiResponse::error(409, "An injection is already running.")
+                    } else {
+                        let wait = inject.wait;
+                        match self.start_injection(inject, ctx) {
+                            Ok(()) if wait => {
+                                ApiResponse::accepted(json!({ "status": "waiting_for_process" }))
+                            }
+                            Ok(()) => ApiResponse::accepted(json!({ "status": "started" })),
+                            Err(e) => ApiResponse::error(404, e),
+                        }
+                    }
+                }
+            };
+
+            call.reply.send(response).ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use super::*;
+
+    const TOKEN: &str = "secret-token";
+
+    /// Starts the API on a free port with a stand-in window that answers every call with
+    /// the name of the request.
+    fn serve() -> (ApiServer, u16) {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (calls, window) = mpsc::channel::<ApiCall>();
+        thread::spawn(move || {
+            for call in window {
+                let body = match call.request {
+                    ApiRequest::Hacks => json!({ "request": "hacks" }),
+                    ApiRequest::Status => json!({ "request": "status" }),
+                    ApiRequest::Download { hack } => json!({ "request": "download", "hack": hack }),
+                    ApiRequest::Inject(args) => match args.command {
+                        InjectCommand::Hack { query, process } => {
+                            json!({ "request": "inject", "hack": query, "process": process })
+                        }
+                        InjectCommand::File { process, .. } => {
+                            json!({ "request": "inject", "process": process })
+                        }
+                    },
+                };
+                call.reply.send(ApiResponse::ok(body)).ok();
+            }
+        });
+
+        let api = ApiServer::listen(
+            listener,
+            TOKEN.to_string(),
+            calls,
+            Arc::new(Mutex::new(String::new())),
+            egui::Context::default(),
+        )
+        .unwrap();
+        (api, port)
+    }
+
+    /// Sends `head` (request line and headers) and `body`, returns the status and body.
+    fn send(port: u16, head: &str, body: &str) -> (u16, Value) {
+        let mut stream = TcpStream::connect((Ipv4Addr::LOCALHOST, port)).unwrap();
+        write!(stream, "{}\r\n\r\n{}", head, body).unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.split_whitespace().nth(1).unwrap().parse().unwrap();
+        (status, serde_json::from_str(body).unwrap())
+    }
+
+    fn authorized(port: u16, method: &str, path: &str, body: &str) -> (u16, Value) {
+        send(
+            port,
+            &format!(
+                "{} {} HTTP/1.1\r\nAuthorization: Bearer {}\r\nContent-Length: {}",
+                method,
+                path,
+                TOKEN,
+                body.len()
+            ),
+            body,
+        )
+    }
+
+    #[test]
+    fn requests_need_the_token() {
+        let (_api, port) = serve();
+
+        let (status, _) = send(port, "GET /api/status HTTP/1.1", "");
+        assert_eq!(status, 401);
+        let (status, _) = send(
+            port,
+            "GET /api/status HTTP/1.1\r\nAuthorization: Bearer secret-tokeN",
+            "",
+        );
+        assert_eq!(status, 401);
+        let (status, _) = send(
+            port,
+            &format!("GET /api/status HTTP/1.1\r\nAuthorization: {}", TOKEN),
+            "",
+        );
+        assert_eq!(status, 401);
+
+        let (status, body) = authorized(port, "GET", "/api/status", "");
+        assert_eq!(status, 200);
+        assert_eq!(body["request"], "status");
+
+        assert!(tokens_match(TOKEN, TOKEN));
+        assert!(!tokens_match("secret", TOKEN));
+        assert!(!tokens_match("", TOKEN));
+    }
+
+    #[test]
+    fn malformed_requests_are_refused() {
+        let (_api, port) = serve();
+
+        let (status, body) = send(
+            port,
+            &format!(
+                "POST /api/inject HTTP/1.1\r\nAuthorization: Bearer {}\r\nContent-Length: {}",
+                TOKEN,
+                MAX_BODY_SIZE + 1
+            ),
+            "",
+        );
+        assert_eq!(status, 400);
+        assert_eq!(body["error"], "request body too large");
+
+        let (status, body) = send(
+            port,
+            "POST /api/inject HTTP/1.1\r\nContent-Length: lots",
+            "",
+        );
+        assert_eq!(status, 400);
+        assert_eq!(body["error"], "invalid Content-Length");
+    }
+
+    #[test]
+    fn routes_requests_to_the_window() {
+        let (_api, port) = serve();
+
+        let (status, body) = authorized(port, "GET", "/api/hacks?fresh=1", "");
+        assert_eq!((status, &body["request"]), (200, &json!("hacks")));
+
+        let (status, body) = authorized(port, "POST", "/api/download", r#"{"hack":"Hack"}"#);
+        assert_eq!(status, 200);
+        assert_eq!(body, json!({ "request": "download", "hack": "Hack" }));
+
+        let (status, body) = authorized(
+            port,
+            "POST",
+            "/api/inject",
+            r#"{"hack":"Hack","process":"game.exe"}"#,
+        );
+        assert_eq!(status, 200);
+        assert_eq!(
+            body,
+            json!({ "request": "inject", "hack": "Hack", "process": "game.exe" })
+        );
+
+        let (status, _) = authorized(port, "POST", "/api/download", "{}");
+        assert_eq!(status, 400);
+        let (status, _) = authorized(port, "DELETE", "/api/hacks", "");
+        assert_eq!(status, 405);
+        let (status, _) = authorized(port, "POST", "/api/events", "");
+        assert_eq!(status, 405);
+        let (status, _) = authorized(port, "GET", "/api/nothing", "");
+        assert_eq!(status, 404);
+    }
+
+    #[test]
+    fn inject_bodies_name_one_target() {
+        let dll = env::temp_dir().join(format!("unknproject-api-{}.dll", process::id()));
+        fs::write(&dll, b"MZ").unwrap();
+        let body = |json: Value| {
+            serde_json::from_value::<InjectBody>(json)
+                .unwrap()
+                .into_args()
+        };
+
+        let args = body(json!({ "hack": "Hack", "method": "manual_map", "wait": true })).unwrap();
+        assert!(matches!(
+            args.command,
+            InjectCommand::Hack { ref query, process: None } if query == "Hack"
+        ));
+        assert_eq!(args.method, Some(InjectionMethod::ManualMap));
+        assert!(args.wait && !args.force);
+        assert_eq!(args.source, InjectionSource::Api);
+
+        let args = body(json!({ "dll_path": dll, "process": "game.exe" })).unwrap();
+        assert!(matches!(
+            args.command,
+            InjectCommand::File { ref process, .. } if process == "game.exe"
+        ));
+
+        let both = json!({ "hack": "Hack", "dll_path": dll, "process": "game.exe" });
+        assert_eq!(body(both).err().unwrap(), "Pass either hack or dll_path.");
+        assert_eq!(
+            body(json!({})).err().unwrap(),
+            "Pass either hack or dll_path."
+        );
+        assert_eq!(
+            body(json!({ "dll_path": dll })).err().unwrap(),
+            "Injecting a dll_path needs a process."
+        );
+        assert!(
+            body(json!({ "dll_path": "missing.dll", "process": "game.exe" }))
+                .err()
+                .unwrap()
+                .ends_with("does not exist.")
+        );
+
+        fs::remove_file(&dll).ok();
+    }
+
+    #[test]
+    fn the_token_is_created_once_and_private() {
+        let path = env::temp_dir().join(format!("unknproject-api-token-{}", process::id()));
+        fs::remove_file(&path).ok();
+
+        let token = read_or_create_token(&path).unwrap();
+        assert!(!token.is_empty());
+        assert_eq!(read_or_create_token(&path).unwrap(), token);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        fs::write(&path, "  \n").unwrap();
+        assert_ne!(read_or_create_token(&path).unwrap().trim(), "");
+
+        fs::remove_file(&path).ok();
+    }
+}
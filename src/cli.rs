@@ -0,0 +1,1092 @@
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::Duration,
+};
+
+use serde_json::Value;
+use simplelog::{ColorChoice, LevelFilter, TermLogger, TerminalMode};
+use unknproject_core::{
+    catalog::{self, CatalogChange, LintIssue, Severity},
+    hacks::{find_hack, Hack},
+};
+
+use crate::{
+    inject::{inject_headless, InjectionRequest, OnDuplicate},
+    utils::{
+        bundle,
+        config::Config,
+        deeplink::DeepLink,
+        downloader::Downloader,
+        errors::ErrorKind,
+        hash::sha256_file,
+        history::{History, InjectionMethod, InjectionOutcome, InjectionRecord, InjectionSource},
+        http,
+        injector_assets::{self, InjectorAssets},
+        instance::Instance,
+        statistics::Statistics,
+    },
+};
+
+const DEFAULT_CATALOG: &str = "resources/Hacklist.html";
+
+const USAGE: &str = "\
+Usage: unknproject [--verbose] <command> [options]
+
+Without a command the loader opens its window.
+
+Commands:
+  list [--game <game>] [--process <name>] [--status <status>] [--search <text>]
+       [--favorites] [--json]                List the hacks in the catalog
+  info <hack> [--json]                       Show details about a hack
+  download <hack> [--force]                  Download a hack without injecting it
+  inject <hack> [--process <name>] [--method standard|manual-map] [--wait] [--force]
+                                             Download and inject a hack
+  inject-file <dll> --process <name> [--method standard|manual-map] [--wait] [--force]
+                                             Inject a DLL from disk
+  catalog lint [<file>] [--hacks-dir <dir>]  Check a catalog for mistakes
+  catalog build <entries dir> [--hacks-dir <dir>] [--output <file>]
+                                             Assemble a catalog from TOML entries
+  catalog diff <old file> <new file>         Compare two catalog versions
+  bundle export <file> [<hack>...]           Write an offline bundle of the given hacks, or
+                                             of the whole catalog, and the injectors
+  bundle import <file>                       Verify an offline bundle and add it as a
+                                             catalog source
+  config get [<key>]                         Print the config, or one value of it
+  config set <key> <value>                   Change a config value
+  help                                       Show this message
+
+--wait waits for the target to start, up to the launch timeout from the config.
+--force injects even if the DLL already seems to be loaded in the target.
+While the loader window is open, inject and inject-file are handed over to it.
+download, config set and bundle import refuse to run while it's open.
+An unknproject://select/<id>, inject/<id> or add-source?url=<url> link opens the
+window, which asks before injecting or adding the source.
+Catalog commands default to resources/Hacklist.html and the hacks directory next to it.
+
+Exit codes:
+  0  success
+  1  unexpected error
+  2  invalid arguments
+  3  unknown hack or config key
+  4  catalog or download unavailable
+  5  target process not running
+  6  injection failed
+  7  target crashed after injection
+  8  catalog or bundle has errors";
+
+/// Process exit codes. Scripts depend on these, so never renumber them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExitCode {
+    Success = 0,
+    Failure = 1,
+    Usage = 2,
+    NotFound = 3,
+    Network = 4,
+    ProcessNotRunning = 5,
+    InjectionFailed = 6,
+    Crashed = 7,
+    InvalidCatalog = 8,
+}
+
+#[derive(Debug)]
+struct CliError {
+    code: ExitCode,
+    message: String,
+}
+
+impl CliError {
+    fn new(code: ExitCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    fn usage(message: impl Into<String>) -> Self {
+        Self::new(ExitCode::Usage, message)
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+type CliResult = Result<(), CliError>;
+
+/// Positional arguments and `--name [value]` options of one command.
+struct Args {
+    positional: Vec<String>,
+    options: Vec<(String, Option<String>)>,
+}
+
+impl Args {
+    /// `valued` options take the next argument as their value, `switches` stand alone.
+    fn parse(args: &[String], valued: &[&str], switches: &[&str]) -> Result<Self, CliError> {
+        let mut parsed = Args {
+            positional: Vec::new(),
+            options: Vec::new(),
+        };
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let Some(name) = arg.strip_prefix("--") else {
+                parsed.positional.push(arg.clone());
+                continue;
+            };
+
+            if valued.contains(&name) {
+                let value = args
+                    .next()
+                    .ok_or_else(|| CliError::usage(format!("--{} needs a value.", name)))?;
+                parsed.options.push((name.to_string(), Some(value.clone())));
+            } else if switches.contains(&name) {
+                parsed.options.push((name.to_string(), None));
+            } else {
+                return Err(CliError::usage(format!("Unknown option --{}.", name)));
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    fn value(&self, name: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .rev()
+            .find(|(option, _)| option == name)
+            .and_then(|(_, value)| value.as_deref())
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.options.iter().any(|(option, _)| option == name)
+    }
+
+    /// The single positional argument named `what` in error messages.
+    fn target(&self, what: &str) -> Result<&str, CliError> {
+        match self.positional.as_slice() {
+            [target] => Ok(target),
+            [] => Err(CliError::usage(format!("Missing {}.", what))),
+            _ => Err(CliError::usage(format!("Expected a single {}.", what))),
+        }
+    }
+}
+
+/// Runs a headless command and returns the process exit code.
+pub fn run(args: &[String]) -> i32 {
+    attach_console();
+
+    let (verbose, args) = strip_verbose(args);
+    if verbose {
+        TermLogger::init(
+            LevelFilter::Debug,
+            simplelog::Config::default(),
+            TerminalMode::Stderr,
+            ColorChoice::Auto,
+        )
+        .ok();
+    }
+
+    let result = match args.split_first() {
+        Some((command, rest)) => match command.as_str() {
+            "list" => list(rest),
+            "info" => info(rest),
+            "download" => download(rest),
+            "inject" | "inject-file" => inject(command, rest),
+            "catalog" => catalog(rest),
+            "bundle" => bundle(rest),
+            "config" => config(rest),
+            "help" | "--help" | "-h" => {
+                println!("{}", USAGE);
+                Ok(())
+            }
+            "--version" | "-V" => {
+                println!("unknproject {}", env!("CARGO_PKG_VERSION"));
+                Ok(())
+            }
+            command => Err(CliError::usage(format!("Unknown command {}.", command))),
+        },
+        None => Err(CliError::usage("Missing command.")),
+    };
+
+    match result {
+        Ok(()) => ExitCode::Success as i32,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            if e.code == ExitCode::Usage {
+                eprintln!("\n{}", USAGE);
+            }
+            e.code as i32
+        }
+    }
+}
+
+/// Splits off `--verbose`, which is accepted anywhere on the command line.
+fn strip_verbose(args: &[String]) -> (bool, Vec<String>) {
+    let verbose = args.iter().any(|arg| arg == "--verbose");
+    let args = args
+        .iter()
+        .filter(|arg| *arg != "--verbose")
+        .cloned()
+        .collect();
+    (verbose, args)
+}
+
+/// Release builds use the GUI subsystem and start without a console, so borrow the one
+/// of the shell we were started from for stdout and stderr.
+#[cfg(windows)]
+pub fn attach_console() {
+    use winapi::um::wincon::{AttachConsole, ATTACH_PARENT_PROCESS};
+
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+/// Other hosts keep the terminal we were started from.
+#[cfg(not(windows))]
+pub fn attach_console() {}
+
+/// The window keeps its own copy of the config and hack files and writes them whenever it
+/// likes, so commands that change them don't run next to it.
+fn refuse_while_running(command: &str) -> CliResult {
+    if Instance::is_running() {
+        return Err(CliError::new(
+            ExitCode::Failure,
+            format!("The loader is running, close it before using {}.", command),
+        ));
+    }
+    Ok(())
+}
+
+// MARK: Forwarding
+/// Commands the window takes over when it's already running.
+const FORWARDED_COMMANDS: [&str; 2] = ["inject", "inject-file"];
+
+/// Whether `args` belong to the window rather than a headless command: nothing at all,
+/// a forwardable command, an `unknproject://` link or a file such as a DLL dragged onto
+/// the exe.
+pub fn is_for_window(args: &[String]) -> bool {
+    match args.iter().find(|arg| *arg != "--verbose") {
+        None => true,
+        Some(first) => {
+            FORWARDED_COMMANDS.contains(&first.as_str())
+                || (args.len() == 1 && (DeepLink::is_link(first) || Path::new(first).is_file()))
+        }
+    }
+}
+
+/// What the window should do with the arguments of a launch.
+pub enum WindowCommand {
+    Focus,
+    Inject(InjectArgs),
+    OpenFile(PathBuf),
+    OpenLink(DeepLink),
+}
+
+pub fn parse_window_command(args: &[String]) -> Result<WindowCommand, String> {
+    let (_, args) = strip_verbose(args);
+
+    match args.split_first() {
+        None => Ok(WindowCommand::Focus),
+        Some((command, rest)) if FORWARDED_COMMANDS.contains(&command.as_str()) => {
+            parse_inject(command, rest)
+                .map(WindowCommand::Inject)
+                .map_err(|e| e.message)
+        }
+        Some((link, [])) if DeepLink::is_link(link) => {
+            DeepLink::parse(link).map(WindowCommand::OpenLink)
+        }
+        Some((path, [])) if Path::new(path).is_file() => {
+            Ok(WindowCommand::OpenFile(PathBuf::from(path)))
+        }
+        Some(_) => Err(format!("Unsupported arguments: {}", args.join(" "))),
+    }
+}
+
+// MARK: Catalog
+fn downloader(config: &Config) -> Downloader {
+    Downloader::new(http::from_env(), config)
+}
+
+fn fetch_catalog(config: &Config) -> Result<Vec<Hack>, CliError> {
+    Hack::fetch_catalog(&downloader(config), config).map_err(|e| {
+        CliError::new(
+            ExitCode::Network,
+            format!("Couldn't load the catalog: {}", e),
+        )
+    })
+}
+
+fn find_catalog_hack(hacks: &[Hack], query: &str) -> Result<Hack, CliError> {
+    find_hack(hacks, query)
+        .cloned()
+        .ok_or_else(|| CliError::new(ExitCode::NotFound, format!("No hack named {}.", query)))
+}
+
+fn list(args: &[String]) -> CliResult {
+    let args = Args::parse(
+        args,
+        &["game", "process", "status", "search"],
+        &["favorites", "json"],
+    )?;
+    if !args.positional.is_empty() {
+        return Err(CliError::usage("list takes no arguments."));
+    }
+
+    let config = Config::load();
+    let search = args.value("search").map(str::to_lowercase);
+    let hacks = fetch_catalog(&config)?
+        .into_iter()
+        .filter(|hack| {
+            args.value("game")
+                .map_or(true, |game| hack.game.eq_ignore_ascii_case(game))
+                && args
+                    .value("process")
+                    .map_or(true, |process| hack.process.eq_ignore_ascii_case(process))
+                && args
+                    .value("status")
+                    .map_or(true, |status| hack.status.eq_ignore_ascii_case(status))
+                && (!args.flag("favorites") || config.favorites.contains(&hack.name))
+                && search.as_ref().map_or(true, |search| {
+                    [&hack.name, &hack.description, &hack.author]
+                        .iter()
+                        .any(|field| field.to_lowercase().contains(search))
+                })
+        })
+        .collect::<Vec<_>>();
+
+    if args.flag("json") {
+        return print_json(&hacks);
+    }
+
+    let name_width = hacks
+        .iter()
+        .map(|hack| hack.name.len())
+        .max()
+        .unwrap_or_default()
+        .max(4);
+    println!(
+        "{:<name_width$}  {:<12}  {:<14}  {:<10}  AUTHOR",
+        "NAME", "GAME", "PROCESS", "STATUS"
+    );
+    for hack in &hacks {
+        println!(
+            "{:<name_width$}  {:<12}  {:<14}  {:<10}  {}",
+            hack.name, hack.game, hack.process, hack.status, hack.author
+        );
+    }
+
+    Ok(())
+}
+
+fn info(args: &[String]) -> CliResult {
+    let args = Args::parse(args, &[], &["json"])?;
+    let config = Config::load();
+    let hack = find_catalog_hack(&fetch_catalog(&config)?, args.target("hack")?)?;
+    let sha256 = sha256_file(&hack.file_path).ok();
+    let method = InjectionMethod::for_game(&hack.game);
+
+    if args.flag("json") {
+        let mut value = serde_json::to_value(&hack).map_err(unexpected)?;
+        value["downloaded"] = Value::Bool(sha256.is_some());
+        value["sha256"] = sha256.map_or(Value::Null, Value::String);
+        value["method"] = serde_json::to_value(method).map_err(unexpected)?;
+        value["favorite"] = Value::Bool(config.favorites.contains(&hack.name));
+        return print_json(&value);
+    }
+
+    println!("{} by {}", hack.name, hack.author);
+    println!("{}", hack.description);
+    println!();
+    println!("Id:       {}", hack.id);
+    println!("Game:     {}", hack.game);
+    println!("Process:  {}", hack.process);
+    println!("Status:   {}", hack.status);
+    println!("Method:   {}", method);
+    println!("Source:   {}", hack.source);
+    println!("File:     {}", hack.file_path.display());
+    match sha256 {
+        Some(sha256) => println!("SHA-256:  {}", sha256),
+        None => println!("SHA-256:  not downloaded"),
+    }
+    if let Some(app_id) = hack.steam_app_id {
+        println!("Steam:    app {}", app_id);
+    }
+    if !hack.required_launch_options.is_empty() {
+        println!("Requires: {}", hack.required_launch_options.join(" "));
+    }
+
+    Ok(())
+}
+
+fn download(args: &[String]) -> CliResult {
+    let args = Args::parse(args, &[], &["force"])?;
+    refuse_while_running("download")?;
+    let config = Config::load();
+    let hack = find_catalog_hack(&fetch_catalog(&config)?, args.target("hack")?)?;
+
+    if hack.file_path.exists() {
+        if !args.flag("force") {
+            println!("{}", hack.file_path.display());
+            eprintln!(
+                "{} is already downloaded, use --force to redownload it.",
+                hack.name
+            );
+            return Ok(());
+        }
+        fs::remove_file(&hack.file_path).map_err(|e| {
+            CliError::new(
+                ExitCode::Failure,
+                format!("Failed to remove {}: {}", hack.file_path.display(), e),
+            )
+        })?;
+    }
+
+    eprintln!("Downloading {}...", hack.name);
+    hack.download(
+        &downloader(&config),
+        hack.file_path.to_string_lossy().to_string(),
+    )
+    .map_err(|e| CliError::new(ExitCode::Network, e))?;
+    println!("{}", hack.file_path.display());
+
+    Ok(())
+}
+
+// MARK: Injection
+fn parse_method(args: &Args) -> Result<Option<InjectionMethod>, CliError> {
+    match args.value("method") {
+        None => Ok(None),
+        Some("standard") => Ok(Some(InjectionMethod::Standard)),
+        Some("manual-map" | "manual_map") => Ok(Some(InjectionMethod::ManualMap)),
+        Some(method) => Err(CliError::usage(format!(
+            "Unknown injection method {}, use standard or manual-map.",
+            method
+        ))),
+    }
+}
+
+/// What `inject` and `inject-file` should do; a running window handles the same
+/// commands when a second launch forwards them.
+pub enum InjectCommand {
+    Hack {
+        query: String,
+        process: Option<String>,
+    },
+    File {
+        dll_path: PathBuf,
+        process: String,
+    },
+}
+
+pub struct InjectArgs {
+    pub command: InjectCommand,
+    pub method: Option<InjectionMethod>,
+    pub wait: bool,
+    pub force: bool,
+    pub source: InjectionSource,
+}
+
+fn parse_inject(command: &str, args: &[String]) -> Result<InjectArgs, CliError> {
+    let args = Args::parse(args, &["process", "method"], &["wait", "force"])?;
+    let method = parse_method(&args)?;
+    let process = args.value("process").map(str::to_string);
+
+    let command = if command == "inject-file" {
+        let dll_path = PathBuf::from(args.target("DLL path")?);
+        let process = process.ok_or_else(|| CliError::usage("inject-file needs --process."))?;
+
+        if !dll_path.is_file() {
+            return Err(CliError::new(
+                ExitCode::NotFound,
+                format!("{} does not exist.", dll_path.display()),
+            ));
+        }
+        let dll_path = dll_path.canonicalize().unwrap_or(dll_path);
+        InjectCommand::File { dll_path, process }
+    } else {
+        InjectCommand::Hack {
+            query: args.target("hack")?.to_string(),
+            process,
+        }
+    };
+
+    Ok(InjectArgs {
+        command,
+        method,
+        wait: args.flag("wait"),
+        force: args.flag("force"),
+        source: InjectionSource::CommandLine,
+    })
+}
+
+impl InjectArgs {
+    /// Builds the request against `hacks`, which is only needed for catalog injections.
+    pub fn request(&self, hacks: &[Hack], pid: Option<u32>) -> Result<InjectionRequest, String> {
+        let mut request = match &self.command {
+            InjectCommand::Hack { query, process } => {
+                let hack =
+                    find_hack(hacks, query).ok_or_else(|| format!("No hack named {}.", query))?;
+                let mut request = InjectionRequest::from_hack(hack, pid);
+                if let Some(process) = process {
+                    request.process = process.clone();
+                }
+                request
+            }
+            InjectCommand::File { dll_path, process } => {
+                InjectionRequest::from_file(dll_path.clone(), process, pid)
+            }
+        };
+
+        request.source = self.source;
+        if let Some(method) = self.method {
+            request.method = method;
+        }
+        if self.force {
+            request.on_duplicate = OnDuplicate::Inject;
+        }
+        Ok(request)
+    }
+}
+
+fn inject(command: &str, args: &[String]) -> CliResult {
+    let args = parse_inject(command, args)?;
+    let config = Config::load();
+    let hacks = match args.command {
+        InjectCommand::Hack { .. } => fetch_catalog(&config)?,
+        InjectCommand::File { .. } => Vec::new(),
+    };
+    let request = args
+        .request(&hacks, None)
+        .map_err(|e| CliError::new(ExitCode::NotFound, e))?;
+
+    run_request(request, args.wait, &config)
+}
+
+fn run_request(request: InjectionRequest, wait: bool, config: &Config) -> CliResult {
+    let wait = wait.then(|| Duration::from_secs(config.launch_timeout_secs));
+    let record = inject_headless(request, config, http::from_env(), wait, |status| {
+        eprintln!("{}", status)
+    })
+    .map_err(|e| CliError::new(ExitCode::Failure, e))?;
+
+    finish_injection(record)
+}
+
+/// Persists the outcome like the GUI does and maps it to an exit code.
+fn finish_injection(record: InjectionRecord) -> CliResult {
+    History::default().append(record.clone());
+    match record.outcome {
+        InjectionOutcome::Success => Statistics::load().increment_inject_count(&record.hack_name),
+        InjectionOutcome::Crashed => Statistics::load().increment_crash_count(&record.hack_name),
+        InjectionOutcome::Failure => {}
+    }
+
+    injection_result(record)
+}
+
+fn injection_result(record: InjectionRecord) -> CliResult {
+    match record.outcome {
+        InjectionOutcome::Success => {
+            println!("Injected {} into {}.", record.hack_name, record.process);
+            Ok(())
+        }
+        InjectionOutcome::Crashed => Err(CliError::new(
+            ExitCode::Crashed,
+            record
+                .error
+                .unwrap_or_else(|| format!("{} crashed after injection.", record.process)),
+        )),
+        InjectionOutcome::Failure => {
+            let kind = record.error_kind.unwrap_or(ErrorKind::Unknown);
+            let code = match kind {
+                ErrorKind::ProcessExited => ExitCode::ProcessNotRunning,
+                _ => ExitCode::InjectionFailed,
+            };
+            let mut message = format!(
+                "[{}] {}",
+                kind.code(),
+                record
+                    .error
+                    .unwrap_or_else(|| "Injection failed.".to_string())
+            );
+            if let Some(hint) = kind.hint() {
+                message = format!("{}\nhint: {}", message, hint);
+            }
+            Err(CliError::new(code, message))
+        }
+    }
+}
+
+// MARK: Catalog authoring
+fn catalog(args: &[String]) -> CliResult {
+    let args = Args::parse(args, &["hacks-dir", "output"], &[])?;
+    let hacks_dir = |path: &Path| {
+        args.value("hacks-dir")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| catalog::default_hacks_dir(path))
+    };
+
+    match args
+        .positional
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        ["lint", rest @ ..] if rest.len() <= 1 => {
+            let path = Path::new(rest.first().copied().unwrap_or(DEFAULT_CATALOG));
+            let issues = catalog::lint_file(path, &hacks_dir(path))
+                .map_err(|e| CliError::new(ExitCode::NotFound, e))?;
+            report_lint(&issues)?;
+            eprintln!("{} looks good.", path.display());
+            Ok(())
+        }
+        ["build", entries_dir] => {
+            let entries_dir = Path::new(entries_dir);
+            let hacks_dir = hacks_dir(entries_dir);
+            let entries = catalog::build(entries_dir, &hacks_dir)
+                .map_err(|e| CliError::new(ExitCode::InvalidCatalog, e))?;
+
+            let value = serde_json::to_value(&entries).map_err(unexpected)?;
+            report_lint(&catalog::lint(&value, &hacks_dir))?;
+
+            let json = catalog::to_json(&entries).map_err(unexpected)?;
+            match args.value("output") {
+                Some(output) => {
+                    fs::write(output, json).map_err(|e| {
+                        CliError::new(
+                            ExitCode::Failure,
+                            format!("Failed to write {}: {}", output, e),
+                        )
+                    })?;
+                    eprintln!("Wrote {} entries to {}.", entries.len(), output);
+                }
+                None => print!("{}", json),
+            }
+            Ok(())
+        }
+        ["diff", old, new] => {
+            let load = |path: &str| {
+                catalog::load(Path::new(path))
+                    .map_err(|e| CliError::new(ExitCode::InvalidCatalog, e))
+            };
+            let changes = catalog::diff(&load(*old)?, &load(*new)?);
+
+            if changes.is_empty() {
+                println!("No changes.");
+            }
+            for change in &changes {
+                println!("{}", change);
+            }
+            if changes
+                .iter()
+                .any(|change| matches!(change, CatalogChange::DuplicateId { .. }))
+            {
+                return Err(CliError::new(
+                    ExitCode::InvalidCatalog,
+                    "Entries with duplicate ids were left out of the comparison.",
+                ));
+            }
+            Ok(())
+        }
+        _ => Err(CliError::usage(
+            "Use catalog lint [<file>], catalog build <entries dir> or catalog diff <old> <new>.",
+        )),
+    }
+}
+
+/// Prints every issue and fails if any of them is an error.
+fn report_lint(issues: &[LintIssue]) -> CliResult {
+    for issue in issues {
+        eprintln!("{}", issue);
+    }
+
+    let errors = issues
+        .iter()
+        .filter(|issue| issue.severity == Severity::Error)
+        .count();
+    if errors > 0 {
+        return Err(CliError::new(
+            ExitCode::InvalidCatalog,
+            format!("{} error(s), {} warning(s).", errors, issues.len() - errors),
+        ));
+    }
+
+    Ok(())
+}
+
+// MARK: Offline bundles
+fn bundle(args: &[String]) -> CliResult {
+    let args = Args::parse(args, &[], &[])?;
+
+    match args
+        .positional
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        ["export", file, queries @ ..] => {
+            let config = Config::load();
+            let hacks = fetch_catalog(&config)?;
+            let hacks = if queries.is_empty() {
+                hacks
+            } else {
+                queries
+                    .iter()
+                    .map(|query| find_catalog_hack(&hacks, query))
+                    .collect::<Result<Vec<_>, _>>()?
+            };
+
+            let summary = bundle::export(
+                &downloader(&config),
+                &Mutex::new(InjectorAssets::load()),
+                &hacks,
+                Path::new(file),
+                |status| eprintln!("{}", status),
+            )
+            .map_err(|e| CliError::new(ExitCode::Failure, e))?;
+            eprintln!(
+                "Bundled {} hacks and {} injectors.",
+                summary.hacks, summary.injectors
+            );
+            println!("{}", summary.location.display());
+            Ok(())
+        }
+        ["import", file] => {
+            refuse_while_running("bundle import")?;
+            let mut config = Config::load();
+            let injectors = Mutex::new(InjectorAssets::load());
+            let published = injector_assets::published_manifest(&injectors, &downloader(&config));
+            let summary =
+                bundle::import(Path::new(file), &mut config, &injectors, published.as_ref())
+                    .map_err(|e| CliError::new(ExitCode::InvalidCatalog, e))?;
+            config.save();
+            eprintln!(
+                "Imported {} hacks and {} injectors.",
+                summary.hacks, summary.injectors
+            );
+            for file_name in &summary.unverified_injectors {
+                eprintln!(
+                    "{} doesn't match a published hash and stays unverified until trusted in the settings.",
+                    file_name
+                );
+            }
+            println!("{}", summary.location.display());
+            Ok(())
+        }
+        _ => Err(CliError::usage(
+            "Use bundle export <file> [<hack>...] or bundle import <file>.",
+        )),
+    }
+}
+
+// MARK: Config
+fn config(args: &[String]) -> CliResult {
+    let args = Args::parse(args, &[], &[])?;
+    let mut config = Config::load();
+    let mut value = serde_json::to_value(&config).map_err(unexpected)?;
+
+    match args
+        .positional
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        ["get"] => print_json(&value),
+        ["get", key] => {
+            let entry = value.get(*key).ok_or_else(|| {
+                CliError::new(ExitCode::NotFound, format!("No config key {}.", key))
+            })?;
+            match entry {
+                Value::String(text) => println!("{}", text),
+                entry => println!("{}", entry),
+            }
+            Ok(())
+        }
+        ["set", key, raw] => {
+            refuse_while_running("config set")?;
+            let entry = value.get_mut(*key).ok_or_else(|| {
+                CliError::new(ExitCode::NotFound, format!("No config key {}.", key))
+            })?;
+            // Strings are taken verbatim, everything else is parsed as JSON (true, 30, ...).
+            *entry = if entry.is_string() {
+                Value::String(raw.to_string())
+            } else {
+                serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()))
+            };
+
+            config = serde_json::from_value(value)
+                .map_err(|e| CliError::usage(format!("Invalid value for {}: {}", key, e)))?;
+            config.validate().map_err(CliError::usage)?;
+            config.save();
+            Ok(())
+        }
+        _ => Err(CliError::usage(
+            "Use config get [<key>] or config set <key> <value>.",
+        )),
+    }
+}
+
+fn print_json(value: &impl serde::Serialize) -> CliResult {
+    let json = serde_json::to_string_pretty(value).map_err(unexpected)?;
+    println!("{}", json);
+    Ok(())
+}
+
+fn unexpected(e: impl fmt::Display) -> CliError {
+    CliError::new(ExitCode::Failure, e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use super::*;
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    /// An existing file to hand to the commands that take a path.
+    fn temp_dll(name: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("unknproject-cli-{}-{}.dll", name, process::id()));
+        fs::write(&path, b"MZ").unwrap();
+        path
+    }
+
+    fn record(outcome: InjectionOutcome, error_kind: Option<ErrorKind>) -> InjectionRecord {
+        let mut record = InjectionRecord::new(
+            Some(1),
+            "Hack",
+            InjectionSource::CommandLine,
+            "game.exe",
+            Some(42),
+            InjectionMethod::Standard,
+        );
+        record.outcome = outcome;
+        record.error_kind = error_kind;
+        record
+    }
+
+    #[test]
+    fn exit_codes_are_stable() {
+        for (code, number) in [
+            (ExitCode::Success, 0),
+            (ExitCode::Failure, 1),
+            (ExitCode::Usage, 2),
+            (ExitCode::NotFound, 3),
+            (ExitCode::Network, 4),
+            (ExitCode::ProcessNotRunning, 5),
+            (ExitCode::InjectionFailed, 6),
+            (ExitCode::Crashed, 7),
+            (ExitCode::InvalidCatalog, 8),
+        ] {
+            assert_eq!(code as i32, number);
+            assert!(
+                USAGE.contains(&format!("\n  {}  ", number)),
+                "{:?} is not in the usage",
+                code
+            );
+        }
+    }
+
+    #[test]
+    fn parses_options_and_positional_arguments() {
+        let args = Args::parse(
+            &strings(&["hack", "--process", "a.exe", "--wait", "--process", "b.exe"]),
+            &["process"],
+            &["wait"],
+        )
+        .unwrap();
+        assert_eq!(args.positional, vec!["hack"]);
+        assert_eq!(args.value("process"), Some("b.exe"));
+        assert!(args.flag("wait"));
+        assert!(!args.flag("force"));
+        assert_eq!(args.target("hack").unwrap(), "hack");
+
+        let error = Args::parse(&strings(&["--nope"]), &["process"], &["wait"])
+            .err()
+            .unwrap();
+        assert_eq!(error.code, ExitCode::Usage);
+        assert_eq!(error.message, "Unknown option --nope.");
+
+        let error = Args::parse(&strings(&["hack", "--process"]), &["process"], &[])
+            .err()
+            .unwrap();
+        assert_eq!(error.code, ExitCode::Usage);
+        assert_eq!(error.message, "--process needs a value.");
+
+        let none = Args::parse(&[], &[], &[]).unwrap();
+        assert_eq!(none.target("hack").unwrap_err().message, "Missing hack.");
+        let two = Args::parse(&strings(&["a", "b"]), &[], &[]).unwrap();
+        assert_eq!(
+            two.target("hack").unwrap_err().message,
+            "Expected a single hack."
+        );
+    }
+
+    #[test]
+    fn verbose_is_accepted_anywhere() {
+        let (verbose, args) =
+            strip_verbose(&strings(&["--verbose", "list", "--verbose", "--json"]));
+        assert!(verbose);
+        assert_eq!(args, vec!["list", "--json"]);
+
+        let (verbose, args) = strip_verbose(&strings(&["list"]));
+        assert!(!verbose);
+        assert_eq!(args, vec!["list"]);
+    }
+
+    #[test]
+    fn tells_window_arguments_from_commands() {
+        let dll = temp_dll("window");
+        let dll_arg = dll.to_string_lossy().to_string();
+
+        assert!(is_for_window(&[]));
+        assert!(is_for_window(&strings(&["--verbose"])));
+        assert!(is_for_window(&strings(&["inject", "Hack"])));
+        assert!(is_for_window(&strings(&[
+            "--verbose",
+            "inject-file",
+            "a.dll"
+        ])));
+        assert!(is_for_window(&strings(&["unknproject://select/1"])));
+        assert!(is_for_window(&[dll_arg.clone()]));
+        assert!(!is_for_window(&strings(&["list"])));
+        assert!(!is_for_window(&strings(&[
+            "unknproject://select/1",
+            "list"
+        ])));
+        assert!(!is_for_window(&strings(&["missing.dll"])));
+
+        assert!(matches!(
+            parse_window_command(&strings(&["--verbose"])),
+            Ok(WindowCommand::Focus)
+        ));
+        assert!(matches!(
+            parse_window_command(&strings(&["unknproject://select/1"])),
+            Ok(WindowCommand::OpenLink(DeepLink::Select(1)))
+        ));
+        assert!(matches!(
+            parse_window_command(&[dll_arg]),
+            Ok(WindowCommand::OpenFile(path)) if path == dll
+        ));
+        match parse_window_command(&strings(&["inject", "Hack", "--wait", "--verbose"])) {
+            Ok(WindowCommand::Inject(args)) => {
+                assert!(matches!(
+                    args.command,
+                    InjectCommand::Hack { ref query, process: None } if query == "Hack"
+                ));
+                assert!(args.wait);
+            }
+            _ => panic!("expected an injection"),
+        }
+        assert_eq!(
+            parse_window_command(&strings(&["list"])).err().unwrap(),
+            "Unsupported arguments: list"
+        );
+        assert_eq!(
+            parse_window_command(&strings(&["unknproject://nope"]))
+                .err()
+                .unwrap(),
+            "Unknown link action nope."
+        );
+
+        fs::remove_file(&dll).ok();
+    }
+
+    #[test]
+    fn parses_inject_commands() {
+        let dll = temp_dll("inject");
+        let dll_arg = dll.to_string_lossy().to_string();
+
+        let args = parse_inject(
+            "inject-file",
+            &strings(&[
+                dll_arg.as_str(),
+                "--process",
+                "game.exe",
+                "--method",
+                "manual-map",
+                "--force",
+            ]),
+        )
+        .unwrap();
+        assert!(matches!(
+            args.command,
+            InjectCommand::File { ref dll_path, ref process }
+                if *dll_path == dll.canonicalize().unwrap() && process == "game.exe"
+        ));
+        assert_eq!(args.method, Some(InjectionMethod::ManualMap));
+        assert!(args.force && !args.wait);
+        assert_eq!(args.source, InjectionSource::CommandLine);
+
+        let error = parse_inject("inject-file", &[dll_arg.clone()])
+            .err()
+            .unwrap();
+        assert_eq!(error.code, ExitCode::Usage);
+        assert_eq!(error.message, "inject-file needs --process.");
+
+        let error = parse_inject(
+            "inject-file",
+            &strings(&["missing.dll", "--process", "game.exe"]),
+        )
+        .err()
+        .unwrap();
+        assert_eq!(error.code, ExitCode::NotFound);
+
+        let error = parse_inject("inject", &strings(&["Hack", "--method", "magic"]))
+            .err()
+            .unwrap();
+        assert_eq!(error.code, ExitCode::Usage);
+
+        let error = parse_inject("inject", &strings(&["A", "B"])).err().unwrap();
+        assert_eq!(error.message, "Expected a single hack.");
+
+        let args = parse_inject("inject", &strings(&["Hack", "--process", "other.exe"])).unwrap();
+        assert!(matches!(
+            args.command,
+            InjectCommand::Hack { ref process, .. } if process.as_deref() == Some("other.exe")
+        ));
+        assert_eq!(args.method, None);
+
+        fs::remove_file(&dll).ok();
+    }
+
+    #[test]
+    fn injection_outcomes_map_to_exit_codes() {
+        assert!(injection_result(record(InjectionOutcome::Success, None)).is_ok());
+
+        let error = injection_result(record(InjectionOutcome::Crashed, None)).unwrap_err();
+        assert_eq!(error.code, ExitCode::Crashed);
+        assert_eq!(error.message, "game.exe crashed after injection.");
+
+        let error = injection_result(record(
+            InjectionOutcome::Failure,
+            Some(ErrorKind::ProcessExited),
+        ))
+        .unwrap_err();
+        assert_eq!(error.code, ExitCode::ProcessNotRunning);
+        assert!(error.message.starts_with("[UP-005] "), "{}", error);
+
+        let mut denied = record(InjectionOutcome::Failure, Some(ErrorKind::AccessDenied));
+        denied.error = Some("Access is denied.".to_string());
+        let error = injection_result(denied).unwrap_err();
+        assert_eq!(error.code, ExitCode::InjectionFailed);
+        assert_eq!(
+            error.message,
+            "[UP-001] Access is denied.\nhint: Restart the loader as administrator."
+        );
+
+        let error = injection_result(record(InjectionOutcome::Failure, None)).unwrap_err();
+        assert_eq!(error.code, ExitCode::InjectionFailed);
+        assert_eq!(error.message, "[UP-000] Injection failed.");
+    }
+}
@@ -1,399 +1,204 @@
 use std::{
-    path::PathBuf,
-    process::Command,
-    sync::{mpsc::Sender, Arc},
+    sync::{atomic::Ordering, Arc},
     thread,
     time::Duration,
 };
 
-use dll_syringe::{process::OwnedProcess, Syringe};
-use eframe::egui::{self};
-
-use crate::{utils::downloader::download_file, Hack, MyApp};
+use eframe::egui::{self, RichText};
+use egui_modal::Modal;
+pub use unknproject_core::inject::{
+    inject_headless, InjectionEvent, InjectionRequest, OnDuplicate,
+};
+use unknproject_core::{
+    inject::{
+        chain_requests, execute_request, execute_when_running, launch_and_inject, run_chain,
+        InProgressGuard, InjectionProgress,
+    },
+    Notify,
+};
 
-impl MyApp {
-    pub fn delete_injectors(&mut self, arch: &str) -> Result<(), String> {
-        let injectors = match arch {
-            "both" => vec!["unknproject.exe", "unknproject.exe"],
-            "x86" => vec!["unknproject.exe"],
-            "x64" => vec!["unknproject.exe"],
-            _ => return Err("Invalid architecture specified".to_string()),
-        };
+use crate::{
+    custom_widgets::Button,
+    utils::{chains::InjectionChain, history::InjectionSource},
+    Hack, MyApp,
+};
 
-        for injector in injectors {
-            let injector_path = dirs::config_dir()
-                .unwrap_or_else(|| PathBuf::from("."))
-                .join("unknprojects")
-                .join(injector);
+/// Repaints the window whenever a background job has news.
+pub fn repaint(ctx: &egui::Context) -> Notify {
+    let ctx = ctx.clone();
+    Arc::new(move || ctx.request_repaint())
+}
 
-            if injector_path.exists() {
-                if let Err(e) = std::fs::remove_file(&injector_path) {
-                    log::error!("Failed to delete {} injector: {}", injector, e);
-                    return Err(format!("Failed to delete {} injector: {}", injector, e));
-                }
-                log::info!("Deleted {}", injector);
-            }
-        }
-        Ok(())
+impl MyApp {
+    fn injection_progress(&self, ctx: egui::Context) -> InjectionProgress {
+        InjectionProgress::new(
+            &self.app.config,
+            Arc::clone(&self.app.http),
+            Arc::clone(&self.communication.status_message),
+            self.communication.message_sender.clone(),
+            repaint(&ctx),
+            Arc::clone(&self.app.injectors),
+            Arc::clone(&self.app.modules),
+        )
     }
 
-    pub fn inject(
-        &mut self,
-        dll_path: Option<std::path::PathBuf>,
-        target_process: &str,
-        message_sender: Sender<String>,
-    ) {
-        if let Some(process) = OwnedProcess::find_first_by_name(target_process) {
-            let syringe = Syringe::for_process(process);
-            let dll_path_clone = dll_path.clone().unwrap();
-            if let Err(e) = syringe.inject(dll_path.unwrap()) {
-                let _ = message_sender.send(format!("Failed to inject: {}", e));
-                log::error!("Failed to inject: {}", e);
-            } else {
-                let _ = message_sender.send(format!(
-                    "SUCCESS: {}",
-                    dll_path_clone.file_name().unwrap().to_string_lossy()
-                ));
-                log::info!("Injected into {}", target_process);
-            }
-        } else {
-            let _ = message_sender.send(format!("Process '{}' not found.", target_process));
-            log::error!("Process '{}' not found.", target_process);
+    // MARK: Injection
+    /// Starts `request`. If the same DLL already seems to be loaded in the target, the
+    /// injection thread stops and the user has to confirm first.
+    pub fn request_injection(&mut self, mut request: InjectionRequest, ctx: egui::Context) {
+        if self.communication.inject_in_progress.load(Ordering::SeqCst) {
+            self.toasts.info("An injection is already running.");
+            return;
         }
-    }
-
-    pub fn manual_map_inject(
-        &mut self,
-        dll_path: Option<std::path::PathBuf>,
-        target_process: &str,
-        message_sender: Sender<String>,
-    ) {
-        let dll_path_clone = dll_path.clone().unwrap();
-        let is_cs2 = target_process.eq_ignore_ascii_case("cs2.exe");
-        let injector_process = if is_cs2 {
-            "unknproject.exe"
-        } else {
-            "unknproject.exe"
-        };
-
-        log::debug!("Using {} injector", if is_cs2 { "x64" } else { "x86" });
-
-        let file_path = dirs::config_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("unknproject")
-            .join(injector_process);
 
-        if !file_path.exists() {
-            match download_file(injector_process, file_path.to_str().unwrap()) {
-                Ok(_) => {
-                    log::debug!("Downloaded manual map injector");
-                }
-                Err(e) => {
-                    let _ = message_sender
-                        .send(format!("Failed to download manual map injector: {}", e));
-                    log::error!("Failed to download manual map injector: {}", e);
-                    return;
-                }
-            }
-        }
-
-        let output = Command::new(file_path)
-            .arg(target_process)
-            .arg(dll_path.unwrap())
-            .output();
-
-        match output {
-            Ok(output) => {
-                if output.status.success() {
-                    let stdout_message = String::from_utf8_lossy(&output.stdout).to_string();
-                    log::info!("Manual map injector output (stdout): {}", stdout_message);
-                    let _ = message_sender.send(format!(
-                        "SUCCESS: {}",
-                        dll_path_clone.file_name().unwrap().to_string_lossy()
-                    ));
-                    log::info!("Injected into {}", target_process);
-                } else {
-                    let error_message = String::from_utf8_lossy(&output.stderr).to_string();
-                    let formatted_error_message = error_message
-                        .split_whitespace()
-                        .collect::<Vec<&str>>()
-                        .chunks(7)
-                        .map(|chunk| chunk.join(" "))
-                        .collect::<Vec<String>>()
-                        .join("\n");
-
-                    let _ = message_sender.send(formatted_error_message.clone());
-                    log::info!("Failed to execute injector: {}", formatted_error_message);
-                }
-            }
-            Err(e) => {
-                let _ = message_sender.send(format!("Failed to execute injector: {}", e));
-                log::error!("Failed to execute injector: {}", e);
-            }
+        if request.on_duplicate == OnDuplicate::Fail {
+            request.on_duplicate = OnDuplicate::Ask;
         }
+        self.spawn_injection(request, ctx);
     }
 
-    pub fn start_injection(
-        &mut self,
-        selected: Hack,
-        ctx: egui::Context,
-        message_sender: Sender<String>,
-    ) {
-        let inject_in_progress = Arc::clone(&self.communication.inject_in_progress);
-        let status_message = Arc::clone(&self.communication.status_message);
-        let selected_clone = selected.clone();
-        let ctx_clone = ctx.clone();
-        let skip_injects_clone = self.app.config.skip_injects_delay.clone();
-
-        {
-            let mut status = status_message.lock().unwrap();
-            *status = "Starting injection...".to_string();
-        }
+    pub fn render_duplicate_injection_dialog(&mut self, ctx: &egui::Context) {
+        let modal = Modal::new(ctx, "duplicate_injection_dialog");
 
-        inject_in_progress.store(true, std::sync::atomic::Ordering::SeqCst);
+        modal.show(|ui| {
+            let Some((request, reason)) = self.ui.pending_injection.clone() else {
+                return;
+            };
 
-        thread::spawn(move || {
-            ctx_clone.request_repaint();
-            if !skip_injects_clone {
-                thread::sleep(Duration::from_secs(1));
-            }
+            ui.heading("Already injected");
+            ui.add_space(5.0);
+            ui.label(reason);
+            ui.label("Injecting the same DLL twice usually crashes the game.");
+            ui.add_space(5.0);
 
-            if !selected_clone.file_path.exists() {
+            ui.horizontal(|ui| {
+                if ui
+                    .cbutton(RichText::new("Inject anyway").color(egui::Color32::LIGHT_RED))
+                    .clicked()
                 {
-                    let mut status = status_message.lock().unwrap();
-                    *status = "Downloading...".to_string();
+                    log::info!("Injecting {} again on user request", request.name);
+                    self.ui.pending_injection = None;
+                    self.spawn_injection(
+                        InjectionRequest {
+                            on_duplicate: OnDuplicate::Inject,
+                            ..request
+                        },
+                        ctx.clone(),
+                    );
+                    modal.close();
                 }
-                ctx_clone.request_repaint();
 
-                match selected_clone
-                    .download(selected_clone.file_path.to_string_lossy().to_string())
-                {
-                    Ok(_) => {
-                        let mut status = status_message.lock().unwrap();
-                        *status = "Downloaded.".to_string();
-                        log::debug!("Downloaded {}", selected_clone.name);
-                        ctx_clone.request_repaint();
-                    }
-                    Err(e) => {
-                        let mut status = status_message.lock().unwrap();
-                        *status = format!("{}", e);
-                        inject_in_progress.store(false, std::sync::atomic::Ordering::SeqCst);
-                        log::error!("Failed to download: {}", e);
-                        ctx_clone.request_repaint();
-                        let _ = message_sender.send(format!("Failed to inject: {}", e));
-                    }
+                if ui.cbutton("Cancel").clicked() {
+                    self.ui.pending_injection = None;
+                    *self.communication.status_message.lock().unwrap() =
+                        "Injection cancelled.".to_string();
+                    modal.close();
                 }
-            }
-
-            if !skip_injects_clone {
-                thread::sleep(Duration::from_secs(1));
-            }
+            });
+        });
 
-            {
-                let mut status = status_message.lock().unwrap();
-                *status = "Injecting...".to_string();
-            }
+        if self.ui.pending_injection.is_some() && !modal.is_open() {
+            modal.open();
+        }
+    }
 
-            ctx_clone.request_repaint();
+    pub fn spawn_injection(&mut self, request: InjectionRequest, ctx: egui::Context) {
+        let progress = self.injection_progress(ctx);
 
-            if !skip_injects_clone {
-                thread::sleep(Duration::from_secs(1));
-            }
+        progress.set_status("Starting injection...");
+        let guard = InProgressGuard::start(&self.communication.inject_in_progress, &progress);
 
-            if let Some(target_process) = OwnedProcess::find_first_by_name(&selected_clone.process)
-            {
-                let syringe = Syringe::for_process(target_process);
-                if let Err(e) = syringe.inject(selected_clone.file_path.clone()) {
-                    let mut status = status_message.lock().unwrap();
-                    *status = format!("Failed to inject: {}", e);
-                    ctx_clone.request_repaint();
-                    log::error!("Failed to inject: {}", e);
-                    let _ = message_sender.send(format!("Failed to inject: {}", e));
-                    Ok::<(), ()>(())
-                } else {
-                    let mut status = status_message.lock().unwrap();
-                    *status = "Injection successful.".to_string();
-                    inject_in_progress.store(false, std::sync::atomic::Ordering::SeqCst);
-                    ctx_clone.request_repaint();
-                    log::info!("Injected {}", selected_clone.name);
-                    let _ = message_sender
-                        .send(format!("SUCCESS: {}", selected_clone.name).to_string());
-                    Ok(())
-                }
-            } else {
-                let mut status = status_message.lock().unwrap();
-                *status = format!(
-                    "Failed to inject: Process '{}' not found.",
-                    selected_clone.process
-                );
-                inject_in_progress.store(false, std::sync::atomic::Ordering::SeqCst);
-                ctx_clone.request_repaint();
-                log::error!("Process '{}' not found.", selected_clone.process);
-                let _ =
-                    message_sender.send(format!("Process '{}' not found.", selected_clone.process));
-                Ok(())
-            }
+        thread::spawn(move || {
+            let _guard = guard;
+            execute_request(&request, &progress);
         });
     }
 
-    // MARK: Manual map injection
-    pub fn manual_map_injection(
-        &mut self,
-        selected: Hack,
-        ctx: egui::Context,
-        message_sender: Sender<String>,
-    ) {
-        let inject_in_progress = Arc::clone(&self.communication.inject_in_progress);
-        let status_message = Arc::clone(&self.communication.status_message);
-        let selected_clone = selected.clone();
-        let ctx_clone = ctx.clone();
-        let skip_inject_delay = self.app.config.skip_injects_delay;
-
-        {
-            let mut status = status_message.lock().unwrap();
-            *status = "Starting injection...".to_string();
-        }
+    /// Like `spawn_injection`, but first waits for the target to start, e.g. for an
+    /// `inject --wait` forwarded from the command line.
+    pub fn spawn_injection_when_running(&mut self, request: InjectionRequest, ctx: egui::Context) {
+        let timeout = Duration::from_secs(self.app.config.launch_timeout_secs);
+        let progress = self.injection_progress(ctx);
 
-        inject_in_progress.store(true, std::sync::atomic::Ordering::SeqCst);
+        progress.set_status(format!("Waiting for {}...", request.process));
+        let guard = InProgressGuard::start(&self.communication.inject_in_progress, &progress);
 
         thread::spawn(move || {
-            ctx_clone.request_repaint();
-            if !skip_inject_delay {
-                thread::sleep(Duration::from_secs(1));
-            }
-
-            if !selected_clone.file_path.exists() {
-                {
-                    let mut status = status_message.lock().unwrap();
-                    *status = format!("Downloading {}...", selected_clone.name);
-                }
-                ctx_clone.request_repaint();
-
-                match selected_clone
-                    .download(selected_clone.file_path.to_string_lossy().to_string())
-                {
-                    Ok(_) => {
-                        let mut status = status_message.lock().unwrap();
-                        *status = "Downloaded.".to_string();
-                        ctx_clone.request_repaint();
-                        log::debug!("Downloaded {}", selected_clone.name);
-                    }
-                    Err(e) => {
-                        let mut status = status_message.lock().unwrap();
-                        *status = format!("{}", e);
-                        inject_in_progress.store(false, std::sync::atomic::Ordering::SeqCst);
-                        ctx_clone.request_repaint();
-                        log::error!("Failed to download: {}", e);
-                        let _ = message_sender.send(format!("Failed to download: {}", e));
-                        return;
-                    }
-                }
-            }
+            let _guard = guard;
+            execute_when_running(request, timeout, &progress);
+        });
+    }
 
-            if !skip_inject_delay {
-                thread::sleep(Duration::from_secs(1));
-            }
+    // MARK: Launch & Inject
+    pub fn spawn_launch_and_inject(&mut self, hack: Hack, ctx: egui::Context) {
+        let Some(app_id) = hack.steam_app_id else {
+            self.toasts
+                .error(format!("{} has no Steam app id.", hack.game));
+            return;
+        };
+        let options = self
+            .app
+            .config
+            .launch_options
+            .get(&app_id)
+            .cloned()
+            .unwrap_or_default();
+        let timeout = Duration::from_secs(self.app.config.launch_timeout_secs);
+        let progress = self.injection_progress(ctx);
+
+        progress.set_status(format!("Launching {} through Steam...", hack.game));
+        let guard = InProgressGuard::start(&self.communication.inject_in_progress, &progress);
 
-            {
-                let mut status = status_message.lock().unwrap();
-                *status = "Injecting...".to_string();
-            }
-            ctx_clone.request_repaint();
+        thread::spawn(move || {
+            let _guard = guard;
+            launch_and_inject(&hack, app_id, &options, timeout, &progress);
+        });
+    }
 
-            if !skip_inject_delay {
-                thread::sleep(Duration::from_secs(1));
-            }
+    // MARK: Chains
+    pub fn spawn_chain(&mut self, chain: InjectionChain, ctx: egui::Context) {
+        if let Err(e) = self.run_chains(vec![chain], InjectionSource::Chain, None, ctx) {
+            self.toasts.error(e);
+        }
+    }
 
-            let is_cs2 = selected_clone.process.eq_ignore_ascii_case("cs2.exe");
-            let injector_process = if is_cs2 {
-                "unknproject.exe"
-            } else {
-                "unknproject.exe"
+    /// Runs every step of `chains`, one chain after another, on one thread. `pid` pins steps
+    /// targeting a chain's process to a specific instance instead of the selected one.
+    pub(crate) fn run_chains(
+        &mut self,
+        chains: Vec<InjectionChain>,
+        source: InjectionSource,
+        pid: Option<u32>,
+        ctx: egui::Context,
+    ) -> Result<(), String> {
+        let mut runs = Vec::new();
+        for chain in chains {
+            let pid_for = |process: &str| {
+                pid.filter(|_| process.eq_ignore_ascii_case(&chain.process))
+                    .or_else(|| self.app.selected_pids.get(process).copied())
             };
+            let requests = chain_requests(&chain, &self.app.hacks, source, pid_for)?;
+            runs.push((chain, requests));
+        }
+        let Some((first, _)) = runs.first() else {
+            return Err("Nothing to inject.".to_string());
+        };
 
-            log::debug!("Using {} injector", if is_cs2 { "x64" } else { "x86" });
-
-            let file_path = dirs::config_dir()
-                .unwrap_or_else(|| PathBuf::from("."))
-                .join("unknproject")
-                .join(injector_process);
-
-            if !file_path.exists() {
-                {
-                    let mut status = status_message.lock().unwrap();
-                    *status = "Downloading manual map injector...".to_string();
-                }
-
-                ctx_clone.request_repaint();
-
-                if !skip_inject_delay {
-                    thread::sleep(Duration::from_secs(2));
-                }
-
-                match download_file(&injector_process, file_path.to_str().unwrap()) {
-                    Ok(_) => {
-                        let mut status = status_message.lock().unwrap();
-                        *status = "Downloaded manual map injector.".to_string();
-                        log::debug!("Downloaded manual map injector");
-                        ctx_clone.request_repaint();
-                    }
-                    Err(e) => {
-                        let mut status = status_message.lock().unwrap();
-                        *status = format!("Failed to download manual map injector: {}", e);
-                        inject_in_progress.store(false, std::sync::atomic::Ordering::SeqCst);
-                        log::error!("Failed to download manual map injector: {}", e);
-                        ctx_clone.request_repaint();
-                        let _ = message_sender
-                            .send(format!("Failed to download manual map injector: {}", e));
-                        return;
-                    }
-                }
-            }
-
-            if !skip_inject_delay {
-                thread::sleep(Duration::from_secs(1));
-            }
-
-            {
-                let mut status = status_message.lock().unwrap();
-                *status = "Injecting with manual map injector...".to_string();
-                ctx_clone.request_repaint();
-            }
-
-            let dll_path = selected_clone.file_path;
+        let progress = self.injection_progress(ctx);
 
-            let output = Command::new(file_path).arg(dll_path).output();
+        progress.set_status(format!("Starting chain {}...", first.name));
+        let guard = InProgressGuard::start(&self.communication.inject_in_progress, &progress);
+        for (chain, requests) in &runs {
+            log::info!("Running chain {} ({} steps)", chain.name, requests.len());
+        }
 
-            match output {
-                Ok(output) => {
-                    if output.status.success() {
-                        let stdout_message = String::from_utf8_lossy(&output.stdout).to_string();
-                        log::info!("{}", stdout_message);
-                        let mut status = status_message.lock().unwrap();
-                        *status = "Injection successful.".to_string();
-                        log::info!("Injected {}", selected_clone.name);
-                        let _ = message_sender
-                            .send(format!("SUCCESS: {}", selected_clone.name).to_string());
-                    } else {
-                        let error_message = String::from_utf8_lossy(&output.stderr).to_string();
-                        let formatted_error_message =
-                            format!("Failed to inject: {}", error_message.replace("\n", ""));
-                        let _ = message_sender.send(formatted_error_message.clone());
-                        log::error!("{}", formatted_error_message);
-                        let mut status = status_message.lock().unwrap();
-                        *status = formatted_error_message;
-                    }
-                }
-                Err(e) => {
-                    let mut status = status_message.lock().unwrap();
-                    *status = format!("Failed to execute injector: {}", e);
-                    log::error!("Failed to execute injector: {}", e);
-                    let _ = message_sender.send(format!("Failed to execute injector: {}", e));
-                }
+        thread::spawn(move || {
+            let _guard = guard;
+            for (chain, requests) in &runs {
+                run_chain(chain, requests, &progress);
             }
-
-            inject_in_progress.store(false, std::sync::atomic::Ordering::SeqCst);
-            ctx_clone.request_repaint();
         });
+
+        Ok(())
     }
-}
\ No newline at end of file
+}
@@ -1,13 +1,14 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod api;
+mod cli;
 mod custom_widgets;
-mod hacks;
 mod inject;
 mod tabs;
 mod utils;
 
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, HashMap},
     env,
     sync::{
         mpsc::{self, Receiver, Sender, TryRecvError},
@@ -16,6 +17,8 @@ use std::{
     time::Duration,
 };
 
+use api::{ApiCall, ApiServer};
+use cli::{InjectArgs, InjectCommand, WindowCommand};
 use eframe::{
     egui::{self, RichText},
     App,
@@ -26,13 +29,41 @@ use egui::{
 };
 use egui_alignments::center_vertical;
 use egui_notify::Toasts;
-use hacks::{get_all_processes, get_hack_by_name, Hack};
+use inject::{repaint, InjectionEvent, InjectionRequest, OnDuplicate};
+#[cfg(windows)]
 use is_elevated::is_elevated;
-use tabs::top_panel::AppTab;
+use tabs::{
+    history::HistoryFilter,
+    home::DroppedInspection,
+    settings::{BundlePanel, InjectorPanel},
+    top_panel::AppTab,
+};
+use unknproject_core::hacks::{self, find_hack, get_all_processes, get_hack_by_name, Hack};
 use utils::{
-    config::Config, logger::MyLogger, rpc::Rpc, statistics::Statistics, steam::SteamAccount,
+    automation::{Automation, AutomationWatcher, RuleFired, RuleLog, ScheduledRun},
+    chains::Chains,
+    config::Config,
+    deeplink::{self, DeepLink},
+    downloader::Downloader,
+    history::History,
+    http::{self, Http},
+    injector_assets::InjectorAssets,
+    inspector::DllInspection,
+    instance::{Instance, InstanceLock},
+    logger::MyLogger,
+    modules::ModuleTracker,
+    processes::ProcessList,
+    rpc::Rpc,
+    statistics::Statistics,
+    steam::{LaunchOptionsCache, SteamAccount},
 };
 
+/// Only Windows tells us whether we run as administrator, and only there does it matter.
+#[cfg(not(windows))]
+fn is_elevated() -> bool {
+    false
+}
+
 pub(crate) fn load_icon() -> egui::IconData {
     let (icon_rgba, icon_width, icon_height) = {
         let icon = include_bytes!("../resources/img/icon.ico");
@@ -52,6 +83,39 @@ pub(crate) fn load_icon() -> egui::IconData {
 }
 
 fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let command = cli::parse_window_command(&args);
+    if !cli::is_for_window(&args) || command.is_err() {
+        std::process::exit(cli::run(&args));
+    }
+
+    let instance = match Instance::acquire() {
+        Ok(Instance::Primary(lock)) => Ok(lock),
+        Ok(Instance::Running(running)) => {
+            cli::attach_console();
+            let code = match running.forward(&args) {
+                Ok(()) => {
+                    if !args.is_empty() {
+                        println!("Handed over to the running loader.");
+                    }
+                    0
+                }
+                Err(e) => {
+                    eprintln!("error: {}", e);
+                    1
+                }
+            };
+            std::process::exit(code);
+        }
+        Err(e) => Err(e),
+    };
+
+    // Without a window to forward to, injections from the command line run headless.
+    if matches!(command, Ok(WindowCommand::Inject(_))) {
+        drop(instance);
+        std::process::exit(cli::run(&args));
+    }
+
     let native_options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_min_inner_size(egui::vec2(600.0, 200.0))
@@ -66,7 +130,7 @@ fn main() {
             "UnknProject (Administrator)"
         },
         native_options,
-        Box::new(|cc| Ok(Box::new(MyApp::new(cc)))),
+        Box::new(move |cc| Ok(Box::new(MyApp::new(cc, instance, args)))),
     )
     .unwrap();
 }
@@ -77,7 +141,15 @@ struct AppState {
     selected_hack: Option<Hack>,
     config: Config,
     statistics: Statistics,
+    history: History,
+    chains: Chains,
+    automation: Automation,
+    scheduled_runs: Vec<ScheduledRun>,
+    injectors: Arc<Mutex<InjectorAssets>>,
+    http: Http,
+    modules: Arc<Mutex<ModuleTracker>>,
     account: SteamAccount,
+    selected_pids: HashMap<String, u32>,
 }
 
 struct UIState {
@@ -85,14 +157,35 @@ struct UIState {
     search_query: String,
     main_menu_message: String,
     dropped_file: DroppedFile,
+    dropped_inspection: DroppedInspection,
     selected_process_dnd: String,
+    process_list: ProcessList,
+    inspection: Option<DllInspection>,
+    history_filter: HistoryFilter,
+    selected_chain: Option<usize>,
+    chain_step_hack: String,
+    chain_step_path: String,
+    injector_panel: InjectorPanel,
+    bundle_panel: BundlePanel,
+    launch_options_cache: LaunchOptionsCache,
+    pending_injection: Option<(InjectionRequest, String)>,
+    selected_rule: Option<usize>,
+    rule_logs: HashMap<u64, RuleLog>,
+    rule_action_hack: String,
+    rule_action_chain: String,
+    pending_link: Option<DeepLink>,
 }
 
 struct Communication {
     status_message: Arc<Mutex<String>>,
     inject_in_progress: Arc<std::sync::atomic::AtomicBool>,
-    message_sender: Sender<String>,
-    message_receiver: Receiver<String>,
+    message_sender: Sender<InjectionEvent>,
+    message_receiver: Receiver<InjectionEvent>,
+    rule_receiver: Receiver<RuleFired>,
+    automation_watcher: AutomationWatcher,
+    instance_receiver: Receiver<Vec<String>>,
+    api_sender: Sender<ApiCall>,
+    api_receiver: Receiver<ApiCall>,
 }
 
 struct MyApp {
@@ -105,6 +198,10 @@ struct MyApp {
     toasts: Toasts,
     parse_error: Option<String>,
     app_version: String,
+    /// Released when the app closes, letting the next launch become the primary instance.
+    _instance: Option<InstanceLock>,
+    /// Running while the control API is enabled in the settings.
+    api: Option<ApiServer>,
 }
 
 fn default_main_menu_message() -> String {
@@ -118,7 +215,11 @@ static LOGGER: OnceLock<MyLogger> = OnceLock::new();
 
 impl MyApp {
     // MARK: Init
-    fn new(_cc: &eframe::CreationContext<'_>) -> Self {
+    fn new(
+        _cc: &eframe::CreationContext<'_>,
+        instance: Result<InstanceLock, String>,
+        args: Vec<String>,
+    ) -> Self {
         let config = Config::load();
 
         let logger = MyLogger::init();
@@ -131,14 +232,45 @@ impl MyApp {
 
         statistics.increment_opened_count();
 
+        let history = History::load();
+
         let status_message = Arc::new(Mutex::new(String::new()));
         let inject_in_progress = Arc::new(std::sync::atomic::AtomicBool::new(false));
 
-        let hacks = hacks::Hack::fetch_hacks(&config.api_endpoint, config.lowercase_hacks)
+        let http = http::from_env();
+        let hacks = hacks::Hack::fetch_catalog(&Downloader::new(Arc::clone(&http), &config))
             .unwrap_or_default();
 
         let hacks_processes = get_all_processes(&hacks);
 
+        let automation = Automation::load();
+        let (rule_sender, rule_receiver) = mpsc::channel();
+        let automation_watcher = AutomationWatcher::spawn(
+            automation.active_rules(),
+            rule_sender,
+            repaint(&_cc.egui_ctx),
+        );
+
+        let (instance_sender, instance_receiver) = mpsc::channel();
+        let instance = match instance {
+            Ok(lock) => {
+                lock.spawn_listener(instance_sender.clone(), repaint(&_cc.egui_ctx));
+                Some(lock)
+            }
+            Err(e) => {
+                log::warn!("Running without the single-instance lock: {}", e);
+                None
+            }
+        };
+        if let Err(e) = deeplink::register() {
+            log::warn!("Failed to register {}:// links: {}", deeplink::SCHEME, e);
+        }
+
+        // Startup arguments, like a DLL dropped onto the exe, take the same path as forwarded ones.
+        if !args.is_empty() {
+            instance_sender.send(args).ok();
+        }
+
         let account = match SteamAccount::new() {
             Ok(account) => account,
             Err(_) => SteamAccount::default(),
@@ -156,27 +288,58 @@ impl MyApp {
             selected_hack = get_hack_by_name(&hacks, &config.selected_hack);
         }
 
-        Self {
+        let (api_sender, api_receiver) = mpsc::channel();
+
+        let mut app = Self {
             app: AppState {
                 hacks,
                 hacks_processes,
                 selected_hack,
                 config,
                 statistics,
+                history,
+                chains: Chains::load(),
+                automation,
+                scheduled_runs: Vec::new(),
+                injectors: Arc::new(Mutex::new(InjectorAssets::load())),
+                http,
+                modules: Arc::new(Mutex::new(ModuleTracker::default())),
                 account,
+                selected_pids: HashMap::new(),
             },
             ui: UIState {
                 tab: AppTab::default(),
                 search_query: String::new(),
                 main_menu_message: default_main_menu_message(),
                 dropped_file: DroppedFile::default(),
+                dropped_inspection: DroppedInspection::default(),
                 selected_process_dnd: String::new(),
+                process_list: ProcessList::default(),
+                inspection: None,
+                history_filter: HistoryFilter::default(),
+                selected_chain: None,
+                chain_step_hack: String::new(),
+                chain_step_path: String::new(),
+                injector_panel: InjectorPanel::default(),
+                bundle_panel: BundlePanel::default(),
+                launch_options_cache: LaunchOptionsCache::default(),
+                pending_injection: None,
+                selected_rule: None,
+                rule_logs: HashMap::new(),
+                rule_action_hack: String::new(),
+                rule_action_chain: String::new(),
+                pending_link: None,
             },
             communication: Communication {
                 status_message,
                 inject_in_progress,
                 message_sender,
                 message_receiver,
+                rule_receiver,
+                automation_watcher,
+                instance_receiver,
+                api_sender,
+                api_receiver,
             },
             rpc,
             log_buffer,
@@ -184,29 +347,110 @@ impl MyApp {
             toasts: Toasts::default(),
             parse_error: None,
             app_version: env!("CARGO_PKG_VERSION").to_string(),
-        }
+            _instance: instance,
+            api: None,
+        };
+        app.apply_api_settings(&_cc.egui_ctx);
+
+        app
     }
 
     fn handle_received_messages(&mut self) {
-        match self.communication.message_receiver.try_recv() {
-            Ok(message) => {
-                if message.starts_with("SUCCESS: ") {
-                    self.handle_successful_injection_message(message);
-                } else {
+        loop {
+            let event = match self.communication.message_receiver.try_recv() {
+                Ok(event) => event,
+                Err(TryRecvError::Empty) => break,
+                Err(e) => {
+                    log::error!("Error receiving from channel: {:?}", e);
+                    break;
+                }
+            };
+            if let Some(api) = &self.api {
+                api.publish(&event);
+            }
+
+            match event {
+                InjectionEvent::Succeeded(name) => {
+                    self.handle_successful_injection_message(name);
+                    self.update_rpc_status_selecting();
+                }
+                InjectionEvent::Failed(message) => {
                     self.handle_error_message(message);
+                    self.update_rpc_status_selecting();
+                }
+                InjectionEvent::Crashed(name, message) => {
+                    self.handle_crash_message(name, message);
+                    self.update_rpc_status_selecting();
                 }
+                InjectionEvent::Recorded(record) => self.app.history.append(record),
+                InjectionEvent::ModuleLoaded(module) => {
+                    self.app.modules.lock().unwrap().track(module)
+                }
+                InjectionEvent::Ejected(module, result) => match result {
+                    Ok(()) => {
+                        self.toasts.success(format!("Ejected {}.", module.name));
+                    }
+                    Err(e) => {
+                        log::error!("{}", e);
+                        self.toasts.error(e);
+                    }
+                },
+                InjectionEvent::Duplicate(request, reason) => {
+                    self.ui.pending_injection = Some((*request, reason));
+                }
+            }
+        }
+    }
 
-                self.update_rpc_status_selecting();
+    /// Runs what a later launch of the loader (or this one's own arguments) asked for.
+    fn handle_window_commands(&mut self, ctx: &egui::Context) {
+        while let Ok(args) = self.communication.instance_receiver.try_recv() {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(false));
+            ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+
+            match cli::parse_window_command(&args) {
+                Ok(WindowCommand::Focus) => {}
+                Ok(WindowCommand::OpenFile(path)) => self.open_dll(path, ctx),
+                Ok(WindowCommand::OpenLink(link)) => self.open_link(link),
+                Ok(WindowCommand::Inject(inject)) => {
+                    if let Err(e) = self.start_injection(inject, ctx) {
+                        self.toasts.error(e);
+                    }
+                }
+                Err(e) => {
+                    log::warn!("{}", e);
+                    self.toasts.error(e);
+                }
             }
-            Err(TryRecvError::Empty) => {}
-            Err(e) => {
-                log::error!("Error receiving from channel: {:?}", e);
+        }
+    }
+
+    /// Starts an injection another launch or the control API asked for, targeting the
+    /// process picked in the UI if there is one.
+    fn start_injection(&mut self, inject: InjectArgs, ctx: &egui::Context) -> Result<(), String> {
+        let pid = match &inject.command {
+            InjectCommand::Hack { query, .. } => find_hack(&self.app.hacks, query)
+                .and_then(|hack| self.app.selected_pids.get(&hack.process).copied()),
+            InjectCommand::File { process, .. } => self.app.selected_pids.get(process).copied(),
+        };
+
+        let mut request = inject.request(&self.app.hacks, pid)?;
+        if let Some(hack) = &request.hack {
+            self.app.selected_hack = Some(hack.clone());
+        }
+        if inject.wait {
+            if request.on_duplicate == OnDuplicate::Fail {
+                request.on_duplicate = OnDuplicate::Ask;
             }
+            self.spawn_injection_when_running(request, ctx.clone());
+        } else {
+            self.request_injection(request, ctx.clone());
         }
+
+        Ok(())
     }
 
-    fn handle_successful_injection_message(&mut self, message: String) {
-        let name = message.trim_start_matches("SUCCESS: ").to_string();
+    fn handle_successful_injection_message(&mut self, name: String) {
         self.toasts
             .success(format!("Successfully injected {}", name))
             .duration(Some(Duration::from_secs(4)));
@@ -214,6 +458,14 @@ impl MyApp {
         self.app.statistics.increment_inject_count(&name);
     }
 
+    fn handle_crash_message(&mut self, name: String, message: String) {
+        self.toasts
+            .error(message)
+            .duration(Some(Duration::from_secs(6)));
+
+        self.app.statistics.increment_crash_count(&name);
+    }
+
     fn handle_error_message(&mut self, message: String) {
         self.toasts
             .error(message)
@@ -314,6 +566,8 @@ impl MyApp {
                             self.render_game_hacks(ui, game_name, versions, ctx);
                             ui.add_space(5.0);
                         }
+
+                        self.render_chain_list(ui);
                     });
             });
     }
@@ -388,9 +642,19 @@ impl MyApp {
         });
     }
 
+    /// Built on demand so it always uses the CDN endpoints currently in the settings.
+    pub fn downloader(&self) -> Downloader {
+        Downloader::new(Arc::clone(&self.app.http), &self.app.config)
+    }
+
+    fn favorites_color(&self) -> Color32 {
+        let [r, g, b, a] = self.app.config.favorites_color;
+        Color32::from_rgba_premultiplied(r, g, b, a)
+    }
+
     fn create_hack_label(&self, hack: &Hack) -> RichText {
         if self.app.config.favorites.contains(&hack.name) {
-            RichText::new(&hack.name).color(self.app.config.favorites_color)
+            RichText::new(&hack.name).color(self.favorites_color())
         } else {
             RichText::new(&hack.name)
         }
@@ -445,13 +709,17 @@ impl MyApp {
                 .get(&hack.name)
                 .unwrap_or(&0);
             if count != &0 {
-                ui.label(format!("{}x", count));
+                let label = ui.label(format!("{}x", count));
+                if let Some(crashes) = self.app.statistics.crash_counts.get(&hack.name) {
+                    label.on_hover_text(format!("{} crashed after injection", crashes));
+                }
             }
         }
     }
 
     fn select_hack(&mut self, hack_clone: &Hack) {
         self.app.selected_hack = Some(hack_clone.clone());
+        self.ui.selected_chain = None;
         self.app.config.selected_hack = hack_clone.name.clone();
         self.app.config.save();
 
@@ -464,7 +732,9 @@ impl MyApp {
 
     fn render_central_panel(&mut self, ctx: &egui::Context, theme_color: egui::Color32) {
         egui::CentralPanel::default().show(ctx, |ui| {
-            if let Some(selected) = self.app.selected_hack.clone() {
+            if let Some(index) = self.ui.selected_chain {
+                self.render_chain_details(ui, ctx, index, theme_color);
+            } else if let Some(selected) = self.app.selected_hack.clone() {
                 self.display_hack_details(ui, ctx, &selected, theme_color);
             } else {
                 center_vertical(ui, |ui| {
@@ -476,7 +746,6 @@ impl MyApp {
 
     // MARK: Home tab
     fn render_home_tab(&mut self, ctx: &egui::Context, theme_color: egui::Color32) {
-        self.handle_received_messages();
         self.handle_key_events(ctx);
 
         let hacks_by_game = self.group_hacks_by_game();
@@ -523,12 +792,22 @@ impl App for MyApp {
 
         self.render_top_panel(ctx);
 
+        self.handle_received_messages();
+        self.handle_window_commands(ctx);
+        self.handle_api_calls(ctx);
+        self.handle_automation(ctx);
+        self.app.modules.lock().unwrap().prune_exited();
         self.handle_dnd(ctx);
+        self.render_inspector_window(ctx);
+        self.render_duplicate_injection_dialog(ctx);
+        self.render_deep_link_dialog(ctx);
 
         match self.ui.tab {
             AppTab::Home => self.render_home_tab(ctx, theme_color),
             AppTab::Settings => self.render_settings_tab(ctx),
             AppTab::About => self.render_about_tab(ctx),
+            AppTab::History => self.render_history_tab(ctx),
+            AppTab::Automation => self.render_automation_tab(ctx),
             AppTab::Logs => self.render_logs_tab(ctx),
             AppTab::Debug => self.render_debug_tab(ctx),
         }
@@ -0,0 +1,407 @@
+use std::{
+    sync::atomic::Ordering,
+    time::{Duration, Instant},
+};
+
+use egui::{CursorIcon::PointingHand as Clickable, RichText};
+
+use crate::{
+    custom_widgets::{Button, CheckBox, SelectableLabel},
+    utils::{
+        automation::{AutomationRule, RuleAction, RuleTrigger, ScheduledRun},
+        chains::{ChainStep, ChainTarget, InjectionChain},
+        history::InjectionSource,
+    },
+    MyApp,
+};
+
+impl MyApp {
+    // MARK: Engine
+    /// Picks up fired rules from the watcher and starts the ones whose delay has passed.
+    pub fn handle_automation(&mut self, ctx: &egui::Context) {
+        while let Ok(fired) = self.communication.rule_receiver.try_recv() {
+            let Some(rule) = self
+                .app
+                .automation
+                .rules
+                .iter()
+                .find(|rule| rule.id == fired.rule_id)
+                .cloned()
+            else {
+                continue;
+            };
+
+            self.log_rule(
+                rule.id,
+                format!(
+                    "Triggered: {} (pid {}), injecting in {} ms",
+                    rule.trigger, fired.pid, rule.delay_ms
+                ),
+            );
+            self.app.scheduled_runs.push(ScheduledRun {
+                rule_id: rule.id,
+                pid: fired.pid,
+                due: Instant::now() + Duration::from_millis(rule.delay_ms),
+            });
+        }
+
+        if self.app.scheduled_runs.is_empty() {
+            return;
+        }
+
+        let now = Instant::now();
+        let in_progress = self.communication.inject_in_progress.load(Ordering::SeqCst);
+
+        if !in_progress {
+            if let Some(index) = self
+                .app
+                .scheduled_runs
+                .iter()
+                .position(|run| run.due <= now)
+            {
+                let run = self.app.scheduled_runs.remove(index);
+                self.run_rule(run, ctx);
+            }
+        }
+
+        let next_due = self
+            .app
+            .scheduled_runs
+            .iter()
+            .map(|run| run.due.saturating_duration_since(now))
+            .min()
+            .unwrap_or_default();
+        ctx.request_repaint_after(next_due.max(Duration::from_millis(250)));
+    }
+
+    fn run_rule(&mut self, run: ScheduledRun, ctx: &egui::Context) {
+        let Some(rule) = self
+            .app
+            .automation
+            .rules
+            .iter()
+            .find(|rule| rule.id == run.rule_id)
+            .cloned()
+        else {
+            return;
+        };
+
+        if !rule.enabled || !self.app.automation.enabled {
+            self.log_rule(rule.id, "Skipped: rule was disabled");
+            return;
+        }
+
+        // Consecutive hacks form a chain of the rule's own, included chains run as they are.
+        let mut chains: Vec<InjectionChain> = Vec::new();
+        let mut hacks_open = false;
+        for action in &rule.actions {
+            match action {
+                RuleAction::Hack { name } => {
+                    if !hacks_open {
+                        let mut chain = InjectionChain::new(&rule.name, rule.trigger.process());
+                        chain.stop_on_failure = false;
+                        chains.push(chain);
+                        hacks_open = true;
+                    }
+                    if let Some(chain) = chains.last_mut() {
+                        chain.steps.push(ChainStep {
+                            target: ChainTarget::Catalog {
+                                id: None,
+                                source: None,
+                                name: name.clone(),
+                            },
+                            delay_ms: 0,
+                        });
+                    }
+                }
+                RuleAction::Chain { name } => {
+                    hacks_open = false;
+                    match self.app.chains.chains.iter().find(|c| &c.name == name) {
+                        Some(included) => chains.push(included.clone()),
+                        None => self.log_rule(rule.id, format!("Chain {} no longer exists", name)),
+                    }
+                }
+            }
+        }
+
+        let steps: usize = chains.iter().map(|chain| chain.steps.len()).sum();
+        match self.run_chains(
+            chains,
+            InjectionSource::Automation,
+            Some(run.pid),
+            ctx.clone(),
+        ) {
+            Ok(()) => self.log_rule(
+                rule.id,
+                format!("Injecting {} step(s) into pid {}", steps, run.pid),
+            ),
+            Err(e) => {
+                log::error!("Automation rule {} failed: {}", rule.name, e);
+                self.log_rule(rule.id, format!("Failed: {}", e));
+            }
+        }
+    }
+
+    fn log_rule(&mut self, rule_id: u64, message: impl Into<String>) {
+        self.ui.rule_logs.entry(rule_id).or_default().push(message);
+    }
+
+    fn save_automation(&mut self) {
+        self.app.automation.save();
+        self.communication
+            .automation_watcher
+            .update(self.app.automation.active_rules());
+    }
+
+    // MARK: Tab
+    pub fn render_automation_tab(&mut self, ctx: &egui::Context) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.heading("Automation");
+            ui.separator();
+
+            if ui
+                .ccheckbox(
+                    &mut self.app.automation.enabled,
+                    "Inject automatically when a rule's trigger fires",
+                )
+                .changed()
+            {
+                self.save_automation();
+            }
+
+            ui.add_space(5.0);
+
+            ui.horizontal_wrapped(|ui| {
+                for index in 0..self.app.automation.rules.len() {
+                    let rule = &mut self.app.automation.rules[index];
+                    let toggled = ui.ccheckbox(&mut rule.enabled, "").changed();
+                    if ui
+                        .cselectable_label(self.ui.selected_rule == Some(index), &rule.name)
+                        .on_hover_text(rule.trigger.to_string())
+                        .clicked()
+                    {
+                        self.ui.selected_rule = Some(index);
+                    }
+                    if toggled {
+                        self.save_automation();
+                    }
+                }
+
+                if ui.cbutton("+ New rule").clicked() {
+                    let process = self
+                        .app
+                        .hacks_processes
+                        .first()
+                        .cloned()
+                        .unwrap_or_default();
+                    let name = format!("Rule {}", self.app.automation.rules.len() + 1);
+                    self.app
+                        .automation
+                        .rules
+                        .push(AutomationRule::new(&name, &process));
+                    self.ui.selected_rule = Some(self.app.automation.rules.len() - 1);
+                    self.save_automation();
+                }
+            });
+
+            ui.separator();
+
+            if let Some(index) = self.ui.selected_rule {
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false; 2])
+                    .show(ui, |ui| {
+                        self.render_rule_editor(ui, index);
+                    });
+            } else {
+                ui.label("Select a rule or create a new one.");
+            }
+        });
+    }
+
+    fn render_rule_editor(&mut self, ui: &mut egui::Ui, index: usize) {
+        let Some(mut rule) = self.app.automation.rules.get(index).cloned() else {
+            self.ui.selected_rule = None;
+            return;
+        };
+        let mut changed = false;
+
+        ui.horizontal(|ui| {
+            ui.label("Name:");
+            changed |= ui.text_edit_singleline(&mut rule.name).changed();
+        });
+
+        // MARK: Trigger
+        ui.horizontal(|ui| {
+            ui.label("When");
+            let mut process = rule.trigger.process().to_string();
+            egui::ComboBox::from_id_salt("rule_process")
+                .selected_text(process.clone())
+                .show_ui(ui, |ui| {
+                    for candidate in &self.app.hacks_processes {
+                        ui.selectable_value(&mut process, candidate.clone(), candidate)
+                            .on_hover_cursor(Clickable);
+                    }
+                })
+                .response
+                .on_hover_cursor(Clickable);
+
+            let mut module_trigger = matches!(rule.trigger, RuleTrigger::ModuleLoaded { .. });
+            egui::ComboBox::from_id_salt("rule_trigger")
+                .selected_text(if module_trigger { "loads" } else { "starts" })
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut module_trigger, false, "starts")
+                        .on_hover_cursor(Clickable);
+                    ui.selectable_value(&mut module_trigger, true, "loads")
+                        .on_hover_cursor(Clickable);
+                })
+                .response
+                .on_hover_cursor(Clickable);
+
+            let mut module = match &rule.trigger {
+                RuleTrigger::ModuleLoaded { module, .. } => module.clone(),
+                RuleTrigger::ProcessStarted { .. } => String::new(),
+            };
+            if module_trigger {
+                ui.add(
+                    egui::TextEdit::singleline(&mut module)
+                        .hint_text("client.dll")
+                        .desired_width(120.0),
+                );
+            }
+
+            let trigger = if module_trigger {
+                RuleTrigger::ModuleLoaded { process, module }
+            } else {
+                RuleTrigger::ProcessStarted { process }
+            };
+            if trigger != rule.trigger {
+                rule.trigger = trigger;
+                changed = true;
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("then wait");
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut rule.delay_ms)
+                        .range(0..=600_000)
+                        .suffix(" ms"),
+                )
+                .changed();
+            ui.label("and inject:");
+        });
+
+        // MARK: Actions
+        let mut remove = None;
+        for (action_index, action) in rule.actions.iter().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(format!("{}. {}", action_index + 1, action));
+                if ui.button_with_tooltip("🗑", "Remove").clicked() {
+                    remove = Some(action_index);
+                }
+            });
+        }
+        if let Some(action_index) = remove {
+            rule.actions.remove(action_index);
+            changed = true;
+        }
+
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt("rule_new_hack")
+                .selected_text(if self.ui.rule_action_hack.is_empty() {
+                    "Select a hack".to_string()
+                } else {
+                    self.ui.rule_action_hack.clone()
+                })
+                .show_ui(ui, |ui| {
+                    for hack in &self.app.hacks {
+                        ui.selectable_value(
+                            &mut self.ui.rule_action_hack,
+                            hack.name.clone(),
+                            &hack.name,
+                        )
+                        .on_hover_cursor(Clickable);
+                    }
+                })
+                .response
+                .on_hover_cursor(Clickable);
+
+            if ui.cbutton("Add hack").clicked() && !self.ui.rule_action_hack.is_empty() {
+                rule.actions.push(RuleAction::Hack {
+                    name: self.ui.rule_action_hack.clone(),
+                });
+                changed = true;
+            }
+        });
+
+        if !self.app.chains.chains.is_empty() {
+            ui.horizontal(|ui| {
+                egui::ComboBox::from_id_salt("rule_new_chain")
+                    .selected_text(if self.ui.rule_action_chain.is_empty() {
+                        "Select a chain".to_string()
+                    } else {
+                        self.ui.rule_action_chain.clone()
+                    })
+                    .show_ui(ui, |ui| {
+                        for chain in &self.app.chains.chains {
+                            ui.selectable_value(
+                                &mut self.ui.rule_action_chain,
+                                chain.name.clone(),
+                                &chain.name,
+                            )
+                            .on_hover_cursor(Clickable);
+                        }
+                    })
+                    .response
+                    .on_hover_cursor(Clickable);
+
+                if ui.cbutton("Add chain").clicked() && !self.ui.rule_action_chain.is_empty() {
+                    rule.actions.push(RuleAction::Chain {
+                        name: self.ui.rule_action_chain.clone(),
+                    });
+                    changed = true;
+                }
+            });
+        }
+
+        ui.add_space(5.0);
+
+        let mut delete = false;
+        if ui
+            .cbutton(RichText::new("Delete rule").color(egui::Color32::LIGHT_RED))
+            .clicked()
+        {
+            delete = true;
+        }
+
+        // MARK: Log
+        ui.separator();
+        ui.label("Log:");
+        match self.ui.rule_logs.get(&rule.id) {
+            Some(log) => {
+                for entry in log.entries().rev() {
+                    ui.label(format!(
+                        "{} {}",
+                        entry.timestamp.format("%H:%M:%S"),
+                        entry.message
+                    ));
+                }
+            }
+            None => {
+                ui.label(RichText::new("Nothing happened yet.").color(egui::Color32::GRAY));
+            }
+        }
+
+        if delete {
+            self.app.automation.rules.remove(index);
+            self.ui.rule_logs.remove(&rule.id);
+            self.ui.selected_rule = None;
+            self.toasts.success(format!("Deleted {}.", rule.name));
+            self.save_automation();
+        } else if changed {
+            self.app.automation.rules[index] = rule;
+            self.save_automation();
+        }
+    }
+}
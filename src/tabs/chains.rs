@@ -0,0 +1,254 @@
+use std::path::PathBuf;
+
+use egui::{CursorIcon::PointingHand as Clickable, RichText};
+
+use crate::{
+    custom_widgets::{Button, CheckBox},
+    utils::chains::{ChainStep, ChainTarget, InjectionChain},
+    MyApp,
+};
+
+enum StepAction {
+    MoveUp,
+    MoveDown,
+    Remove,
+}
+
+impl MyApp {
+    // MARK: Chain list
+    pub fn render_chain_list(&mut self, ui: &mut egui::Ui) {
+        ui.group(|ui| {
+            ui.with_layout(
+                egui::Layout::top_down_justified(egui::Align::Center),
+                |ui| {
+                    ui.heading("Chains");
+                },
+            );
+            ui.separator();
+
+            for (index, chain) in self.app.chains.chains.clone().iter().enumerate() {
+                if ui
+                    .selectable_label(self.ui.selected_chain == Some(index), &chain.name)
+                    .on_hover_text(format!("{} steps", chain.steps.len()))
+                    .on_hover_cursor(Clickable)
+                    .clicked()
+                {
+                    self.select_chain(index);
+                }
+            }
+
+            if ui.cbutton("+ New chain").clicked() {
+                let process = self
+                    .app
+                    .hacks_processes
+                    .first()
+                    .cloned()
+                    .unwrap_or_default();
+                let name = format!("Chain {}", self.app.chains.chains.len() + 1);
+                self.app
+                    .chains
+                    .chains
+                    .push(InjectionChain::new(&name, &process));
+                self.app.chains.save();
+                self.select_chain(self.app.chains.chains.len() - 1);
+            }
+        });
+    }
+
+    fn select_chain(&mut self, index: usize) {
+        self.app.selected_hack = None;
+        self.ui.selected_chain = Some(index);
+        *self.communication.status_message.lock().unwrap() = String::new();
+    }
+
+    // MARK: Chain details
+    pub fn render_chain_details(
+        &mut self,
+        ui: &mut egui::Ui,
+        ctx: &egui::Context,
+        index: usize,
+        theme_color: egui::Color32,
+    ) {
+        let Some(mut chain) = self.app.chains.chains.get(index).cloned() else {
+            self.ui.selected_chain = None;
+            return;
+        };
+        let mut changed = false;
+
+        ui.horizontal(|ui| {
+            ui.label("Name:");
+            changed |= ui.text_edit_singleline(&mut chain.name).changed();
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Process for local DLLs:");
+            egui::ComboBox::from_id_salt("chain_process")
+                .selected_text(chain.process.clone())
+                .show_ui(ui, |ui| {
+                    for process in &self.app.hacks_processes {
+                        changed |= ui
+                            .selectable_value(&mut chain.process, process.clone(), process)
+                            .on_hover_cursor(Clickable)
+                            .changed();
+                    }
+                })
+                .response
+                .on_hover_cursor(Clickable);
+        });
+
+        changed |= ui
+            .ccheckbox(&mut chain.stop_on_failure, "Stop on failure")
+            .changed();
+
+        ui.separator();
+        ui.label("Steps:");
+
+        let mut action = None;
+        for (step_index, step) in chain.steps.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(format!("{}. {}", step_index + 1, step.target));
+                ui.label("delay");
+                changed |= ui
+                    .add(
+                        egui::DragValue::new(&mut step.delay_ms)
+                            .range(0..=60_000)
+                            .suffix(" ms"),
+                    )
+                    .changed();
+
+                if ui.button_with_tooltip("⬆", "Move up").clicked() {
+                    action = Some((step_index, StepAction::MoveUp));
+                }
+                if ui.button_with_tooltip("⬇", "Move down").clicked() {
+                    action = Some((step_index, StepAction::MoveDown));
+                }
+                if ui.button_with_tooltip("🗑", "Remove step").clicked() {
+                    action = Some((step_index, StepAction::Remove));
+                }
+            });
+        }
+
+        if let Some((step_index, action)) = action {
+            match action {
+                StepAction::MoveUp if step_index > 0 => {
+                    chain.steps.swap(step_index, step_index - 1)
+                }
+                StepAction::MoveDown if step_index + 1 < chain.steps.len() => {
+                    chain.steps.swap(step_index, step_index + 1)
+                }
+                StepAction::Remove => {
+                    chain.steps.remove(step_index);
+                }
+                _ => {}
+            }
+            changed = true;
+        }
+
+        ui.add_space(5.0);
+
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt("chain_new_step")
+                .selected_text(if self.ui.chain_step_hack.is_empty() {
+                    "Select a hack".to_string()
+                } else {
+                    self.ui.chain_step_hack.clone()
+                })
+                .show_ui(ui, |ui| {
+                    for hack in &self.app.hacks {
+                        ui.selectable_value(
+                            &mut self.ui.chain_step_hack,
+                            hack.name.clone(),
+                            &hack.name,
+                        )
+                        .on_hover_cursor(Clickable);
+                    }
+                })
+                .response
+                .on_hover_cursor(Clickable);
+
+            if ui.cbutton("Add hack").clicked() && !self.ui.chain_step_hack.is_empty() {
+                let target = self
+                    .app
+                    .hacks
+                    .iter()
+                    .find(|hack| hack.name == self.ui.chain_step_hack)
+                    .map(ChainTarget::catalog)
+                    .unwrap_or_else(|| ChainTarget::Catalog {
+                        id: None,
+                        source: None,
+                        name: self.ui.chain_step_hack.clone(),
+                    });
+                chain.steps.push(ChainStep {
+                    target,
+                    delay_ms: 0,
+                });
+                changed = true;
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.ui.chain_step_path)
+                    .hint_text("C:\\path\\to\\file.dll"),
+            );
+
+            if ui.cbutton("Add local DLL").clicked() {
+                let path = PathBuf::from(self.ui.chain_step_path.trim());
+                if path.is_file() {
+                    chain.steps.push(ChainStep {
+                        target: ChainTarget::Local { path },
+                        delay_ms: 0,
+                    });
+                    self.ui.chain_step_path.clear();
+                    changed = true;
+                } else {
+                    self.toasts.error("File does not exist.");
+                }
+            }
+        });
+
+        ui.separator();
+
+        let inject_in_progress = self
+            .communication
+            .inject_in_progress
+            .load(std::sync::atomic::Ordering::SeqCst);
+
+        let mut delete = false;
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled_ui(!inject_in_progress && !chain.steps.is_empty(), |ui| {
+                    ui.button_with_tooltip(
+                        format!("Run {}", chain.name),
+                        "Inject every step in order",
+                    )
+                })
+                .inner
+                .clicked()
+            {
+                self.rpc
+                    .update(None, Some(&format!("Running chain {}", chain.name)));
+                self.spawn_chain(chain.clone(), ctx.clone());
+            }
+
+            if ui
+                .cbutton(RichText::new("Delete chain").color(egui::Color32::LIGHT_RED))
+                .clicked()
+            {
+                delete = true;
+            }
+        });
+
+        self.render_injection_status(ui, ctx, theme_color);
+
+        if delete {
+            self.app.chains.chains.remove(index);
+            self.app.chains.save();
+            self.ui.selected_chain = None;
+            self.toasts.success(format!("Deleted {}.", chain.name));
+        } else if changed {
+            self.app.chains.chains[index] = chain;
+            self.app.chains.save();
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use egui::{Color32, RichText};
+
+use crate::{
+    custom_widgets::{Button, SelectableLabel},
+    utils::history::{History, InjectionOutcome, InjectionRecord, InjectionSource},
+    MyApp,
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct HistoryFilter {
+    pub query: String,
+    pub outcome: Option<InjectionOutcome>,
+    pub source: Option<InjectionSource>,
+}
+
+impl HistoryFilter {
+    fn matches(&self, record: &InjectionRecord) -> bool {
+        let query = self.query.to_lowercase();
+
+        (query.is_empty()
+            || record.hack_name.to_lowercase().contains(&query)
+            || record.process.to_lowercase().contains(&query)
+            || record
+                .error
+                .as_ref()
+                .is_some_and(|error| error.to_lowercase().contains(&query))
+            || record
+                .error_kind
+                .is_some_and(|kind| kind.code().to_lowercase().contains(&query)))
+            && self
+                .outcome
+                .map_or(true, |outcome| record.outcome == outcome)
+            && self.source.map_or(true, |source| record.source == source)
+    }
+}
+
+impl MyApp {
+    pub fn render_history_tab(&mut self, ctx: &egui::Context) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.heading("History");
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.ui.history_filter.query)
+                        .hint_text("Filter by hack, process or error..."),
+                );
+
+                let filter = &mut self.ui.history_filter;
+                for (label, outcome) in [
+                    ("All", None),
+                    ("Success", Some(InjectionOutcome::Success)),
+                    ("Failure", Some(InjectionOutcome::Failure)),
+                    ("Crashed", Some(InjectionOutcome::Crashed)),
+                ] {
+                    if ui
+                        .cselectable_label(filter.outcome == outcome, label)
+                        .clicked()
+                    {
+                        filter.outcome = outcome;
+                    }
+                }
+
+                ui.separator();
+
+                for (label, source) in [
+                    ("Any source", None),
+                    ("Catalog", Some(InjectionSource::Catalog)),
+                    ("Drag & drop", Some(InjectionSource::DragAndDrop)),
+                    ("Chain", Some(InjectionSource::Chain)),
+                    ("Automation", Some(InjectionSource::Automation)),
+                    ("Command line", Some(InjectionSource::CommandLine)),
+                    ("API", Some(InjectionSource::Api)),
+                ] {
+                    if ui
+                        .cselectable_label(filter.source == source, label)
+                        .clicked()
+                    {
+                        filter.source = source;
+                    }
+                }
+            });
+
+            let records = self
+                .app
+                .history
+                .records
+                .iter()
+                .rev()
+                .filter(|record| self.ui.history_filter.matches(record))
+                .collect::<Vec<_>>();
+
+            ui.horizontal(|ui| {
+                ui.label(format!(
+                    "{} of {} records",
+                    records.len(),
+                    self.app.history.records.len()
+                ));
+
+                let mut export = None;
+                if ui.cbutton("Export CSV").clicked() {
+                    export = Some(History::export_csv(&records));
+                }
+                if ui.cbutton("Export JSON").clicked() {
+                    export = Some(History::export_json(&records));
+                }
+
+                match export {
+                    Some(Ok(path)) => {
+                        self.toasts
+                            .success(format!("Exported {} records.", records.len()));
+                        if let Some(dir) = path.parent() {
+                            let _ = opener::open(dir);
+                        }
+                    }
+                    Some(Err(e)) => {
+                        self.toasts.error(e);
+                    }
+                    None => {}
+                }
+            });
+
+            ui.add_space(5.0);
+
+            egui::ScrollArea::both()
+                .auto_shrink([false; 2])
+                .show(ui, |ui| {
+                    egui::Grid::new("history_grid")
+                        .num_columns(7)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong("Time");
+                            ui.strong("Hack");
+                            ui.strong("Source");
+                            ui.strong("Process");
+                            ui.strong("Method");
+                            ui.strong("Duration");
+                            ui.strong("Outcome");
+                            ui.end_row();
+
+                            for record in &records {
+                                render_record(ui, record);
+                            }
+                        });
+                });
+        });
+    }
+}
+
+fn render_record(ui: &mut egui::Ui, record: &InjectionRecord) {
+    ui.label(record.timestamp.format("%Y-%m-%d %H:%M:%S").to_string());
+    ui.label(&record.hack_name);
+    ui.label(record.source.to_string());
+    ui.label(match record.pid {
+        Some(pid) => format!("{} ({})", record.process, pid),
+        None => record.process.clone(),
+    });
+    ui.label(record.method.to_string());
+
+    let stages = record
+        .stages
+        .iter()
+        .map(|stage| format!("{}: {} ms", stage.stage, stage.duration_ms))
+        .collect::<Vec<_>>()
+        .join("\n");
+    ui.label(format!("{} ms", record.total_duration_ms()))
+        .on_hover_text(if stages.is_empty() {
+            "no stages recorded".to_string()
+        } else {
+            stages
+        });
+
+    let color = match record.outcome {
+        InjectionOutcome::Success => Color32::GREEN,
+        InjectionOutcome::Failure => Color32::RED,
+        InjectionOutcome::Crashed => Color32::ORANGE,
+    };
+    let outcome = ui.label(RichText::new(record.outcome.to_string()).color(color));
+    if let Some(error) = &record.error {
+        let hover = match record.error_kind {
+            Some(kind) => match kind.hint() {
+                Some(hint) => format!("[{}] {}\n{}", kind.code(), error, hint),
+                None => format!("[{}] {}", kind.code(), error),
+            },
+            None => error.clone(),
+        };
+        outcome.on_hover_text(hover);
+    }
+    ui.end_row();
+}
@@ -1,47 +1,53 @@
-use std::{fs, path::Path, process::Command, sync::Arc, thread, time::Duration};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
 
-use egui::{CursorIcon::PointingHand as Clickable, RichText, Spinner, TextStyle};
+use egui::{CursorIcon::PointingHand as Clickable, DroppedFile, RichText, Spinner, TextStyle};
 use egui_modal::Modal;
+use unknproject_core::hacks::{self, Hack};
 
 use crate::{
     custom_widgets::{Button, Hyperlink},
     default_main_menu_message,
-    hacks::{self, Hack},
+    inject::{InjectionEvent, InjectionRequest},
+    utils::{
+        history::InjectionMethod,
+        inspector::{format_size, DllInspection},
+        modules::{self, InjectedModule},
+        processes::ProcessArch,
+        steam::missing_launch_options,
+    },
     MyApp,
 };
 
+/// Filled in by the worker reading a dropped DLL, `None` while it's still at it.
+pub type DroppedInspection = Arc<Mutex<Option<Result<DllInspection, String>>>>;
+
 impl MyApp {
     // MARK: Key events
     pub fn handle_key_events(&mut self, ctx: &egui::Context) {
         if ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Escape)) {
             self.app.selected_hack = None;
+            self.ui.selected_chain = None;
         }
 
         if ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Enter)) {
-            if let Some(selected) = &self.app.selected_hack {
-                if selected.game == "CS:GO" {
-                    self.manual_map_injection(
-                        selected.clone(),
-                        ctx.clone(),
-                        self.communication.message_sender.clone(),
-                    );
-                } else {
-                    self.start_injection(
-                        selected.clone(),
-                        ctx.clone(),
-                        self.communication.message_sender.clone(),
-                    );
-                }
+            if let Some(selected) = self.app.selected_hack.clone() {
+                let pid = self.app.selected_pids.get(&selected.process).copied();
+                self.request_injection(InjectionRequest::from_hack(&selected, pid), ctx.clone());
             }
         }
 
         if ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::F5)) {
             self.ui.main_menu_message = "Fetching hacks...".to_string();
             ctx.request_repaint();
-            self.app.hacks = match hacks::Hack::fetch_hacks(
-                &self.app.config.api_endpoint,
-                self.app.config.lowercase_hacks,
-            ) {
+            self.app.hacks = match hacks::Hack::fetch_catalog(&self.downloader(), &self.app.config)
+            {
                 Ok(hacks) => {
                     self.ui.main_menu_message = default_main_menu_message();
                     ctx.request_repaint();
@@ -94,8 +100,16 @@ impl MyApp {
                 .response
                 .on_hover_cursor(Clickable);
 
-            let is_csgo = self.ui.selected_process_dnd == "csgo.exe";
-            let is_cs2 = self.ui.selected_process_dnd == "cs2.exe";
+            if !self.ui.selected_process_dnd.is_empty() {
+                ui.add_space(5.0);
+                let process = self.ui.selected_process_dnd.clone();
+                self.render_process_instances(ui, &process, "dnd_instance");
+            }
+
+            let method = InjectionMethod::for_process(&self.ui.selected_process_dnd);
+
+            ui.add_space(5.0);
+            self.render_dropped_summary(ui);
 
             ui.add_space(5.0);
 
@@ -109,47 +123,39 @@ impl MyApp {
                 }
 
                 self.toasts.info(format!(
-                    "Injecting {}{}",
-                    dropped_filename,
-                    if is_csgo || is_cs2 {
-                        " using manual map injection."
-                    } else {
-                        " using standard injection."
-                    }
+                    "Injecting {} using {} injection.",
+                    dropped_filename, method
                 ));
 
-                if is_csgo || is_cs2 {
-                    self.manual_map_inject(
-                        self.ui.dropped_file.path.clone(),
-                        &self.ui.selected_process_dnd.clone(),
-                        self.communication.message_sender.clone(),
-                    );
-                } else {
-                    self.inject(
-                        self.ui.dropped_file.path.clone(),
-                        &self.ui.selected_process_dnd.clone(),
-                        self.communication.message_sender.clone(),
+                if let Some(path) = self.ui.dropped_file.path.clone() {
+                    let process = self.ui.selected_process_dnd.clone();
+                    let pid = self.app.selected_pids.get(&process).copied();
+                    self.request_injection(
+                        InjectionRequest::from_file(path, &process, pid),
+                        ctx.clone(),
                     );
                 }
 
                 modal.close();
             }
-        });
 
-        if let Some(dropped_file) = ctx.input(|i| i.raw.dropped_files.first().cloned()) {
-            if dropped_file
-                .path
-                .as_ref()
-                .unwrap()
-                .extension()
-                .unwrap_or_default()
-                != "dll"
+            if ui
+                .button_with_tooltip("Inspect", "Show the PE metadata of the dropped file")
+                .clicked()
             {
-                self.toasts.error("Only DLL files are supported.");
-                return;
+                let inspection = self.ui.dropped_inspection.lock().unwrap().clone();
+                self.ui.inspection = inspection.and_then(Result::ok);
+                modal.close();
             }
-            self.ui.dropped_file = dropped_file.clone();
-            modal.open();
+        });
+
+        if let Some(path) = ctx.input(|i| {
+            i.raw
+                .dropped_files
+                .first()
+                .and_then(|dropped_file| dropped_file.path.clone())
+        }) {
+            self.open_dll(path, ctx);
         }
 
         if ctx.input(|i| i.raw.hovered_files.first().is_some()) {
@@ -162,6 +168,30 @@ impl MyApp {
         }
     }
 
+    /// Shows the process picker for a DLL dropped onto the window or passed on launch.
+    pub fn open_dll(&mut self, path: PathBuf, ctx: &egui::Context) {
+        if path.extension().unwrap_or_default() != "dll" {
+            self.toasts.error("Only DLL files are supported.");
+            return;
+        }
+
+        // A fresh slot, so a slow read of an earlier drop can't overwrite this one.
+        let inspection = DroppedInspection::default();
+        self.ui.dropped_inspection = Arc::clone(&inspection);
+        let worker_path = path.clone();
+        let worker_ctx = ctx.clone();
+        thread::spawn(move || {
+            *inspection.lock().unwrap() = Some(DllInspection::inspect(&worker_path));
+            worker_ctx.request_repaint();
+        });
+
+        self.ui.dropped_file = DroppedFile {
+            path: Some(path),
+            ..Default::default()
+        };
+        Modal::new(ctx, "dnd_modal").open();
+    }
+
     // MARK: Hack details
     pub fn display_hack_details(
         &mut self,
@@ -170,8 +200,7 @@ impl MyApp {
         selected: &Hack,
         theme_color: egui::Color32,
     ) {
-        let is_csgo = selected.game == "CS:GO";
-        let is_cs2 = selected.game == "CS2";
+        let method = InjectionMethod::for_game(&selected.game);
 
         ui.vertical(|ui| {
             ui.horizontal(|ui| {
@@ -190,6 +219,12 @@ impl MyApp {
         ui.separator();
         ui.label(&selected.description);
 
+        if let Some(app_id) = selected.steam_app_id {
+            if !selected.required_launch_options.is_empty() {
+                self.render_launch_options_check(ui, selected, app_id);
+            }
+        }
+
         if !self.app.config.hide_steam_account {
             ui.horizontal_wrapped(|ui| {
                 let width = ui.fonts(|f| f.glyph_width(&TextStyle::Body.resolve(ui.style()), ' '));
@@ -206,23 +241,31 @@ impl MyApp {
             });
         }
 
+        ui.add_space(5.0);
+        self.render_process_instances(ui, &selected.process, "details_instance");
+
         // MARK: Inject button
-        let is_32bit = std::mem::size_of::<usize>() == 4;
-        let is_cs2_32bit = is_32bit && selected.game == "CS2";
+        let target_arch = self.selected_instance_arch(&selected.process);
+        let arch_unsupported = method == InjectionMethod::Standard
+            && ProcessArch::current() == ProcessArch::X86
+            && target_arch == ProcessArch::X64;
         let inject_button = ui
-            .add_enabled_ui(!is_cs2_32bit, |ui| {
+            .add_enabled_ui(!arch_unsupported, |ui| {
                 ui.button_with_tooltip(format!("Inject {}", selected.name), &selected.file)
             })
             .inner;
 
-        if is_32bit {
+        if arch_unsupported {
             ui.label(
-                RichText::new("32-bit detected, cs2 hacks are not supported.")
-                    .color(egui::Color32::RED),
+                RichText::new(format!(
+                    "32-bit loader detected, {} is a 64-bit process.",
+                    selected.process
+                ))
+                .color(egui::Color32::RED),
             );
         }
 
-        if inject_button.clicked() && !is_cs2_32bit {
+        if inject_button.clicked() && !arch_unsupported {
             self.toasts
                 .custom(
                     format!("Injecting {}", selected.name),
@@ -236,21 +279,25 @@ impl MyApp {
 
             log::info!("Injecting {}", selected.name);
 
-            if is_csgo || is_cs2 {
-                self.manual_map_injection(
-                    selected.clone(),
-                    ctx.clone(),
-                    self.communication.message_sender.clone(),
-                );
-            } else {
-                self.start_injection(
-                    selected.clone(),
-                    ctx.clone(),
-                    self.communication.message_sender.clone(),
-                );
-            }
+            let pid = self.app.selected_pids.get(&selected.process).copied();
+            self.request_injection(InjectionRequest::from_hack(selected, pid), ctx.clone());
+        }
+
+        if let Some(app_id) = selected.steam_app_id {
+            self.render_launch_and_inject(ui, ctx, selected, app_id);
         }
 
+        self.render_injection_status(ui, ctx, theme_color);
+
+        self.render_injected_modules(ui, &selected.process);
+    }
+
+    pub fn render_injection_status(
+        &mut self,
+        ui: &mut egui::Ui,
+        ctx: &egui::Context,
+        theme_color: egui::Color32,
+    ) {
         let inject_in_progress = self
             .communication
             .inject_in_progress
@@ -285,11 +332,285 @@ impl MyApp {
         }
     }
 
+    // MARK: Injected modules
+    fn render_injected_modules(&mut self, ui: &mut egui::Ui, process: &str) {
+        let modules = self.app.modules.lock().unwrap().for_process(process);
+        if modules.is_empty() {
+            return;
+        }
+
+        ui.add_space(5.0);
+        ui.separator();
+        ui.label("Injected modules:");
+
+        for module in modules {
+            ui.horizontal(|ui| {
+                ui.label(format!(
+                    "{} in {} ({}) at {}",
+                    module.name,
+                    module.process,
+                    module.pid,
+                    module.injected_at.format("%H:%M:%S")
+                ))
+                .on_hover_text(module.path.to_string_lossy());
+
+                if !module.ejectable() {
+                    ui.label(RichText::new("manual map").color(egui::Color32::GRAY))
+                        .on_hover_text("Manual-mapped modules can't be ejected");
+                    return;
+                }
+
+                if ui
+                    .button_with_tooltip("Eject", "Unload this module from the process")
+                    .clicked()
+                {
+                    self.spawn_eject(module, ui.ctx().clone());
+                }
+            });
+        }
+    }
+
+    /// Ejecting opens the target and walks its modules, so it runs on a worker. The result
+    /// comes back as [`InjectionEvent::Ejected`].
+    fn spawn_eject(&self, module: InjectedModule, ctx: egui::Context) {
+        let tracker = Arc::clone(&self.app.modules);
+        let sender = self.communication.message_sender.clone();
+
+        thread::spawn(move || {
+            let result = modules::eject(&tracker, &module);
+            sender.send(InjectionEvent::Ejected(module, result)).ok();
+            ctx.request_repaint();
+        });
+    }
+
+    fn render_dropped_summary(&mut self, ui: &mut egui::Ui) {
+        let inspection = match self.ui.dropped_inspection.lock().unwrap().clone() {
+            Some(Ok(inspection)) => inspection,
+            Some(Err(e)) => {
+                ui.label(RichText::new(e).color(egui::Color32::RED));
+                return;
+            }
+            None => {
+                ui.horizontal(|ui| {
+                    ui.add(Spinner::new());
+                    ui.label("Reading file...");
+                });
+                return;
+            }
+        };
+
+        let pe = match &inspection.pe {
+            Ok(pe) => pe,
+            Err(e) => {
+                ui.label(RichText::new(e).color(egui::Color32::RED));
+                return;
+            }
+        };
+
+        ui.label(format!(
+            "{} {}, {}",
+            pe.arch(),
+            if pe.is_dll() { "DLL" } else { "PE file" },
+            format_size(inspection.size)
+        ));
+
+        if !pe.is_dll() {
+            ui.label(RichText::new("This file is not a DLL.").color(egui::Color32::RED));
+        }
+
+        if !self.ui.selected_process_dnd.is_empty() {
+            let process = self.ui.selected_process_dnd.clone();
+            let target_arch = self.selected_instance_arch(&process);
+            if target_arch != ProcessArch::Unknown && target_arch != pe.arch() {
+                ui.label(
+                    RichText::new(format!(
+                        "Architecture mismatch: {} is {}.",
+                        process, target_arch
+                    ))
+                    .color(egui::Color32::RED),
+                );
+            }
+        }
+    }
+
+    // MARK: Process instances
+    fn render_process_instances(&mut self, ui: &mut egui::Ui, process: &str, id_salt: &str) {
+        let instances = self.ui.process_list.instances(process);
+
+        if instances.is_empty() {
+            ui.label(
+                RichText::new(format!("{} is not running.", process)).color(egui::Color32::GRAY),
+            );
+            return;
+        }
+
+        let selected_instance = self
+            .app
+            .selected_pids
+            .get(process)
+            .and_then(|pid| instances.iter().find(|instance| instance.pid == *pid));
+        let selected_pid = selected_instance.map(|instance| instance.pid);
+        let selected_text = selected_instance
+            .map(|instance| instance.label())
+            .unwrap_or_else(|| "First found".to_string());
+
+        ui.horizontal(|ui| {
+            ui.label("Instance:");
+            egui::ComboBox::from_id_salt(id_salt)
+                .selected_text(selected_text)
+                .show_ui(ui, |ui| {
+                    for instance in &instances {
+                        if ui
+                            .selectable_label(selected_pid == Some(instance.pid), instance.label())
+                            .on_hover_cursor(Clickable)
+                            .clicked()
+                        {
+                            log::debug!("Selected {} instance {}", process, instance.pid);
+                            self.app
+                                .selected_pids
+                                .insert(process.to_string(), instance.pid);
+                        }
+                    }
+                })
+                .response
+                .on_hover_cursor(Clickable);
+
+            if ui
+                .button_with_tooltip("⟳", "Refresh process list")
+                .clicked()
+            {
+                self.ui.process_list.refresh(process);
+            }
+        });
+    }
+
+    fn render_launch_options_check(&mut self, ui: &mut egui::Ui, selected: &Hack, app_id: u32) {
+        let required = &selected.required_launch_options;
+        let (message, suggested) = match self.ui.launch_options_cache.get(&self.app.account, app_id)
+        {
+            Ok(configured) => {
+                let missing = missing_launch_options(&configured, required);
+                if missing.is_empty() {
+                    return;
+                }
+
+                let suggested = configured
+                    .split_whitespace()
+                    .map(str::to_string)
+                    .chain(missing.iter().cloned())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                (
+                    format!(
+                        "⚠ {} needs {} in the Steam launch options of {}.",
+                        selected.name,
+                        missing.join(" "),
+                        selected.game
+                    ),
+                    suggested,
+                )
+            }
+            // Can't read Steam's config, so just say what's needed.
+            Err(_) => (
+                format!(
+                    "{} needs these Steam launch options: {}",
+                    selected.name,
+                    required.join(" ")
+                ),
+                required.join(" "),
+            ),
+        };
+
+        ui.horizontal_wrapped(|ui| {
+            ui.label(RichText::new(message).color(egui::Color32::YELLOW));
+            if ui
+                .button_with_tooltip("Copy launch options", &suggested)
+                .clicked()
+            {
+                ui.output_mut(|o| o.copied_text = suggested.clone());
+                self.toasts.info("Launch options copied.");
+            }
+        });
+    }
+
+    fn render_launch_and_inject(
+        &mut self,
+        ui: &mut egui::Ui,
+        ctx: &egui::Context,
+        selected: &Hack,
+        app_id: u32,
+    ) {
+        let running = !self.ui.process_list.instances(&selected.process).is_empty();
+        let inject_in_progress = self
+            .communication
+            .inject_in_progress
+            .load(std::sync::atomic::Ordering::SeqCst);
+
+        ui.horizontal(|ui| {
+            let mut options = self
+                .app
+                .config
+                .launch_options
+                .get(&app_id)
+                .cloned()
+                .unwrap_or_default();
+            if ui
+                .add(
+                    egui::TextEdit::singleline(&mut options)
+                        .hint_text("Launch options")
+                        .desired_width(150.0),
+                )
+                .changed()
+            {
+                if options.trim().is_empty() {
+                    self.app.config.launch_options.remove(&app_id);
+                } else {
+                    self.app.config.launch_options.insert(app_id, options);
+                }
+                self.app.config.save();
+            }
+
+            let tooltip = if running {
+                format!("{} is already running", selected.process)
+            } else {
+                format!(
+                    "Start {} through Steam and inject once it has loaded",
+                    selected.game
+                )
+            };
+            if ui
+                .add_enabled_ui(!running && !inject_in_progress, |ui| {
+                    ui.button_with_tooltip("Launch & Inject", tooltip)
+                })
+                .inner
+                .clicked()
+            {
+                self.rpc
+                    .update(None, Some(&format!("Launching {}", selected.game)));
+                log::info!("Launching {} to inject {}", selected.game, selected.name);
+                self.spawn_launch_and_inject(selected.clone(), ctx.clone());
+            }
+        });
+    }
+
+    fn selected_instance_arch(&mut self, process: &str) -> ProcessArch {
+        let instances = self.ui.process_list.instances(process);
+        let pid = self.app.selected_pids.get(process).copied();
+
+        instances
+            .iter()
+            .find(|instance| Some(instance.pid) == pid)
+            .or(instances.first())
+            .map(|instance| instance.arch)
+            .unwrap_or(ProcessArch::Unknown)
+    }
+
     pub fn context_menu(&mut self, response: &egui::Response, ctx: &egui::Context, hack: &Hack) {
         // MARK: Context menu
         let file_path_owned = hack.file_path.clone();
         let ctx_clone = ctx.clone();
         let status_message = Arc::clone(&self.communication.status_message);
+        let downloader = self.downloader();
         let is_favorite = self.app.config.favorites.contains(&hack.name);
 
         response.context_menu(|ui| {
@@ -313,6 +634,14 @@ impl MyApp {
 
             // show only if file exists
             if Path::new(&file_path_owned).exists() {
+                if ui
+                    .button_with_tooltip("Inspect", "Show the PE metadata of the file")
+                    .clicked()
+                {
+                    self.open_inspector(&file_path_owned);
+                    ui.close_menu();
+                }
+
                 if ui
                     .button_with_tooltip("Open in Explorer", "Open the file location in Explorer")
                     .clicked()
@@ -365,7 +694,9 @@ impl MyApp {
                             ctx_clone.request_repaint();
                             return;
                         }
-                        match hack_clone.download(file_path_owned.to_string_lossy().to_string()) {
+                        match hack_clone
+                            .download(&downloader, file_path_owned.to_string_lossy().to_string())
+                        {
                             Ok(_) => {
                                 let mut status = status_message.lock().unwrap();
                                 *status = "Reinstalled.".to_string();
@@ -382,23 +713,31 @@ impl MyApp {
                 }
             } else {
                 if ui.cbutton("Download").clicked() {
-                    let file_path = hack.file_path.clone();
-                    let hack_clone = hack.clone();
-                    thread::spawn(move || {
-                        match hack_clone.download(file_path.to_string_lossy().to_string()) {
-                            Ok(_) => {
-                                let mut status = status_message.lock().unwrap();
-                                *status = "Downloaded.".to_string();
-                            }
-                            Err(e) => {
-                                let mut status = status_message.lock().unwrap();
-                                *status = format!("Failed to download: {}", e);
-                            }
-                        }
-                    });
+                    self.spawn_download(hack.clone(), ctx.clone());
                     ui.close_menu();
                 }
             }
         });
     }
+
+    /// Downloads `hack` in the background, reporting progress in the status line.
+    pub fn spawn_download(&self, hack: Hack, ctx: egui::Context) {
+        let status_message = Arc::clone(&self.communication.status_message);
+        let downloader = self.downloader();
+        *status_message.lock().unwrap() = format!("Downloading {}...", hack.name);
+
+        thread::spawn(move || {
+            match hack.download(&downloader, hack.file_path.to_string_lossy().to_string()) {
+                Ok(_) => {
+                    let mut status = status_message.lock().unwrap();
+                    *status = "Downloaded.".to_string();
+                }
+                Err(e) => {
+                    let mut status = status_message.lock().unwrap();
+                    *status = format!("Failed to download: {}", e);
+                }
+            }
+            ctx.request_repaint();
+        });
+    }
 }
@@ -0,0 +1,175 @@
+use chrono::{Local, TimeZone};
+use egui::{Color32, RichText};
+
+use crate::{
+    custom_widgets::Button,
+    utils::inspector::{format_size, DllInspection},
+    MyApp,
+};
+
+impl MyApp {
+    pub fn open_inspector(&mut self, path: &std::path::Path) {
+        match DllInspection::inspect(path) {
+            Ok(inspection) => {
+                log::debug!("Inspecting {}", path.display());
+                self.ui.inspection = Some(inspection);
+            }
+            Err(e) => {
+                log::error!("Failed to inspect DLL: {}", e);
+                self.toasts.error(e);
+            }
+        }
+    }
+
+    // MARK: DLL inspector
+    pub fn render_inspector_window(&mut self, ctx: &egui::Context) {
+        let Some(inspection) = self.ui.inspection.clone() else {
+            return;
+        };
+
+        let mut open = true;
+        egui::Window::new(format!("Inspector: {}", inspection.file_name()))
+            .id(egui::Id::new("dll_inspector"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical()
+                    .max_height(350.0)
+                    .show(ui, |ui| self.render_inspection(ui, &inspection));
+            });
+
+        if !open {
+            self.ui.inspection = None;
+        }
+    }
+
+    fn render_inspection(&mut self, ui: &mut egui::Ui, inspection: &DllInspection) {
+        egui::Grid::new("inspector_file")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Path:");
+                ui.label(inspection.path.to_string_lossy());
+                ui.end_row();
+
+                ui.label("Size:");
+                ui.label(format_size(inspection.size));
+                ui.end_row();
+
+                ui.label("SHA-256:");
+                ui.horizontal(|ui| {
+                    ui.monospace(&inspection.sha256[..16])
+                        .on_hover_text(&inspection.sha256);
+                    if ui.cbutton("Copy").clicked() {
+                        ui.output_mut(|o| o.copied_text = inspection.sha256.clone());
+                        self.toasts.success("SHA-256 copied to clipboard.");
+                    }
+                });
+                ui.end_row();
+            });
+
+        let pe = match &inspection.pe {
+            Ok(pe) => pe,
+            Err(e) => {
+                ui.add_space(5.0);
+                ui.label(RichText::new(e).color(Color32::RED));
+                return;
+            }
+        };
+
+        ui.separator();
+
+        egui::Grid::new("inspector_pe")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Architecture:");
+                ui.label(format!(
+                    "{} ({})",
+                    pe.machine,
+                    if pe.is_pe32_plus { "PE32+" } else { "PE32" }
+                ));
+                ui.end_row();
+
+                ui.label("Type:");
+                if pe.is_dll() {
+                    ui.label("DLL");
+                } else {
+                    ui.label(RichText::new("Not a DLL").color(Color32::RED));
+                }
+                ui.end_row();
+
+                ui.label("Timestamp:");
+                ui.label(
+                    Local
+                        .timestamp_opt(pe.timestamp as i64, 0)
+                        .single()
+                        .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+                        .unwrap_or_else(|| format!("0x{:08x}", pe.timestamp)),
+                );
+                ui.end_row();
+
+                if let Some(version) = &pe.version {
+                    if let Some(file_version) = &version.file_version {
+                        ui.label("File version:");
+                        ui.label(file_version);
+                        ui.end_row();
+                    }
+                    if let Some(product_version) = &version.product_version {
+                        ui.label("Product version:");
+                        ui.label(product_version);
+                        ui.end_row();
+                    }
+                    for (key, value) in &version.strings {
+                        if value.is_empty() {
+                            continue;
+                        }
+                        ui.label(format!("{}:", key));
+                        ui.label(value);
+                        ui.end_row();
+                    }
+                } else {
+                    ui.label("Version:");
+                    ui.label(RichText::new("no version resource").color(Color32::GRAY));
+                    ui.end_row();
+                }
+            });
+
+        ui.separator();
+
+        ui.collapsing(format!("Sections ({})", pe.sections.len()), |ui| {
+            egui::Grid::new("inspector_sections")
+                .num_columns(4)
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.strong("Name");
+                    ui.strong("Virtual address");
+                    ui.strong("Virtual size");
+                    ui.strong("Raw size");
+                    ui.end_row();
+
+                    for section in &pe.sections {
+                        ui.monospace(&section.name);
+                        ui.monospace(format!("0x{:08x}", section.virtual_address));
+                        ui.monospace(format_size(section.virtual_size as u64));
+                        ui.monospace(format_size(section.raw_size as u64));
+                        ui.end_row();
+                    }
+                });
+        });
+
+        ui.collapsing(format!("Exports ({})", pe.exports.len()), |ui| {
+            for export in &pe.exports {
+                ui.monospace(export);
+            }
+        });
+
+        ui.collapsing(format!("Imports ({})", pe.imports.len()), |ui| {
+            for import in &pe.imports {
+                ui.monospace(import);
+            }
+        });
+    }
+}
@@ -0,0 +1,145 @@
+use egui::RichText;
+use egui_modal::Modal;
+use unknproject_core::hacks::{get_all_processes, Hack};
+
+use crate::{
+    custom_widgets::Button, default_main_menu_message, inject::InjectionRequest,
+    tabs::top_panel::AppTab, utils::deeplink::DeepLink, MyApp,
+};
+
+impl MyApp {
+    /// Selecting is harmless and happens right away; injecting and adding a source wait
+    /// for the user to confirm, since anyone can put a link on a web page.
+    pub fn open_link(&mut self, link: DeepLink) {
+        log::info!("Opening {}", link);
+
+        match &link {
+            DeepLink::Select(id) | DeepLink::Inject(id) => {
+                let Some(hack) = self.app.hacks.iter().find(|hack| hack.id == *id).cloned() else {
+                    self.toasts
+                        .error(format!("No hack with id {} in the catalog.", id));
+                    return;
+                };
+
+                self.select_linked_hack(hack);
+                if matches!(link, DeepLink::Inject(_)) {
+                    self.ui.pending_link = Some(link);
+                }
+            }
+            DeepLink::AddSource(url) => {
+                if *url == self.app.config.api_endpoint
+                    || self.app.config.catalog_sources.contains(url)
+                {
+                    self.toasts.info("This catalog source was already added.");
+                    return;
+                }
+                self.ui.pending_link = Some(link);
+            }
+        }
+    }
+
+    fn select_linked_hack(&mut self, hack: Hack) {
+        self.ui.tab = AppTab::Home;
+        self.select_hack(&hack);
+    }
+
+    pub fn render_deep_link_dialog(&mut self, ctx: &egui::Context) {
+        let modal = Modal::new(ctx, "deep_link_dialog");
+
+        modal.show(|ui| {
+            let Some(link) = self.ui.pending_link.clone() else {
+                return;
+            };
+
+            let confirm = match &link {
+                DeepLink::Inject(id) => {
+                    let Some(hack) = self.app.hacks.iter().find(|hack| hack.id == *id) else {
+                        self.ui.pending_link = None;
+                        modal.close();
+                        return;
+                    };
+
+                    ui.heading("Inject from link");
+                    ui.add_space(5.0);
+                    ui.label(format!(
+                        "A link asks to inject {} by {} into {}.",
+                        hack.name, hack.author, hack.process
+                    ));
+                    ui.label(RichText::new(link.to_string()).monospace().weak());
+                    "Inject"
+                }
+                DeepLink::AddSource(url) => {
+                    ui.heading("Add catalog source");
+                    ui.add_space(5.0);
+                    ui.label("A link asks to add this catalog source:");
+                    ui.label(RichText::new(url).monospace());
+                    ui.label(
+                        RichText::new(
+                            "Its hacks run inside your games. Only add sources you trust.",
+                        )
+                        .color(egui::Color32::LIGHT_RED),
+                    );
+                    "Add source"
+                }
+                DeepLink::Select(_) => {
+                    self.ui.pending_link = None;
+                    modal.close();
+                    return;
+                }
+            };
+            ui.add_space(5.0);
+
+            ui.horizontal(|ui| {
+                if ui.cbutton(confirm).clicked() {
+                    self.ui.pending_link = None;
+                    modal.close();
+                    self.apply_link(link.clone(), ctx);
+                }
+
+                if ui.cbutton("Cancel").clicked() {
+                    log::info!("Dismissed {}", link);
+                    self.ui.pending_link = None;
+                    modal.close();
+                }
+            });
+        });
+
+        if self.ui.pending_link.is_some() && !modal.is_open() {
+            modal.open();
+        }
+    }
+
+    fn apply_link(&mut self, link: DeepLink, ctx: &egui::Context) {
+        match link {
+            DeepLink::Select(_) => {}
+            DeepLink::Inject(id) => {
+                if let Some(hack) = self.app.hacks.iter().find(|hack| hack.id == id).cloned() {
+                    let pid = self.app.selected_pids.get(&hack.process).copied();
+                    self.request_injection(InjectionRequest::from_hack(&hack, pid), ctx.clone());
+                }
+            }
+            DeepLink::AddSource(url) => {
+                log::info!("Adding catalog source {}", url);
+                self.app.config.catalog_sources.push(url);
+                self.app.config.save();
+                self.refresh_hacks();
+                self.toasts.success("Catalog source added.");
+            }
+        }
+    }
+
+    /// Reloads the catalog and every source, e.g. after the list of sources changed.
+    pub fn refresh_hacks(&mut self) {
+        self.app.hacks = match Hack::fetch_catalog(&self.downloader(), &self.app.config) {
+            Ok(hacks) => {
+                self.ui.main_menu_message = default_main_menu_message();
+                hacks
+            }
+            Err(_err) => {
+                self.ui.main_menu_message = "Failed to fetch hacks.".to_string();
+                Vec::new()
+            }
+        };
+        self.app.hacks_processes = get_all_processes(&self.app.hacks);
+    }
+}
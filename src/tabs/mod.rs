@@ -1,6 +1,11 @@
 pub mod about;
+pub mod automation;
+pub mod chains;
 pub mod debug;
+pub mod history;
 pub mod home;
+pub mod inspector;
+pub mod links;
 pub mod logs;
 pub mod settings;
 pub mod top_panel;
@@ -1,10 +1,29 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
 use egui::{CursorIcon::PointingHand as Clickable, RichText};
 use egui_modal::Modal;
+use unknproject_core::hacks;
 
 use crate::{
+    api::ApiServer,
     custom_widgets::{Button, CheckBox, TextEdit},
-    hacks,
-    utils::config::{default_api_endpoint, default_cdn_endpoint, default_cdn_fallback_endpoint},
+    default_main_menu_message,
+    utils::{
+        bundle,
+        config::{default_api_endpoint, default_cdn_endpoint, default_cdn_fallback_endpoint},
+        injector_assets::{
+            self, injector_file_name, InjectorAsset, InjectorAssets, InjectorManifest,
+            InjectorStatus, INJECTOR_ARCHES,
+        },
+        processes::ProcessArch,
+    },
     MyApp,
 };
 
@@ -40,9 +59,9 @@ impl MyApp {
                             )
                             .changed()
                         {
-                            self.app.hacks = match hacks::Hack::fetch_hacks(
-                                &self.app.config.api_endpoint,
-                                self.app.config.lowercase_hacks,
+                            self.app.hacks = match hacks::Hack::fetch_catalog(
+                                &self.downloader(),
+                                &self.app.config,
                             ) {
                                 Ok(hacks) => hacks,
                                 Err(_err) => {
@@ -110,11 +129,13 @@ impl MyApp {
 
                         ui.horizontal(|ui| {
                             ui.label("Favorites Color:");
+                            let mut color = self.favorites_color();
                             if ui
-                                .color_edit_button_srgba(&mut self.app.config.favorites_color)
+                                .color_edit_button_srgba(&mut color)
                                 .on_hover_cursor(Clickable)
                                 .changed()
                             {
+                                self.app.config.favorites_color = color.to_array();
                                 self.app.config.save();
                             }
                         });
@@ -126,57 +147,178 @@ impl MyApp {
                     ui.group(|ui| {
                         ui.label("Injection Options:");
 
-                        let modal_injector = Modal::new(ctx, "injector_confirm_dialog")
-                            .with_close_on_outside_click(true);
+                        self.render_injector_assets(ui, ctx);
 
-                        modal_injector.show(|ui| {
-                            ui.label("Select architecture to delete:");
+                        ui.add_space(5.0);
+
+                        if ui
+                            .ccheckbox(
+                                &mut self.app.config.health_check,
+                                "Confirm injection (check the game is still running)",
+                            )
+                            .changed()
+                        {
+                            self.app.config.save();
+                        }
+
+                        ui.add_enabled_ui(self.app.config.health_check, |ui| {
                             ui.horizontal(|ui| {
+                                ui.label("Grace period:");
                                 if ui
-                                    .cbutton(RichText::new("x64").color(egui::Color32::LIGHT_RED))
-                                    .clicked()
+                                    .add(
+                                        egui::DragValue::new(
+                                            &mut self.app.config.health_check_grace_secs,
+                                        )
+                                        .range(1..=60)
+                                        .suffix(" s"),
+                                    )
+                                    .on_hover_text("How long the game must survive after injection")
+                                    .changed()
                                 {
-                                    if let Err(err) = self.delete_injectors("x64") {
-                                        self.toasts.error(err);
-                                    } else {
-                                        self.toasts.success("x64 injector deleted.");
-                                        modal_injector.close();
-                                    }
+                                    self.app.config.save();
                                 }
+                            });
+                        });
 
-                                if ui
-                                    .cbutton(RichText::new("x86").color(egui::Color32::LIGHT_RED))
-                                    .clicked()
-                                {
-                                    if let Err(err) = self.delete_injectors("x86") {
-                                        self.toasts.error(err);
-                                    } else {
-                                        self.toasts.success("x86 injector deleted.");
-                                        modal_injector.close();
-                                    }
-                                    modal_injector.close();
+                        ui.horizontal(|ui| {
+                            ui.label("Injector timeout:");
+                            if ui
+                                .add(
+                                    egui::DragValue::new(
+                                        &mut self.app.config.injector_timeout_secs,
+                                    )
+                                    .range(5..=600)
+                                    .suffix(" s"),
+                                )
+                                .on_hover_text("Kill the manual map injector if it runs longer")
+                                .changed()
+                            {
+                                self.app.config.save();
+                            }
+                        });
+
+                        ui.horizontal(|ui| {
+                            ui.label("Launch timeout:");
+                            if ui
+                                .add(
+                                    egui::DragValue::new(&mut self.app.config.launch_timeout_secs)
+                                        .range(30..=900)
+                                        .suffix(" s"),
+                                )
+                                .on_hover_text(
+                                    "How long Launch & Inject waits for the game to load",
+                                )
+                                .changed()
+                            {
+                                self.app.config.save();
+                            }
+                        });
+                    });
+
+                    ui.add_space(5.0);
+
+                    // MARK: - Catalog Sources
+                    ui.group(|ui| {
+                        ui.label("Catalog Sources:");
+                        ui.add_space(5.0);
+
+                        if self.app.config.catalog_sources.is_empty() {
+                            ui.label(
+                                RichText::new(
+                                    "Add sources through add-source links or offline bundles.",
+                                )
+                                .weak(),
+                            );
+                        }
+
+                        let mut removed = None;
+                        for (index, source) in self.app.config.catalog_sources.iter().enumerate() {
+                            ui.horizontal(|ui| {
+                                if ui.cbutton("Remove").clicked() {
+                                    removed = Some(index);
                                 }
+                                ui.label(RichText::new(source).monospace());
+                            });
+                        }
 
-                                if ui
-                                    .cbutton(RichText::new("Both").color(egui::Color32::LIGHT_RED))
-                                    .clicked()
-                                {
-                                    if let Err(err) = self.delete_injectors("both") {
-                                        self.toasts.error(err);
-                                    } else {
-                                        self.toasts.success("Both injectors deleted.");
-                                        modal_injector.close();
-                                    }
+                        if let Some(index) = removed {
+                            let source = self.app.config.catalog_sources.remove(index);
+                            log::info!("Removed catalog source {}", source);
+                            self.app.config.save();
+                            self.refresh_hacks();
+                            self.toasts.info("Catalog source removed.");
+                        }
+                    });
+
+                    ui.add_space(5.0);
+
+                    // MARK: - Offline Bundle
+                    ui.group(|ui| {
+                        ui.label("Offline Bundle:");
+                        ui.add_space(5.0);
+
+                        self.render_offline_bundle(ui, ctx);
+                    });
+
+                    ui.add_space(5.0);
+
+                    // MARK: - Control API
+                    ui.group(|ui| {
+                        ui.label("Control API:");
+                        ui.add_space(5.0);
+
+                        if ui
+                            .ccheckbox(
+                                &mut self.app.config.api_enabled,
+                                "Accept commands from local scripts",
+                            )
+                            .changed()
+                        {
+                            self.app.config.save();
+                            self.apply_api_settings(ctx);
+                        }
+
+                        ui.horizontal(|ui| {
+                            ui.label("Port:");
+                            let response = ui.add(
+                                egui::DragValue::new(&mut self.app.config.api_port)
+                                    .range(1024..=65535),
+                            );
+                            // Restart once dragging ends rather than for every value in between.
+                            if (response.changed() && !response.dragged())
+                                || response.drag_stopped()
+                            {
+                                self.app.config.save();
+                                if self.app.config.api_enabled {
+                                    self.apply_api_settings(ctx);
                                 }
+                            }
 
-                                if ui.cbutton("Cancel").clicked() {
-                                    modal_injector.close();
+                            if ui
+                                .cbutton("Copy token")
+                                .on_hover_text("Send it as \"Authorization: Bearer <token>\"")
+                                .clicked()
+                            {
+                                match ApiServer::token() {
+                                    Ok(token) => {
+                                        ctx.copy_text(token);
+                                        self.toasts.info("API token copied.");
+                                    }
+                                    Err(e) => {
+                                        self.toasts.error(e);
+                                    }
                                 }
-                            });
+                            }
                         });
 
-                        if ui.cbutton("Delete injector").clicked() {
-                            modal_injector.open();
+                        if self.api.is_some() {
+                            ui.label(
+                                RichText::new(format!(
+                                    "Listening on http://127.0.0.1:{}/api/",
+                                    self.app.config.api_port
+                                ))
+                                .weak(),
+                            );
                         }
                     });
 
@@ -256,4 +398,506 @@ impl MyApp {
                 });
         });
     }
+
+    // MARK: - Injector assets
+    fn render_injector_assets(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        let panel = &mut self.ui.injector_panel;
+        let busy = panel.busy.load(Ordering::SeqCst);
+        if panel.was_busy && !busy {
+            panel.refreshed = None;
+        }
+        panel.was_busy = busy;
+
+        if panel
+            .refreshed
+            .map_or(true, |t| t.elapsed() > Duration::from_secs(5))
+        {
+            panel.refresh(&self.app.injectors);
+        }
+
+        let mut action = None;
+
+        egui::Grid::new("injector_assets_grid")
+            .num_columns(4)
+            .show(ui, |ui| {
+                let assets = self.app.injectors.lock().unwrap().clone();
+                let unpublished = self.ui.injector_panel.unpublished.lock().unwrap().clone();
+                for (arch, status) in &self.ui.injector_panel.statuses {
+                    ui.label(injector_file_name(*arch));
+
+                    let version = assets
+                        .get(*arch)
+                        .map(|asset| asset.version.clone())
+                        .unwrap_or_else(|| "-".to_string());
+                    let label = ui.label(version);
+                    if let Some(asset) = assets.get(*arch) {
+                        label.on_hover_text(format!(
+                            "SHA-256: {}\nSource: {}\nInstalled: {}",
+                            asset.sha256,
+                            asset.source,
+                            asset.installed_at.format("%Y-%m-%d %H:%M:%S")
+                        ));
+                    }
+
+                    let color = match status {
+                        InjectorStatus::Verified => egui::Color32::GREEN,
+                        InjectorStatus::Missing | InjectorStatus::Unverified => egui::Color32::GRAY,
+                        InjectorStatus::UpdateAvailable(_) => egui::Color32::YELLOW,
+                        InjectorStatus::Modified => egui::Color32::LIGHT_RED,
+                    };
+                    ui.label(RichText::new(status.to_string()).color(color));
+
+                    ui.add_enabled_ui(!busy, |ui| {
+                        ui.horizontal(|ui| {
+                            let download = if *status == InjectorStatus::Missing {
+                                "Download"
+                            } else {
+                                "Redownload"
+                            };
+                            if ui.cbutton(download).clicked() {
+                                action = Some(InjectorAction::Install(*arch));
+                            }
+                            if unpublished.contains(arch)
+                                && ui
+                                    .cbutton("Install unverified")
+                                    .on_hover_text("There's no published hash to check it against")
+                                    .clicked()
+                            {
+                                action = Some(InjectorAction::InstallUnverified(*arch));
+                            }
+                            if *status == InjectorStatus::Unverified
+                                && ui
+                                    .cbutton("Trust")
+                                    .on_hover_text("Use the file on disk without verifying it")
+                                    .clicked()
+                            {
+                                action = Some(InjectorAction::Trust(*arch));
+                            }
+                            if *status != InjectorStatus::Missing
+                                && ui
+                                    .cbutton(
+                                        RichText::new("Delete").color(egui::Color32::LIGHT_RED),
+                                    )
+                                    .clicked()
+                            {
+                                action = Some(InjectorAction::Delete(*arch));
+                            }
+                        });
+                    });
+                    ui.end_row();
+                }
+            });
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled_ui(!busy, |ui| ui.cbutton("Check for updates"))
+                .inner
+                .clicked()
+            {
+                action = Some(InjectorAction::CheckUpdates);
+            }
+            if ui.cbutton("Verify").clicked() {
+                self.ui.injector_panel.refresh(&self.app.injectors);
+            }
+
+            let message = self.ui.injector_panel.message.lock().unwrap().clone();
+            if busy {
+                ui.spinner();
+            }
+            ui.label(message);
+        });
+
+        let modal_injector =
+            Modal::new(ctx, "injector_confirm_dialog").with_close_on_outside_click(true);
+
+        modal_injector.show(|ui| {
+            let Some(pending) = self.ui.injector_panel.pending_confirm else {
+                return;
+            };
+            let (question, confirm) = match pending {
+                InjectorAction::Delete(arch) => {
+                    (format!("Delete {}?", injector_file_name(arch)), "Delete")
+                }
+                InjectorAction::InstallUnverified(arch) => (
+                    format!(
+                        "{} has no published hash, so the download can't be checked.\n\
+                         Install it anyway?",
+                        injector_file_name(arch)
+                    ),
+                    "Install",
+                ),
+                InjectorAction::Trust(arch) => (
+                    format!(
+                        "{} wasn't installed by the loader and can't be verified.\n\
+                         Trust it anyway?",
+                        injector_file_name(arch)
+                    ),
+                    "Trust",
+                ),
+                _ => return,
+            };
+
+            ui.label(question);
+            ui.horizontal(|ui| {
+                if ui
+                    .cbutton(RichText::new(confirm).color(egui::Color32::LIGHT_RED))
+                    .clicked()
+                {
+                    match pending {
+                        InjectorAction::Delete(arch) => {
+                            match injector_assets::delete(&self.app.injectors, arch) {
+                                Ok(()) => {
+                                    self.toasts.success(format!("{} injector deleted.", arch));
+                                }
+                                Err(err) => {
+                                    self.toasts.error(err);
+                                }
+                            }
+                        }
+                        InjectorAction::Trust(arch) => {
+                            match self.app.injectors.lock().unwrap().trust(arch) {
+                                Ok(_) => {
+                                    self.toasts.info(format!("{} injector trusted.", arch));
+                                }
+                                Err(err) => {
+                                    self.toasts.error(err);
+                                }
+                            }
+                        }
+                        action => self.spawn_injector_task(action, ctx.clone()),
+                    }
+                    self.ui.injector_panel.pending_confirm = None;
+                    self.ui.injector_panel.refreshed = None;
+                    modal_injector.close();
+                }
+
+                if ui.cbutton("Cancel").clicked() {
+                    self.ui.injector_panel.pending_confirm = None;
+                    modal_injector.close();
+                }
+            });
+        });
+
+        match action {
+            Some(
+                action @ (InjectorAction::Delete(_)
+                | InjectorAction::InstallUnverified(_)
+                | InjectorAction::Trust(_)),
+            ) => {
+                self.ui.injector_panel.pending_confirm = Some(action);
+                modal_injector.open();
+            }
+            Some(action) => self.spawn_injector_task(action, ctx.clone()),
+            None => {}
+        }
+    }
+
+    // MARK: - Offline bundle
+    fn render_offline_bundle(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        let imported = self.ui.bundle_panel.imported.lock().unwrap().take();
+        if let Some(result) = imported {
+            self.finish_bundle_import(result);
+        }
+        let busy = self.ui.bundle_panel.busy.load(Ordering::SeqCst);
+
+        ui.horizontal(|ui| {
+            ui.label("File:");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.ui.bundle_panel.path)
+                    .hint_text("C:\\path\\to\\bundle.tar"),
+            );
+        });
+
+        let selected = self
+            .app
+            .selected_hack
+            .as_ref()
+            .map(|hack| hack.name.clone());
+        ui.add_enabled_ui(selected.is_some(), |ui| {
+            ui.ccheckbox(
+                &mut self.ui.bundle_panel.selected_only,
+                match &selected {
+                    Some(name) => format!("Only {}", name),
+                    None => "Only the selected hack".to_string(),
+                },
+            );
+        });
+
+        ui.horizontal(|ui| {
+            ui.add_enabled_ui(!busy, |ui| {
+                if ui
+                    .cbutton("Export offline bundle")
+                    .on_hover_text("Downloads missing DLLs and injectors first")
+                    .clicked()
+                {
+                    self.spawn_bundle_export(ctx.clone());
+                }
+
+                if ui.cbutton("Import offline bundle").clicked() {
+                    self.spawn_bundle_import(ctx.clone());
+                }
+            });
+
+            let message = self.ui.bundle_panel.message.lock().unwrap().clone();
+            if busy {
+                ui.spinner();
+            }
+            ui.label(message);
+        });
+    }
+
+    fn spawn_bundle_export(&mut self, ctx: egui::Context) {
+        let hacks = match (&self.app.selected_hack, self.ui.bundle_panel.selected_only) {
+            (Some(hack), true) => vec![hack.clone()],
+            _ => self.app.hacks.clone(),
+        };
+
+        let panel = &mut self.ui.bundle_panel;
+        if panel.path.trim().is_empty() {
+            let file_name = format!(
+                "unknproject-bundle-{}.tar",
+                chrono::Local::now().format("%Y%m%d")
+            );
+            panel.path = dirs::download_dir()
+                .unwrap_or_else(|| std::path::PathBuf::from("."))
+                .join(file_name)
+                .to_string_lossy()
+                .to_string();
+        }
+
+        let destination = std::path::PathBuf::from(panel.path.trim());
+        let injectors = Arc::clone(&self.app.injectors);
+        let message = Arc::clone(&panel.message);
+        let busy = Arc::clone(&panel.busy);
+        let downloader = self.downloader();
+
+        busy.store(true, Ordering::SeqCst);
+
+        thread::spawn(move || {
+            let result = bundle::export(&downloader, &injectors, &hacks, &destination, |status| {
+                *message.lock().unwrap() = status.to_string();
+                ctx.request_repaint();
+            });
+
+            *message.lock().unwrap() = match result {
+                Ok(summary) => format!(
+                    "Exported {} hacks and {} injectors to {}.",
+                    summary.hacks,
+                    summary.injectors,
+                    summary.location.display()
+                ),
+                Err(e) => {
+                    log::error!("Failed to export offline bundle: {}", e);
+                    e
+                }
+            };
+            busy.store(false, Ordering::SeqCst);
+            ctx.request_repaint();
+        });
+    }
+
+    /// Verifying the bundle hashes every file and the catalog is refetched afterwards, so
+    /// both happen in the background on a copy of the config.
+    fn spawn_bundle_import(&mut self, ctx: egui::Context) {
+        let path = self.ui.bundle_panel.path.trim().to_string();
+        if path.is_empty() {
+            self.toasts.error("Enter the path of the bundle to import.");
+            return;
+        }
+
+        let panel = &self.ui.bundle_panel;
+        let mut config = self.app.config.clone();
+        let injectors = Arc::clone(&self.app.injectors);
+        let message = Arc::clone(&panel.message);
+        let busy = Arc::clone(&panel.busy);
+        let imported = Arc::clone(&panel.imported);
+        let downloader = self.downloader();
+
+        busy.store(true, Ordering::SeqCst);
+        *message.lock().unwrap() = "Importing bundle...".to_string();
+
+        thread::spawn(move || {
+            let published = injector_assets::published_manifest(&injectors, &downloader);
+            let result = bundle::import(
+                std::path::Path::new(&path),
+                &mut config,
+                &injectors,
+                published.as_ref(),
+            )
+            .map(|summary| {
+                *message.lock().unwrap() = "Refreshing hacks...".to_string();
+                ctx.request_repaint();
+                let hacks = hacks::Hack::fetch_catalog(&downloader, &config);
+                BundleImport { summary, hacks }
+            });
+
+            *imported.lock().unwrap() = Some(result);
+            *message.lock().unwrap() = String::new();
+            busy.store(false, Ordering::SeqCst);
+            ctx.request_repaint();
+        });
+    }
+
+    fn finish_bundle_import(&mut self, result: ImportResult) {
+        let BundleImport { summary, hacks } = match result {
+            Ok(import) => import,
+            Err(e) => {
+                log::error!("Failed to import offline bundle: {}", e);
+                self.toasts.error(e);
+                return;
+            }
+        };
+
+        // Only the new source is taken over, settings may have changed in the meantime.
+        let source = summary.location.to_string_lossy().to_string();
+        if !self.app.config.catalog_sources.contains(&source) {
+            self.app.config.catalog_sources.push(source);
+        }
+        self.app.config.save();
+
+        self.app.hacks = match hacks {
+            Ok(hacks) => {
+                self.ui.main_menu_message = default_main_menu_message();
+                hacks
+            }
+            Err(_err) => {
+                self.ui.main_menu_message = "Failed to fetch hacks.".to_string();
+                Vec::new()
+            }
+        };
+        self.app.hacks_processes = hacks::get_all_processes(&self.app.hacks);
+        self.ui.injector_panel.refreshed = None;
+        self.toasts.success(format!(
+            "Imported {} hacks and {} injectors.",
+            summary.hacks, summary.injectors
+        ));
+        if !summary.unverified_injectors.is_empty() {
+            self.toasts.warning(format!(
+                "{} doesn't match a published hash. Trust it in the settings to use it.",
+                summary.unverified_injectors.join(", ")
+            ));
+        }
+    }
+
+    fn spawn_injector_task(&mut self, action: InjectorAction, ctx: egui::Context) {
+        let panel = &self.ui.injector_panel;
+        let injectors = Arc::clone(&self.app.injectors);
+        let message = Arc::clone(&panel.message);
+        let busy = Arc::clone(&panel.busy);
+        let unpublished = Arc::clone(&panel.unpublished);
+        let downloader = self.downloader();
+
+        busy.store(true, Ordering::SeqCst);
+        *message.lock().unwrap() = "Fetching injector manifest...".to_string();
+
+        thread::spawn(move || {
+            let downloading = |arch: ProcessArch| {
+                *message.lock().unwrap() = format!("Downloading {}...", injector_file_name(arch));
+                ctx.request_repaint();
+            };
+            let installed = |arch: ProcessArch, asset: InjectorAsset| {
+                unpublished.lock().unwrap().retain(|other| *other != arch);
+                format!("Installed {} {}.", injector_file_name(arch), asset.version)
+            };
+
+            let result = match action {
+                InjectorAction::InstallUnverified(arch) => {
+                    downloading(arch);
+                    injector_assets::install_unverified(&injectors, &downloader, arch)
+                        .map(|asset| installed(arch, asset))
+                }
+                action => {
+                    let manifest = InjectorManifest::fetch(&downloader);
+                    if let Ok(manifest) = &manifest {
+                        injectors.lock().unwrap().latest = Some(manifest.clone());
+                    }
+
+                    match (action, manifest) {
+                        (InjectorAction::Install(arch), manifest) => {
+                            // Without a published hash the user has to confirm an
+                            // unverified install instead.
+                            if manifest.as_ref().map_or(true, |m| m.get(arch).is_none()) {
+                                let mut unpublished = unpublished.lock().unwrap();
+                                if !unpublished.contains(&arch) {
+                                    unpublished.push(arch);
+                                }
+                            }
+
+                            manifest.and_then(|manifest| {
+                                downloading(arch);
+                                injector_assets::install(&injectors, &downloader, arch, &manifest)
+                                    .map(|asset| installed(arch, asset))
+                            })
+                        }
+                        (_, Ok(_)) => Ok("Fetched latest injector versions.".to_string()),
+                        (_, Err(e)) => Err(e),
+                    }
+                }
+            };
+
+            *message.lock().unwrap() = match result {
+                Ok(done) => done,
+                Err(e) => {
+                    log::error!("{}", e);
+                    e
+                }
+            };
+            busy.store(false, Ordering::SeqCst);
+            ctx.request_repaint();
+        });
+
+        self.ui.injector_panel.refreshed = None;
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum InjectorAction {
+    Install(ProcessArch),
+    /// Download without a published hash, after the user confirmed it.
+    InstallUnverified(ProcessArch),
+    /// Accept the file on disk as is, after the user confirmed it.
+    Trust(ProcessArch),
+    Delete(ProcessArch),
+    CheckUpdates,
+}
+
+/// Settings-side view of the injector assets. Statuses hash the files, so they're cached.
+#[derive(Default)]
+pub struct InjectorPanel {
+    statuses: Vec<(ProcessArch, InjectorStatus)>,
+    refreshed: Option<Instant>,
+    pending_confirm: Option<InjectorAction>,
+    was_busy: bool,
+    message: Arc<Mutex<String>>,
+    busy: Arc<AtomicBool>,
+    /// Architectures whose last install found no published hash.
+    unpublished: Arc<Mutex<Vec<ProcessArch>>>,
+}
+
+impl InjectorPanel {
+    fn refresh(&mut self, injectors: &Mutex<InjectorAssets>) {
+        let assets = injectors.lock().unwrap();
+        self.statuses = INJECTOR_ARCHES
+            .into_iter()
+            .map(|arch| (arch, assets.status(arch)))
+            .collect();
+        self.refreshed = Some(Instant::now());
+    }
+}
+
+#[derive(Default)]
+pub struct BundlePanel {
+    path: String,
+    selected_only: bool,
+    message: Arc<Mutex<String>>,
+    busy: Arc<AtomicBool>,
+    /// Handed back by the import worker for the UI thread to apply.
+    imported: Arc<Mutex<Option<ImportResult>>>,
+}
+
+type ImportResult = Result<BundleImport, String>;
+
+struct BundleImport {
+    summary: bundle::BundleSummary,
+    hacks: Result<Vec<hacks::Hack>, String>,
 }
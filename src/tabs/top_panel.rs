@@ -5,6 +5,8 @@ pub enum AppTab {
     Home,
     Settings,
     About,
+    History,
+    Automation,
     Logs,
     Debug,
 }
@@ -41,6 +43,20 @@ impl MyApp {
                     "Learn more about this loader",
                     "Reading about",
                 );
+                self.render_tab(
+                    ui,
+                    AppTab::History,
+                    "History",
+                    "Browse past injections",
+                    "Viewing history",
+                );
+                self.render_tab(
+                    ui,
+                    AppTab::Automation,
+                    "Automation",
+                    "Inject automatically when games start",
+                    "Configuring automation",
+                );
                 self.render_tab(ui, AppTab::Logs, "Logs", "Check the logs", "Viewing Logs");
 
                 if ctx.input_mut(|i| i.modifiers.shift) || self.ui.tab == AppTab::Debug {
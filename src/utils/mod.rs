@@ -1,6 +1,8 @@
-pub mod config;
-pub mod downloader;
 pub mod logger;
 pub mod rpc;
-pub mod statistics;
-pub mod steam;
+
+// The loader's logic lives in unknproject-core; re-exported so the UI keeps one import path.
+pub use unknproject_core::{
+    automation, bundle, chains, config, deeplink, downloader, errors, hash, history, http,
+    injector, injector_assets, inspector, instance, modules, pe, processes, statistics, steam,
+};
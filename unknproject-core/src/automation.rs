@@ -0,0 +1,421 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    fmt, fs,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::Sender,
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::{platform, processes::is_alive, Notify};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RuleTrigger {
+    ProcessStarted { process: String },
+    ModuleLoaded { process: String, module: String },
+}
+
+impl RuleTrigger {
+    pub fn process(&self) -> &str {
+        match self {
+            RuleTrigger::ProcessStarted { process } => process,
+            RuleTrigger::ModuleLoaded { process, .. } => process,
+        }
+    }
+}
+
+impl fmt::Display for RuleTrigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleTrigger::ProcessStarted { process } => write!(f, "{} started", process),
+            RuleTrigger::ModuleLoaded { process, module } => {
+                write!(f, "{} loaded {}", process, module)
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RuleAction {
+    Hack { name: String },
+    Chain { name: String },
+}
+
+impl fmt::Display for RuleAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleAction::Hack { name } => write!(f, "{}", name),
+            RuleAction::Chain { name } => write!(f, "{} (chain)", name),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AutomationRule {
+    /// Stable across renames and reordering, used to match engine events to rules.
+    pub id: u64,
+    pub name: String,
+    pub enabled: bool,
+    pub trigger: RuleTrigger,
+    /// Wait after the trigger fires, e.g. to get past the game's splash screen.
+    pub delay_ms: u64,
+    pub actions: Vec<RuleAction>,
+}
+
+impl AutomationRule {
+    pub fn new(name: &str, process: &str) -> Self {
+        Self {
+            id: Local::now().timestamp_nanos_opt().unwrap_or_default() as u64,
+            name: name.to_string(),
+            enabled: true,
+            trigger: RuleTrigger::ProcessStarted {
+                process: process.to_string(),
+            },
+            delay_ms: 5000,
+            actions: Vec::new(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Automation {
+    pub enabled: bool,
+    pub rules: Vec<AutomationRule>,
+}
+
+impl Automation {
+    fn path() -> PathBuf {
+        let automation_dir = crate::config_dir();
+
+        fs::create_dir_all(&automation_dir).ok();
+        automation_dir.join("automation.json")
+    }
+
+    pub fn load() -> Self {
+        if let Ok(data) = fs::read_to_string(Self::path()) {
+            serde_json::from_str::<Automation>(&data).unwrap_or_default()
+        } else {
+            Automation::default()
+        }
+    }
+
+    pub fn save(&self) {
+        if let Ok(data) = serde_json::to_string_pretty(&self) {
+            fs::write(Self::path(), data).ok();
+        }
+    }
+
+    /// Rules the engine should watch right now.
+    pub fn active_rules(&self) -> Vec<AutomationRule> {
+        if !self.enabled {
+            return Vec::new();
+        }
+
+        self.rules
+            .iter()
+            .filter(|rule| rule.enabled && !rule.actions.is_empty())
+            .cloned()
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RuleLogEntry {
+    pub timestamp: DateTime<Local>,
+    pub message: String,
+}
+
+/// The most recent things a rule did, newest last.
+#[derive(Clone, Debug, Default)]
+pub struct RuleLog {
+    entries: VecDeque<RuleLogEntry>,
+}
+
+impl RuleLog {
+    const CAPACITY: usize = 50;
+
+    pub fn push(&mut self, message: impl Into<String>) {
+        if self.entries.len() == Self::CAPACITY {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(RuleLogEntry {
+            timestamp: Local::now(),
+            message: message.into(),
+        });
+    }
+
+    pub fn entries(&self) -> impl DoubleEndedIterator<Item = &RuleLogEntry> {
+        self.entries.iter()
+    }
+}
+
+/// A fired rule waiting out its delay before it injects.
+#[derive(Clone, Debug)]
+pub struct ScheduledRun {
+    pub rule_id: u64,
+    pub pid: u32,
+    pub due: Instant,
+}
+
+/// Sent by the watcher when a rule's trigger matched a process it hasn't fired for yet.
+#[derive(Clone, Debug)]
+pub struct RuleFired {
+    pub rule_id: u64,
+    pub pid: u32,
+}
+
+/// Which processes each rule has fired for. Each rule fires once per process instance,
+/// so restarting the game fires it again.
+#[derive(Default)]
+struct WatchState {
+    fired: HashSet<(u64, u32)>,
+    seen_rules: HashSet<u64>,
+}
+
+impl WatchState {
+    /// Looks for processes that match `rules` and returns the rules that fire for them.
+    fn poll(&mut self, rules: &[AutomationRule]) -> Vec<RuleFired> {
+        self.fired.retain(|(rule_id, pid)| {
+            rules.iter().any(|rule| rule.id == *rule_id) && is_alive(*pid)
+        });
+        self.seen_rules
+            .retain(|rule_id| rules.iter().any(|rule| rule.id == *rule_id));
+
+        let mut runs = Vec::new();
+        for rule in rules {
+            let pids = matching_pids(&rule.trigger);
+
+            // Processes already running when we start watching a rule didn't
+            // "start", and may well have been injected by hand already.
+            if self.seen_rules.insert(rule.id) {
+                self.fired
+                    .extend(pids.into_iter().map(|pid| (rule.id, pid)));
+                continue;
+            }
+
+            for pid in pids {
+                if self.fired.insert((rule.id, pid)) {
+                    log::info!("Automation rule {} fired for {}", rule.name, pid);
+                    runs.push(RuleFired {
+                        rule_id: rule.id,
+                        pid,
+                    });
+                }
+            }
+        }
+        runs
+    }
+}
+
+/// Background thread that polls for rule triggers until it's dropped.
+pub struct AutomationWatcher {
+    rules: Arc<Mutex<Vec<AutomationRule>>>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl AutomationWatcher {
+    const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+    pub fn spawn(rules: Vec<AutomationRule>, sender: Sender<RuleFired>, notify: Notify) -> Self {
+        let rules = Arc::new(Mutex::new(rules));
+        let stop = Arc::new(AtomicBool::new(false));
+        let watched = Arc::clone(&rules);
+        let thread_stop = Arc::clone(&stop);
+
+        let thread = thread::spawn(move || {
+            let mut state = WatchState::default();
+            while !thread_stop.load(Ordering::SeqCst) {
+                let rules = watched.lock().unwrap().clone();
+                let runs = state.poll(&rules);
+                if !runs.is_empty() {
+                    if runs.into_iter().any(|run| sender.send(run).is_err()) {
+                        return;
+                    }
+                    notify();
+                }
+
+                // Slept in steps, so dropping the watcher doesn't wait out a whole interval.
+                let next_poll = Instant::now() + Self::POLL_INTERVAL;
+                while Instant::now() < next_poll && !thread_stop.load(Ordering::SeqCst) {
+                    thread::sleep(Duration::from_millis(100));
+                }
+            }
+        });
+
+        Self {
+            rules,
+            stop,
+            thread: Some(thread),
+        }
+    }
+
+    pub fn update(&self, rules: Vec<AutomationRule>) {
+        *self.rules.lock().unwrap() = rules;
+    }
+}
+
+impl Drop for AutomationWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            thread.join().ok();
+        }
+    }
+}
+
+fn matching_pids(trigger: &RuleTrigger) -> Vec<u32> {
+    platform::processes()
+        .find_all(trigger.process())
+        .into_iter()
+        .filter(|pid| match trigger {
+            RuleTrigger::ProcessStarted { .. } => true,
+            RuleTrigger::ModuleLoaded { module, .. } => matches!(
+                platform::processes().module_by_name(*pid, module),
+                Ok(Some(_))
+            ),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::Path, sync::mpsc};
+
+    use super::*;
+    use crate::{platform::testing, processes::ProcessArch};
+
+    fn rule(id: u64, trigger: RuleTrigger) -> AutomationRule {
+        AutomationRule {
+            id,
+            name: format!("Rule {}", id),
+            enabled: true,
+            trigger,
+            delay_ms: 0,
+            actions: vec![RuleAction::Hack {
+                name: "Aim".to_string(),
+            }],
+        }
+    }
+
+    fn started(id: u64, process: &str) -> AutomationRule {
+        rule(
+            id,
+            RuleTrigger::ProcessStarted {
+                process: process.to_string(),
+            },
+        )
+    }
+
+    fn fired(runs: Vec<RuleFired>) -> Vec<(u64, u32)> {
+        runs.into_iter().map(|run| (run.rule_id, run.pid)).collect()
+    }
+
+    #[test]
+    fn only_enabled_rules_with_actions_are_active() {
+        let mut disabled = started(2, "game.exe");
+        disabled.enabled = false;
+        let mut empty = started(3, "game.exe");
+        empty.actions.clear();
+        let mut automation = Automation {
+            enabled: true,
+            rules: vec![started(1, "game.exe"), disabled, empty],
+        };
+
+        let active = automation.active_rules();
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].id, 1);
+
+        automation.enabled = false;
+        assert!(automation.active_rules().is_empty());
+    }
+
+    #[test]
+    fn rule_logs_keep_the_latest_entries() {
+        let mut log = RuleLog::default();
+        for index in 0..RuleLog::CAPACITY + 10 {
+            log.push(index.to_string());
+        }
+
+        let messages = log
+            .entries()
+            .map(|entry| entry.message.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(messages.len(), RuleLog::CAPACITY);
+        assert_eq!(messages.first().unwrap(), "10");
+        assert_eq!(
+            messages.last().unwrap(),
+            &(RuleLog::CAPACITY + 9).to_string()
+        );
+    }
+
+    #[test]
+    fn rules_fire_once_for_processes_started_after_them() {
+        let (processes, _) = testing::install();
+        processes.spawn(10, "game.exe", ProcessArch::X64, 1_000);
+        let mut state = WatchState::default();
+        let rules = vec![started(1, "game.exe")];
+
+        // Already running when the rule was first seen.
+        assert!(state.poll(&rules).is_empty());
+        assert!(state.poll(&rules).is_empty());
+
+        processes.spawn(20, "game.exe", ProcessArch::X64, 2_000);
+        assert_eq!(fired(state.poll(&rules)), vec![(1, 20)]);
+        assert!(state.poll(&rules).is_empty());
+
+        // A restart, even one that reuses the pid, fires again.
+        processes.kill(20);
+        assert!(state.poll(&rules).is_empty());
+        processes.spawn(20, "game.exe", ProcessArch::X64, 3_000);
+        assert_eq!(fired(state.poll(&rules)), vec![(1, 20)]);
+
+        // A rule added later doesn't fire for what's running already.
+        let rules = vec![started(1, "game.exe"), started(2, "game.exe")];
+        assert!(state.poll(&rules).is_empty());
+        processes.spawn(30, "game.exe", ProcessArch::X64, 4_000);
+        assert_eq!(fired(state.poll(&rules)), vec![(1, 30), (2, 30)]);
+    }
+
+    #[test]
+    fn module_rules_wait_for_the_module() {
+        let (processes, _) = testing::install();
+        let mut state = WatchState::default();
+        let rules = vec![rule(
+            1,
+            RuleTrigger::ModuleLoaded {
+                process: "game.exe".to_string(),
+                module: "client.dll".to_string(),
+            },
+        )];
+        assert!(state.poll(&rules).is_empty());
+
+        processes.spawn(10, "game.exe", ProcessArch::X64, 1_000);
+        assert!(state.poll(&rules).is_empty());
+
+        processes.load(10, Path::new("game/bin/client.dll"));
+        assert_eq!(fired(state.poll(&rules)), vec![(1, 10)]);
+        assert!(state.poll(&rules).is_empty());
+    }
+
+    #[test]
+    fn dropping_the_watcher_stops_it() {
+        let (sender, receiver) = mpsc::channel();
+        let watcher = AutomationWatcher::spawn(Vec::new(), sender, Arc::new(|| {}));
+
+        let dropped = Instant::now();
+        drop(watcher);
+        assert!(dropped.elapsed() < AutomationWatcher::POLL_INTERVAL);
+        // The thread and with it the sender are gone.
+        assert!(receiver.recv().is_err());
+    }
+}
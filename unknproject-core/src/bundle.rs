@@ -0,0 +1,649 @@
+//! Offline bundles carry a catalog, its DLLs and the manual map injectors to machines
+//! without internet access. A bundle is a plain tar archive:
+//!
+//! ```text
+//! manifest.json        format version and the sha256 of every other file
+//! Hacklist.html        catalog of the bundled hacks
+//! hacks/<file>         their DLLs
+//! injectors/<file>     unknproject.exe and unknproject64.exe, where available
+//! ```
+//!
+//! Importing checks every file against the manifest before anything is installed, then
+//! adds the catalog as a local catalog source with its DLLs already downloaded.
+
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::Read,
+    path::{Component, Path, PathBuf},
+    sync::Mutex,
+    time::SystemTime,
+};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    catalog,
+    config::Config,
+    downloader::Downloader,
+    hacks::{is_plain_file_name, Hack, HackApiResponse},
+    hash::sha256_hex,
+    injector_assets::{
+        self, injector_file_name, InjectorAssets, InjectorManifest, InjectorStatus, INJECTOR_ARCHES,
+    },
+};
+
+/// Bumped whenever the layout changes in a way older loaders can't import.
+const FORMAT: u32 = 1;
+const MANIFEST: &str = "manifest.json";
+const CATALOG: &str = "Hacklist.html";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BundleFile {
+    pub sha256: String,
+    pub size: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BundleManifest {
+    pub format: u32,
+    pub created_at: DateTime<Local>,
+    pub loader_version: String,
+    /// Every file in the archive besides the manifest, keyed by archive path.
+    pub files: BTreeMap<String, BundleFile>,
+    /// Version of each bundled injector, keyed by file name.
+    #[serde(default)]
+    pub injectors: BTreeMap<String, String>,
+    /// Injectors that weren't available when the bundle was made.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub missing_injectors: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BundleSummary {
+    pub hacks: usize,
+    pub injectors: usize,
+    /// The archive that was written, or the catalog source an import installed.
+    pub location: PathBuf,
+    /// Imported injectors that don't match a published hash and wait to be trusted.
+    pub unverified_injectors: Vec<String>,
+}
+
+fn bundles_dir() -> PathBuf {
+    crate::config_dir().join("bundles")
+}
+
+// MARK: Export
+/// Writes `hacks`, downloading any that aren't yet, and the injectors to a bundle at
+/// `destination`. Progress is passed to `on_status`.
+pub fn export(
+    downloader: &Downloader,
+    injectors: &Mutex<InjectorAssets>,
+    hacks: &[Hack],
+    destination: &Path,
+    mut on_status: impl FnMut(&str),
+) -> Result<BundleSummary, String> {
+    if hacks.is_empty() {
+        return Err("No hacks to bundle.".to_string());
+    }
+
+    let mut files = BTreeMap::<String, Vec<u8>>::new();
+    let mut entries = Vec::new();
+
+    for hack in hacks {
+        if !is_plain_file_name(&hack.file) {
+            return Err(format!(
+                "{} has an invalid file name: {}",
+                hack.name, hack.file
+            ));
+        }
+
+        if !hack.file_path.exists() {
+            on_status(&format!("Downloading {}...", hack.name));
+            hack.download(downloader, hack.file_path.to_string_lossy().to_string())?;
+        }
+
+        let data = fs::read(&hack.file_path)
+            .map_err(|e| format!("Failed to read {}: {}", hack.file_path.display(), e))?;
+        let sha256 = sha256_hex(&data);
+        let size = data.len() as u64;
+
+        let path = format!("hacks/{}", hack.file);
+        match files.get(&path) {
+            Some(existing) if sha256_hex(existing) != sha256 => {
+                return Err(format!(
+                    "Two of the selected hacks ship different files named {}.",
+                    hack.file
+                ));
+            }
+            Some(_) => {}
+            None => {
+                files.insert(path, data);
+            }
+        }
+
+        entries.push(HackApiResponse {
+            id: hack.id,
+            name: hack.name.clone(),
+            description: hack.description.clone(),
+            author: hack.author.clone(),
+            status: hack.status.clone(),
+            file: hack.file.clone(),
+            process: hack.process.clone(),
+            source: hack.source.clone(),
+            game: hack.game.clone(),
+            steam_app_id: hack.steam_app_id,
+            required_modules: hack.required_modules.clone(),
+            required_launch_options: hack.required_launch_options.clone(),
+            sha256: Some(sha256),
+            size: Some(size),
+        });
+    }
+
+    files.insert(
+        CATALOG.to_string(),
+        catalog::to_json(&entries)?.into_bytes(),
+    );
+
+    // Not every injector is published, so the bundle carries whichever ones are
+    // installed or can be downloaded and names the rest.
+    let mut versions = BTreeMap::new();
+    let mut missing_injectors = Vec::new();
+    for arch in INJECTOR_ARCHES {
+        let file_name = injector_file_name(arch);
+        on_status(&format!("Adding {}...", file_name));
+
+        let data = injector_assets::ensure(injectors, downloader, arch).and_then(|path| {
+            fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+        });
+        let data = match data {
+            Ok(data) => data,
+            Err(e) => {
+                log::warn!("Bundling without {}: {}", file_name, e);
+                missing_injectors.push(file_name.to_string());
+                continue;
+            }
+        };
+        let version = injectors
+            .lock()
+            .unwrap()
+            .get(arch)
+            .map_or_else(|| "unknown".to_string(), |asset| asset.version.clone());
+
+        files.insert(format!("injectors/{}", file_name), data);
+        versions.insert(file_name.to_string(), version);
+    }
+    let bundled_injectors = versions.len();
+
+    let manifest = BundleManifest {
+        format: FORMAT,
+        created_at: Local::now(),
+        loader_version: env!("CARGO_PKG_VERSION").to_string(),
+        files: files
+            .iter()
+            .map(|(path, data)| {
+                (
+                    path.clone(),
+                    BundleFile {
+                        sha256: sha256_hex(data),
+                        size: data.len() as u64,
+                    },
+                )
+            })
+            .collect(),
+        injectors: versions,
+        missing_injectors,
+    };
+
+    on_status("Writing bundle...");
+    write_archive(destination, &manifest, &files)?;
+    log::info!(
+        "Exported {} hacks and {} injectors to {}",
+        entries.len(),
+        bundled_injectors,
+        destination.display()
+    );
+
+    Ok(BundleSummary {
+        hacks: entries.len(),
+        injectors: bundled_injectors,
+        location: destination.to_path_buf(),
+        unverified_injectors: Vec::new(),
+    })
+}
+
+/// Writes next to `destination` first, so a failed export never leaves half a bundle.
+fn write_archive(
+    destination: &Path,
+    manifest: &BundleManifest,
+    files: &BTreeMap<String, Vec<u8>>,
+) -> Result<(), String> {
+    let partial = destination.with_extension("part");
+    let manifest = serde_json::to_vec_pretty(manifest).map_err(|e| e.to_string())?;
+    let mtime = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |now| now.as_secs());
+
+    let write = || -> std::io::Result<()> {
+        let mut builder = tar::Builder::new(File::create(&partial)?);
+        for (path, data) in [(MANIFEST, &manifest)]
+            .into_iter()
+            .chain(files.iter().map(|(path, data)| (path.as_str(), data)))
+        {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_mtime(mtime);
+            header.set_cksum();
+            builder.append_data(&mut header, path, data.as_slice())?;
+        }
+        builder.into_inner()?.sync_all()
+    };
+
+    write()
+        .and_then(|_| fs::rename(&partial, destination))
+        .map_err(|e| {
+            fs::remove_file(&partial).ok();
+            format!("Failed to write {}: {}", destination.display(), e)
+        })
+}
+
+// MARK: Import
+/// Verifies the bundle at `path` and installs it: the catalog becomes a catalog source
+/// in `config`, which the caller saves, and the injectors replace the installed ones
+/// unless they're the same. The bundle's own hashes only prove it arrived intact, so
+/// injectors are only trusted if they match `published`, the injector manifest from the
+/// CDN if it could be reached.
+pub fn import(
+    path: &Path,
+    config: &mut Config,
+    injectors: &Mutex<InjectorAssets>,
+    published: Option<&InjectorManifest>,
+) -> Result<BundleSummary, String> {
+    let files = read_archive(path)?;
+    let manifest_data = files
+        .get(MANIFEST)
+        .ok_or_else(|| format!("{} is not an offline bundle.", path.display()))?;
+    let manifest: BundleManifest = serde_json::from_slice(manifest_data)
+        .map_err(|e| format!("Invalid bundle manifest: {}", e))?;
+
+    if manifest.format > FORMAT {
+        return Err(format!(
+            "The bundle was made by a newer loader ({}), please update first.",
+            manifest.loader_version
+        ));
+    }
+    verify(&manifest, &files)?;
+
+    let entries: Vec<HackApiResponse> = files
+        .get(CATALOG)
+        .ok_or_else(|| format!("The bundle has no {}.", CATALOG))
+        .and_then(|data| {
+            serde_json::from_slice(data).map_err(|e| format!("Invalid bundled catalog: {}", e))
+        })?;
+    for entry in &entries {
+        if !is_plain_file_name(&entry.file) || !files.contains_key(&format!("hacks/{}", entry.file))
+        {
+            return Err(format!("The bundle is missing the DLL of {}.", entry.name));
+        }
+    }
+
+    // Bundles are told apart by their manifest, so importing one twice reuses its folder.
+    let dir = bundles_dir().join(&sha256_hex(manifest_data)[..12]);
+    for (name, data) in &files {
+        if name.starts_with("injectors/") {
+            continue;
+        }
+        write_file(&dir.join(name), data)?;
+    }
+
+    let catalog_path = dir.join(CATALOG);
+    let source = catalog_path.to_string_lossy().to_string();
+    for entry in &entries {
+        let hack = Hack::from_entry(entry.clone(), false).with_source(&source);
+        link_file(&dir.join("hacks").join(&entry.file), &hack.file_path)?;
+    }
+
+    let origin = path.file_name().map_or_else(
+        || path.display().to_string(),
+        |name| name.to_string_lossy().to_string(),
+    );
+    let mut installed = 0;
+    let mut unverified_injectors = Vec::new();
+    for arch in INJECTOR_ARCHES {
+        let file_name = injector_file_name(arch);
+        let Some(data) = files.get(&format!("injectors/{}", file_name)) else {
+            continue;
+        };
+
+        let sha256 = sha256_hex(data);
+        let current = {
+            let assets = injectors.lock().unwrap();
+            assets
+                .get(arch)
+                .is_some_and(|asset| asset.sha256.eq_ignore_ascii_case(&sha256))
+                && matches!(
+                    assets.status(arch),
+                    InjectorStatus::Verified | InjectorStatus::UpdateAvailable(_)
+                )
+        };
+        if !current {
+            let asset = injector_assets::install_bytes(
+                injectors,
+                arch,
+                data,
+                published.and_then(|published| published.get(arch)),
+                &format!("bundle {}", origin),
+            )?;
+            if asset.is_none() {
+                unverified_injectors.push(file_name.to_string());
+            }
+        }
+        installed += 1;
+    }
+
+    for file_name in &manifest.missing_injectors {
+        log::warn!("The bundle doesn't include {}", file_name);
+    }
+
+    if !config.catalog_sources.contains(&source) {
+        config.catalog_sources.push(source);
+    }
+    log::info!(
+        "Imported {} hacks and {} injectors from {}",
+        entries.len(),
+        installed,
+        path.display()
+    );
+
+    Ok(BundleSummary {
+        hacks: entries.len(),
+        injectors: installed,
+        location: catalog_path,
+        unverified_injectors,
+    })
+}
+
+/// Reads every file of the archive into memory, refusing links and paths that would
+/// leave the bundle folder.
+fn read_archive(path: &Path) -> Result<BTreeMap<String, Vec<u8>>, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let invalid = |e: std::io::Error| format!("{} is not a valid bundle: {}", path.display(), e);
+
+    let mut archive = tar::Archive::new(file);
+    let mut files = BTreeMap::new();
+    for entry in archive.entries().map_err(invalid)? {
+        let mut entry = entry.map_err(invalid)?;
+        let kind = entry.header().entry_type();
+        if kind.is_dir() {
+            continue;
+        }
+
+        let entry_path = entry.path().map_err(invalid)?.to_path_buf();
+        let name = entry_path
+            .components()
+            .map(|component| match component {
+                Component::Normal(part) => part.to_str(),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()
+            .filter(|parts| !parts.is_empty() && kind.is_file())
+            .map(|parts| parts.join("/"))
+            .ok_or_else(|| {
+                format!(
+                    "The bundle contains an unsafe entry: {}",
+                    entry_path.display()
+                )
+            })?;
+
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data).map_err(invalid)?;
+        if files.insert(name.clone(), data).is_some() {
+            return Err(format!("The bundle contains {} twice.", name));
+        }
+    }
+
+    Ok(files)
+}
+
+/// Every file must match the manifest, and nothing may be in the archive that it
+/// doesn't list.
+fn verify(manifest: &BundleManifest, files: &BTreeMap<String, Vec<u8>>) -> Result<(), String> {
+    for (name, expected) in &manifest.files {
+        let data = files
+            .get(name)
+            .ok_or_else(|| format!("The bundle is missing {}.", name))?;
+        let sha256 = sha256_hex(data);
+        if data.len() as u64 != expected.size || !sha256.eq_ignore_ascii_case(&expected.sha256) {
+            log::error!(
+                "Bundled {} hash mismatch: expected {}, got {}",
+                name,
+                expected.sha256,
+                sha256
+            );
+            return Err(format!("{} in the bundle does not match its hash.", name));
+        }
+    }
+
+    match files
+        .keys()
+        .find(|name| *name != MANIFEST && !manifest.files.contains_key(*name))
+    {
+        Some(name) => Err(format!("{} is not listed in the bundle manifest.", name)),
+        None => Ok(()),
+    }
+}
+
+/// Installs a bundled DLL where its catalog source keeps downloads. A hard link shares the
+/// data with the bundle folder; file systems without them get a copy.
+fn link_file(bundled: &Path, installed: &Path) -> Result<(), String> {
+    if let Some(parent) = installed.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    fs::remove_file(installed).ok();
+
+    fs::hard_link(bundled, installed)
+        .or_else(|e| {
+            log::debug!("Copying {} instead of linking it: {}", bundled.display(), e);
+            fs::copy(bundled, installed).map(|_| ())
+        })
+        .map_err(|e| format!("Failed to write {}: {}", installed.display(), e))
+}
+
+fn write_file(path: &Path, data: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    fs::write(path, data).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use super::*;
+
+    fn archive_path(name: &str) -> PathBuf {
+        env::temp_dir().join(format!("unknproject-bundle-{}-{}.tar", name, process::id()))
+    }
+
+    fn manifest(files: &BTreeMap<String, Vec<u8>>) -> BundleManifest {
+        BundleManifest {
+            format: FORMAT,
+            created_at: Local::now(),
+            loader_version: "1.0.0".to_string(),
+            files: files
+                .iter()
+                .map(|(path, data)| {
+                    (
+                        path.clone(),
+                        BundleFile {
+                            sha256: sha256_hex(data),
+                            size: data.len() as u64,
+                        },
+                    )
+                })
+                .collect(),
+            injectors: BTreeMap::new(),
+            missing_injectors: Vec::new(),
+        }
+    }
+
+    fn sample() -> BTreeMap<String, Vec<u8>> {
+        BTreeMap::from([
+            (CATALOG.to_string(), b"[]".to_vec()),
+            ("hacks/hack.dll".to_string(), vec![0x4d, 0x5a, 1, 2, 3]),
+        ])
+    }
+
+    /// Writes entries with their names as given, which `tar::Builder` would refuse.
+    fn raw_archive(path: &Path, entries: &[(&str, tar::EntryType, &[u8])]) {
+        let mut builder = tar::Builder::new(File::create(path).unwrap());
+        for (name, kind, data) in entries {
+            let mut header = tar::Header::new_gnu();
+            let raw = &mut header.as_gnu_mut().unwrap().name;
+            raw[..name.len()].copy_from_slice(name.as_bytes());
+            header.set_entry_type(*kind);
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append(&header, *data).unwrap();
+        }
+        builder.finish().unwrap();
+    }
+
+    #[test]
+    fn archives_round_trip() {
+        let path = archive_path("round-trip");
+        let files = sample();
+        let manifest = manifest(&files);
+        write_archive(&path, &manifest, &files).unwrap();
+        assert!(!path.with_extension("part").exists());
+
+        let read = read_archive(&path).unwrap();
+        let read_manifest: BundleManifest = serde_json::from_slice(&read[MANIFEST]).unwrap();
+        assert_eq!(read_manifest.files, manifest.files);
+        assert_eq!(read["hacks/hack.dll"], files["hacks/hack.dll"]);
+        assert_eq!(read.len(), files.len() + 1);
+        assert!(verify(&read_manifest, &read).is_ok());
+
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn verify_rejects_changed_missing_and_unlisted_files() {
+        let files = sample();
+        let manifest = manifest(&files);
+
+        let mut changed = files.clone();
+        changed.insert("hacks/hack.dll".to_string(), vec![0x4d, 0x5a, 1, 2, 4]);
+        assert!(verify(&manifest, &changed)
+            .unwrap_err()
+            .contains("does not match its hash"));
+
+        let mut missing = files.clone();
+        missing.remove("hacks/hack.dll");
+        assert!(verify(&manifest, &missing)
+            .unwrap_err()
+            .contains("missing hacks/hack.dll"));
+
+        let mut unlisted = files.clone();
+        unlisted.insert("hacks/extra.dll".to_string(), Vec::new());
+        assert!(verify(&manifest, &unlisted)
+            .unwrap_err()
+            .contains("not listed"));
+    }
+
+    #[test]
+    fn unsafe_entries_are_refused() {
+        let path = archive_path("unsafe");
+        let regular = tar::EntryType::Regular;
+
+        for entries in [
+            vec![("../escape.dll", regular, &b"x"[..])],
+            vec![("/etc/escape.dll", regular, &b"x"[..])],
+            vec![("hacks/link.dll", tar::EntryType::Symlink, &b""[..])],
+        ] {
+            raw_archive(&path, &entries);
+            let error = read_archive(&path).unwrap_err();
+            assert!(error.contains("unsafe entry"), "{}", error);
+        }
+
+        raw_archive(
+            &path,
+            &[
+                ("hacks/hack.dll", regular, b"a"),
+                ("hacks/hack.dll", regular, b"b"),
+            ],
+        );
+        assert!(read_archive(&path).unwrap_err().contains("twice"));
+
+        raw_archive(&path, &[("hacks/", tar::EntryType::Directory, b"")]);
+        assert!(read_archive(&path).unwrap().is_empty());
+
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn bundled_injectors_are_only_trusted_if_published() {
+        use crate::{injector_assets::RemoteInjector, platform::testing, processes::ProcessArch};
+
+        testing::install_config_dir();
+        let path = archive_path("injectors");
+        let injector = b"MZ bundled injector".to_vec();
+        let mut files = sample();
+        files.insert("injectors/unknproject.exe".to_string(), injector.clone());
+        write_archive(&path, &manifest(&files), &files).unwrap();
+        let injectors = Mutex::new(InjectorAssets::default());
+        let mut config = Config::default();
+
+        let summary = import(&path, &mut config, &injectors, None).unwrap();
+        assert_eq!(summary.injectors, 1);
+        assert_eq!(summary.unverified_injectors, vec!["unknproject.exe"]);
+        assert_eq!(
+            injectors.lock().unwrap().status(ProcessArch::X86),
+            InjectorStatus::Unverified
+        );
+        assert!(config
+            .catalog_sources
+            .contains(&summary.location.to_string_lossy().to_string()));
+
+        let published = InjectorManifest {
+            x86: Some(RemoteInjector {
+                version: "1.0.0".to_string(),
+                sha256: sha256_hex(&injector),
+            }),
+            x64: None,
+        };
+        let summary = import(&path, &mut config, &injectors, Some(&published)).unwrap();
+        assert!(summary.unverified_injectors.is_empty());
+        let assets = injectors.lock().unwrap();
+        assert_eq!(assets.status(ProcessArch::X86), InjectorStatus::Verified);
+        assert_eq!(assets.get(ProcessArch::X86).unwrap().version, "1.0.0");
+
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn bundled_dlls_are_installed_once() {
+        let dir = env::temp_dir().join(format!("unknproject-bundle-link-{}", process::id()));
+        let bundled = dir.join("bundle/hacks/hack.dll");
+        let installed = dir.join("sources/hack.dll");
+        write_file(&bundled, b"new").unwrap();
+        write_file(&installed, b"old").unwrap();
+
+        link_file(&bundled, &installed).unwrap();
+        assert_eq!(fs::read(&installed).unwrap(), b"new");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            let (bundled, installed) = (fs::metadata(&bundled), fs::metadata(&installed));
+            assert_eq!(bundled.unwrap().ino(), installed.unwrap().ino());
+        }
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,586 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use serde_json::{Map, Value};
+
+use crate::{hacks::HackApiResponse, hash::sha256_file};
+
+/// Fields every entry needs for the loader to parse it.
+const REQUIRED_FIELDS: [&str; 9] = [
+    "id",
+    "name",
+    "description",
+    "author",
+    "status",
+    "process",
+    "file",
+    "source",
+    "game",
+];
+
+const OPTIONAL_FIELDS: [&str; 5] = [
+    "steam_app_id",
+    "required_modules",
+    "required_launch_options",
+    "sha256",
+    "size",
+];
+
+/// Games the loader knows how to group and inject into. CSS entries carry their
+/// version after the name, e.g. `CSS v34`.
+fn is_known_game(game: &str) -> bool {
+    matches!(game, "CS:GO" | "CS2") || game == "CSS" || game.starts_with("CSS ")
+}
+
+/// DLLs referenced by a catalog next to `resources/Hacklist.html` live in `resources/hacks/`.
+pub fn default_hacks_dir(catalog: &Path) -> PathBuf {
+    catalog
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join("hacks")
+}
+
+// MARK: Lint
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Clone, Debug)]
+pub struct LintIssue {
+    pub severity: Severity,
+    /// `#<index> <name>` of the offending entry, `None` for problems with the whole file.
+    pub entry: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for LintIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        match &self.entry {
+            Some(entry) => write!(f, "{}: {}: {}", severity, entry, self.message),
+            None => write!(f, "{}: {}", severity, self.message),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Linter {
+    issues: Vec<LintIssue>,
+    entry: Option<String>,
+}
+
+impl Linter {
+    fn error(&mut self, message: impl Into<String>) {
+        self.push(Severity::Error, message.into());
+    }
+
+    fn warning(&mut self, message: impl Into<String>) {
+        self.push(Severity::Warning, message.into());
+    }
+
+    fn push(&mut self, severity: Severity, message: String) {
+        self.issues.push(LintIssue {
+            severity,
+            entry: self.entry.clone(),
+            message,
+        });
+    }
+}
+
+pub fn lint_file(catalog: &Path, hacks_dir: &Path) -> Result<Vec<LintIssue>, String> {
+    let data = fs::read_to_string(catalog)
+        .map_err(|e| format!("Failed to read {}: {}", catalog.display(), e))?;
+
+    match serde_json::from_str::<Value>(&data) {
+        Ok(value) => Ok(lint(&value, hacks_dir)),
+        Err(e) => Ok(vec![LintIssue {
+            severity: Severity::Error,
+            entry: None,
+            message: format!("Not valid JSON: {}", e),
+        }]),
+    }
+}
+
+/// Checks a parsed catalog for everything that would only show up once a user's loader fails.
+pub fn lint(catalog: &Value, hacks_dir: &Path) -> Vec<LintIssue> {
+    let mut linter = Linter::default();
+
+    let Some(entries) = catalog.as_array() else {
+        linter.error("The catalog must be a JSON array of entries.");
+        return linter.issues;
+    };
+    if entries.is_empty() {
+        linter.error("The catalog is empty, the loader refuses to start with it.");
+    }
+
+    let mut ids: HashMap<u64, usize> = HashMap::new();
+    let mut names: HashMap<String, usize> = HashMap::new();
+
+    for (index, entry) in entries.iter().enumerate() {
+        let name = entry.get("name").and_then(Value::as_str).unwrap_or("?");
+        linter.entry = Some(format!("#{} {}", index + 1, name));
+
+        let Some(fields) = entry.as_object() else {
+            linter.error("Entry is not an object.");
+            continue;
+        };
+
+        lint_fields(&mut linter, fields);
+
+        if let Some(id) = fields.get("id").and_then(Value::as_u64) {
+            if let Some(first) = ids.insert(id, index) {
+                linter.error(format!("Duplicate id {} (also #{}).", id, first + 1));
+            }
+        }
+
+        // Names are matched case-insensitively once "lowercase hacks" is on.
+        if let Some(name) = fields.get("name").and_then(Value::as_str) {
+            if let Some(first) = names.insert(name.to_lowercase(), index) {
+                linter.error(format!("Duplicate name {} (also #{}).", name, first + 1));
+            }
+        }
+
+        if let Some(game) = fields.get("game").and_then(Value::as_str) {
+            if !is_known_game(game) {
+                linter.warning(format!("Unknown game {}.", game));
+            }
+        }
+
+        if let Some(process) = fields.get("process").and_then(Value::as_str) {
+            if !process.to_lowercase().ends_with(".exe") {
+                linter.warning(format!("Process {} doesn't end in .exe.", process));
+            }
+        }
+
+        if let Some(source) = fields.get("source").and_then(Value::as_str) {
+            if let Err(e) = check_url(source) {
+                linter.error(format!("Invalid source URL {}: {}", source, e));
+            }
+        }
+
+        if let Some(file) = fields.get("file").and_then(Value::as_str) {
+            lint_file_reference(&mut linter, fields, file, hacks_dir);
+        }
+    }
+
+    linter.issues
+}
+
+fn lint_fields(linter: &mut Linter, fields: &Map<String, Value>) {
+    for field in REQUIRED_FIELDS {
+        match fields.get(field) {
+            None => linter.error(format!("Missing field {}.", field)),
+            Some(Value::Number(id)) if field == "id" => {
+                if id.as_u64().is_none_or(|id| id > u32::MAX as u64) {
+                    linter.error(format!("Field id must be a positive integer, got {}.", id));
+                }
+            }
+            Some(value) if field == "id" => {
+                linter.error(format!("Field id must be a number, got {}.", value));
+            }
+            Some(Value::String(text)) if text.trim().is_empty() => {
+                linter.warning(format!("Field {} is empty.", field));
+            }
+            Some(Value::String(_)) => {}
+            Some(value) => {
+                linter.error(format!("Field {} must be a string, got {}.", field, value))
+            }
+        }
+    }
+
+    for (field, value) in fields {
+        if REQUIRED_FIELDS.contains(&field.as_str()) {
+            continue;
+        }
+        if !OPTIONAL_FIELDS.contains(&field.as_str()) {
+            linter.warning(format!("Unknown field {}, the loader ignores it.", field));
+            continue;
+        }
+
+        let valid = match field.as_str() {
+            "steam_app_id" | "size" => value.is_u64(),
+            "sha256" => value.as_str().is_some_and(|hash| {
+                hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit())
+            }),
+            _ => value
+                .as_array()
+                .is_some_and(|items| items.iter().all(Value::is_string)),
+        };
+        if !valid {
+            linter.error(format!("Field {} has an invalid value {}.", field, value));
+        }
+    }
+}
+
+fn lint_file_reference(
+    linter: &mut Linter,
+    fields: &Map<String, Value>,
+    file: &str,
+    hacks_dir: &Path,
+) {
+    if file.contains(['/', '\\']) {
+        linter.error(format!("File {} must be a plain file name.", file));
+        return;
+    }
+    if !file.to_lowercase().ends_with(".dll") {
+        linter.warning(format!("File {} doesn't end in .dll.", file));
+    }
+
+    let path = hacks_dir.join(file);
+    if !path.is_file() {
+        linter.error(format!("File {} is not in {}.", file, hacks_dir.display()));
+        return;
+    }
+
+    if let Some(expected) = fields.get("size").and_then(Value::as_u64) {
+        match fs::metadata(&path) {
+            Ok(metadata) if metadata.len() != expected => linter.error(format!(
+                "Size of {} is {} bytes, the catalog says {}.",
+                file,
+                metadata.len(),
+                expected
+            )),
+            Ok(_) => {}
+            Err(e) => linter.error(format!("Failed to read {}: {}", file, e)),
+        }
+    }
+
+    if let Some(expected) = fields.get("sha256").and_then(Value::as_str) {
+        match sha256_file(&path) {
+            Ok(sha256) if !sha256.eq_ignore_ascii_case(expected) => linter.error(format!(
+                "SHA-256 of {} is {}, the catalog says {}.",
+                file, sha256, expected
+            )),
+            Ok(_) => {}
+            Err(e) => linter.error(e),
+        }
+    }
+}
+
+/// Minimal http(s) URL check; the catalog only links to websites and Telegram.
+fn check_url(url: &str) -> Result<(), &'static str> {
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .ok_or("must start with http:// or https://")?;
+
+    if url.chars().any(char::is_whitespace) {
+        return Err("contains whitespace");
+    }
+
+    let host = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = host.rsplit('@').next().unwrap_or_default();
+    let host = host.split(':').next().unwrap_or_default();
+    if host.is_empty() || !host.contains('.') || host.starts_with('.') || host.ends_with('.') {
+        return Err("has no valid host");
+    }
+
+    Ok(())
+}
+
+// MARK: Build
+/// Assembles a catalog from one TOML file per entry, ordered by id. Hashes and sizes are
+/// filled in from the DLLs in `hacks_dir` and override whatever the TOML says.
+pub fn build(entries_dir: &Path, hacks_dir: &Path) -> Result<Vec<HackApiResponse>, String> {
+    let mut paths = fs::read_dir(entries_dir)
+        .map_err(|e| format!("Failed to read {}: {}", entries_dir.display(), e))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "toml")
+        })
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    if paths.is_empty() {
+        return Err(format!("No .toml entries in {}.", entries_dir.display()));
+    }
+
+    let mut entries = Vec::new();
+    for path in paths {
+        let data = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let mut entry: HackApiResponse = toml::from_str(&data)
+            .map_err(|e| format!("Invalid entry {}: {}", path.display(), e))?;
+
+        // Missing files are left for the lint that runs on the result.
+        let file = hacks_dir.join(&entry.file);
+        if let Ok(metadata) = fs::metadata(&file) {
+            entry.size = Some(metadata.len());
+            entry.sha256 = Some(sha256_file(&file)?);
+        }
+
+        entries.push(entry);
+    }
+
+    entries.sort_by_key(|entry| entry.id);
+    Ok(entries)
+}
+
+/// Serializes like the hand-written `Hacklist.html`: pretty printed with four spaces.
+pub fn to_json(entries: &[HackApiResponse]) -> Result<String, String> {
+    let mut data = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
+    let mut serializer = serde_json::Serializer::with_formatter(&mut data, formatter);
+    serde::Serialize::serialize(entries, &mut serializer).map_err(|e| e.to_string())?;
+    data.push(b'\n');
+    String::from_utf8(data).map_err(|e| e.to_string())
+}
+
+// MARK: Diff
+pub enum CatalogChange {
+    Added(HackApiResponse),
+    Removed(HackApiResponse),
+    Changed {
+        id: u32,
+        name: String,
+        /// Field name, old value and new value.
+        fields: Vec<(String, Value, Value)>,
+    },
+    /// Several entries of one version share an id, so they can't be compared.
+    DuplicateId {
+        id: u32,
+        /// Whether the duplicates are in the new version rather than the old one.
+        in_new: bool,
+        names: Vec<String>,
+    },
+}
+
+impl fmt::Display for CatalogChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CatalogChange::Added(entry) => write!(f, "+ #{} {}", entry.id, entry.name),
+            CatalogChange::Removed(entry) => write!(f, "- #{} {}", entry.id, entry.name),
+            CatalogChange::Changed { id, name, fields } => {
+                write!(f, "~ #{} {}", id, name)?;
+                for (field, old, new) in fields {
+                    write!(f, "\n    {}: {} -> {}", field, old, new)?;
+                }
+                Ok(())
+            }
+            CatalogChange::DuplicateId { id, in_new, names } => {
+                write!(
+                    f,
+                    "! #{} is used by {} entries in the {} catalog: {}",
+                    id,
+                    names.len(),
+                    if *in_new { "new" } else { "old" },
+                    names.join(", ")
+                )
+            }
+        }
+    }
+}
+
+pub fn load(catalog: &Path) -> Result<Vec<HackApiResponse>, String> {
+    let data = fs::read_to_string(catalog)
+        .map_err(|e| format!("Failed to read {}: {}", catalog.display(), e))?;
+    serde_json::from_str(&data).map_err(|e| format!("Invalid catalog {}: {}", catalog.display(), e))
+}
+
+/// Compares two catalog versions entry by entry, matching entries by id. Ids used more
+/// than once are reported as such instead of being compared.
+pub fn diff(old: &[HackApiResponse], new: &[HackApiResponse]) -> Vec<CatalogChange> {
+    let mut changes = Vec::new();
+    let old_by_id = by_id(old, false, &mut changes);
+    let new_by_id = by_id(new, true, &mut changes);
+    let duplicated = |id: &u32| {
+        changes.iter().any(|change| {
+            matches!(change, CatalogChange::DuplicateId { id: duplicate, .. } if duplicate == id)
+        })
+    };
+
+    let mut compared = Vec::new();
+    for (id, old_entry) in &old_by_id {
+        if duplicated(id) {
+            continue;
+        }
+        match new_by_id.get(id) {
+            None => compared.push(CatalogChange::Removed((*old_entry).clone())),
+            Some(new_entry) if old_entry != new_entry => {
+                compared.push(CatalogChange::Changed {
+                    id: *id,
+                    name: new_entry.name.clone(),
+                    fields: changed_fields(old_entry, new_entry),
+                });
+            }
+            Some(_) => {}
+        }
+    }
+    for (id, new_entry) in &new_by_id {
+        if !duplicated(id) && !old_by_id.contains_key(id) {
+            compared.push(CatalogChange::Added((*new_entry).clone()));
+        }
+    }
+
+    changes.extend(compared);
+    changes
+}
+
+fn by_id<'a>(
+    entries: &'a [HackApiResponse],
+    in_new: bool,
+    changes: &mut Vec<CatalogChange>,
+) -> BTreeMap<u32, &'a HackApiResponse> {
+    let mut by_id = BTreeMap::new();
+    let mut duplicates = BTreeMap::<u32, Vec<String>>::new();
+    for entry in entries {
+        if let Some(first) = by_id.insert(entry.id, entry) {
+            duplicates
+                .entry(entry.id)
+                .or_insert_with(|| vec![first.name.clone()])
+                .push(entry.name.clone());
+        }
+    }
+
+    changes.extend(
+        duplicates
+            .into_iter()
+            .map(|(id, names)| CatalogChange::DuplicateId { id, in_new, names }),
+    );
+    by_id
+}
+
+fn changed_fields(old: &HackApiResponse, new: &HackApiResponse) -> Vec<(String, Value, Value)> {
+    let to_map = |entry: &HackApiResponse| match serde_json::to_value(entry) {
+        Ok(Value::Object(map)) => map,
+        _ => Map::new(),
+    };
+    let (old, new) = (to_map(old), to_map(new));
+
+    REQUIRED_FIELDS
+        .iter()
+        .chain(OPTIONAL_FIELDS.iter())
+        .filter_map(|field| {
+            let old = old.get(*field).cloned().unwrap_or(Value::Null);
+            let new = new.get(*field).cloned().unwrap_or(Value::Null);
+            (old != new).then(|| (field.to_string(), old, new))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use serde_json::json;
+
+    use super::*;
+
+    fn entry(id: u32, name: &str) -> HackApiResponse {
+        serde_json::from_value(json!({
+            "id": id,
+            "name": name,
+            "description": "Desc",
+            "author": "a",
+            "status": "working",
+            "process": "game.exe",
+            "file": format!("{}.dll", name.to_lowercase()),
+            "source": "https://example.com/",
+            "game": "CS2",
+        }))
+        .unwrap()
+    }
+
+    fn hacks_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("unknproject-lint-{}-{}", name, process::id()));
+        fs::remove_dir_all(&dir).ok();
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn messages(issues: &[LintIssue]) -> Vec<String> {
+        issues.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn clean_catalogs_pass_lint() {
+        let dir = hacks_dir("clean");
+        fs::write(dir.join("aim.dll"), b"MZ").unwrap();
+        let mut aim = serde_json::to_value(entry(1, "Aim")).unwrap();
+        aim["sha256"] = json!(crate::hash::sha256_hex(b"MZ"));
+        aim["size"] = json!(2);
+
+        assert!(messages(&lint(&json!([aim]), &dir)).is_empty());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn lint_reports_broken_entries() {
+        let dir = hacks_dir("broken");
+        fs::write(dir.join("aim.dll"), b"MZ").unwrap();
+        let mut aim = serde_json::to_value(entry(1, "Aim")).unwrap();
+        aim["sha256"] = json!("0".repeat(64));
+        let mut copy = serde_json::to_value(entry(1, "aim")).unwrap();
+        copy["source"] = json!("ftp://example.com");
+        copy["game"] = json!("Quake");
+        copy["extra"] = json!(true);
+
+        let issues = messages(&lint(&json!([aim, copy, "text"]), &dir));
+        for expected in [
+            "error: #1 Aim: SHA-256 of aim.dll is",
+            "error: #2 aim: Duplicate id 1 (also #1).",
+            "error: #2 aim: Duplicate name aim (also #1).",
+            "error: #2 aim: Invalid source URL ftp://example.com",
+            "warning: #2 aim: Unknown game Quake.",
+            "warning: #2 aim: Unknown field extra",
+            "error: #3 ?: Entry is not an object.",
+        ] {
+            assert!(
+                issues.iter().any(|issue| issue.starts_with(expected)),
+                "{} not in {:?}",
+                expected,
+                issues
+            );
+        }
+
+        let issues = messages(&lint(&json!({}), &dir));
+        assert_eq!(
+            issues,
+            ["error: The catalog must be a JSON array of entries."]
+        );
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn diffs_entries_by_id() {
+        let mut changed = entry(2, "Esp");
+        changed.status = "detected".to_string();
+        let changes = diff(
+            &[entry(1, "Aim"), entry(2, "Esp")],
+            &[changed, entry(3, "Bhop")],
+        );
+        let changes = changes.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+        assert_eq!(
+            changes,
+            [
+                "- #1 Aim",
+                "~ #2 Esp\n    status: \"working\" -> \"detected\"",
+                "+ #3 Bhop",
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_reports_duplicate_ids() {
+        let changes = diff(
+            &[entry(1, "Aim"), entry(2, "Esp")],
+            &[entry(1, "Aim"), entry(2, "Esp"), entry(2, "Bhop")],
+        );
+        let changes = changes.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+        assert_eq!(
+            changes,
+            ["! #2 is used by 2 entries in the new catalog: Esp, Bhop"]
+        );
+    }
+}
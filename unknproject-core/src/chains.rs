@@ -0,0 +1,171 @@
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+
+use crate::hacks::Hack;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ChainTarget {
+    Catalog {
+        /// Catalog id, which survives renames. Chains saved before it was stored go by
+        /// `name` alone.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        id: Option<u32>,
+        /// Catalog source the id belongs to, ids are only unique within one.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        source: Option<String>,
+        /// Shown in the chain and used to find hacks without an id, or whose id is ambiguous.
+        name: String,
+    },
+    Local {
+        path: PathBuf,
+    },
+}
+
+impl ChainTarget {
+    /// A step injecting `hack`. Catalogs that leave out ids give every hack 0, so that's
+    /// not kept.
+    pub fn catalog(hack: &Hack) -> Self {
+        ChainTarget::Catalog {
+            id: (hack.id != 0).then_some(hack.id),
+            source: Some(hack.source.clone()),
+            name: hack.name.clone(),
+        }
+    }
+}
+
+impl fmt::Display for ChainTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChainTarget::Catalog { name, .. } => write!(f, "{}", name),
+            ChainTarget::Local { path } => write!(
+                f,
+                "{} (local)",
+                path.file_name().unwrap_or_default().to_string_lossy()
+            ),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ChainStep {
+    pub target: ChainTarget,
+    /// Wait before running this step, e.g. to let a helper runtime initialize.
+    pub delay_ms: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct InjectionChain {
+    pub name: String,
+    /// Target for local DLL steps; catalog steps use their own process.
+    pub process: String,
+    pub stop_on_failure: bool,
+    pub steps: Vec<ChainStep>,
+}
+
+impl InjectionChain {
+    pub fn new(name: &str, process: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            process: process.to_string(),
+            stop_on_failure: true,
+            steps: Vec::new(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Chains {
+    pub chains: Vec<InjectionChain>,
+}
+
+impl Chains {
+    fn path() -> PathBuf {
+        let chains_dir = crate::config_dir();
+
+        fs::create_dir_all(&chains_dir).ok();
+        chains_dir.join("chains.json")
+    }
+
+    pub fn load() -> Self {
+        Self::load_from(&Self::path())
+    }
+
+    /// A file that doesn't parse is moved aside rather than overwritten by the next save.
+    fn load_from(path: &Path) -> Self {
+        let Ok(data) = fs::read_to_string(path) else {
+            return Chains::default();
+        };
+
+        match serde_json::from_str::<Chains>(&data) {
+            Ok(chains) => chains,
+            Err(e) => {
+                log::error!("Failed to parse {}: {}", path.display(), e);
+                let backup = path
+                    .with_extension(format!("json.{}.bak", Local::now().format("%Y%m%d%H%M%S")));
+                match fs::rename(path, &backup) {
+                    Ok(()) => log::warn!("Moved the unreadable chains to {}", backup.display()),
+                    Err(e) => log::error!("Failed to back up {}: {}", path.display(), e),
+                }
+                Chains::default()
+            }
+        }
+    }
+
+    pub fn save(&self) {
+        if let Ok(data) = serde_json::to_string_pretty(&self) {
+            fs::write(Self::path(), data).ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use super::*;
+
+    #[test]
+    fn catalog_steps_without_an_id_still_load() {
+        let step: ChainStep =
+            serde_json::from_str(r#"{"target":{"kind":"catalog","name":"Aim"},"delay_ms":0}"#)
+                .unwrap();
+        assert_eq!(
+            step.target,
+            ChainTarget::Catalog {
+                id: None,
+                source: None,
+                name: "Aim".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn unreadable_chains_are_backed_up() {
+        let dir = env::temp_dir().join(format!("unknproject-chains-{}", process::id()));
+        fs::remove_dir_all(&dir).ok();
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("chains.json");
+        fs::write(&path, "{\"chains\": [").unwrap();
+
+        assert!(Chains::load_from(&path).chains.is_empty());
+        assert!(!path.exists());
+        let backups = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(backups.len(), 1);
+        assert!(backups[0].starts_with("chains.json.") && backups[0].ends_with(".bak"));
+        assert_eq!(
+            fs::read_to_string(dir.join(&backups[0])).unwrap(),
+            "{\"chains\": ["
+        );
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
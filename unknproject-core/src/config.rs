@@ -0,0 +1,178 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Config {
+    pub favorites: HashSet<String>,
+    pub show_only_favorites: bool,
+    /// Premultiplied sRGBA, the layout egui stores colors in, so existing configs still load.
+    pub favorites_color: [u8; 4],
+    pub automatically_select_hack: bool,
+    pub skip_injects_delay: bool,
+    pub lowercase_hacks: bool,
+    pub api_endpoint: String,
+    pub cdn_endpoint: String,
+    pub cdn_fallback_endpoint: String,
+    pub hide_steam_account: bool,
+    pub hide_statistics: bool,
+    pub disable_notifications: bool,
+    pub disable_rpc: bool,
+    pub selected_hack: String,
+    pub log_level: log::Level,
+    #[serde(default = "default_injector_timeout_secs")]
+    pub injector_timeout_secs: u64,
+    #[serde(default = "default_health_check")]
+    pub health_check: bool,
+    #[serde(default = "default_health_check_grace_secs")]
+    pub health_check_grace_secs: u64,
+    /// Extra Steam launch options per app id, used by "Launch & Inject".
+    #[serde(default)]
+    pub launch_options: HashMap<u32, String>,
+    #[serde(default = "default_launch_timeout_secs")]
+    pub launch_timeout_secs: u64,
+    /// Catalogs added on top of `api_endpoint`, e.g. through `unknproject://add-source` links.
+    #[serde(default)]
+    pub catalog_sources: Vec<String>,
+    /// Serves the control API on 127.0.0.1 while the window is open.
+    #[serde(default)]
+    pub api_enabled: bool,
+    #[serde(default = "default_api_port")]
+    pub api_port: u16,
+}
+
+fn default_favorites_color() -> [u8; 4] {
+    // egui's GOLD
+    [255, 215, 0, 255]
+}
+
+pub fn default_api_endpoint() -> String {
+    "https://raw.githubusercontent.com/Unkn0Wms/UnknProject/refs/heads/main/resources/Hacklist.html".to_string()
+}
+
+pub fn default_cdn_endpoint() -> String {
+    "https://raw.githubusercontent.com/Unkn0Wms/UnknProject/refs/heads/main/resources/hacks/".to_string()
+}
+
+pub fn default_cdn_fallback_endpoint() -> String {
+    "https://raw.githubusercontent.com/Unkn0Wms/UnknProject/refs/heads/main/resources/hacks/".to_string()
+}
+
+pub fn default_log_level() -> log::Level {
+    log::Level::Info
+}
+
+pub fn default_injector_timeout_secs() -> u64 {
+    30
+}
+
+pub fn default_health_check() -> bool {
+    false
+}
+
+pub fn default_health_check_grace_secs() -> u64 {
+    3
+}
+
+pub fn default_launch_timeout_secs() -> u64 {
+    180
+}
+
+pub fn default_api_port() -> u16 {
+    7787
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        // default config
+        Config {
+            favorites: HashSet::new(),
+            show_only_favorites: false,
+            favorites_color: default_favorites_color(),
+            automatically_select_hack: true,
+            skip_injects_delay: false,
+            lowercase_hacks: true,
+            api_endpoint: default_api_endpoint(),
+            cdn_endpoint: default_cdn_endpoint(),
+            cdn_fallback_endpoint: default_cdn_fallback_endpoint(),
+            hide_steam_account: false,
+            hide_statistics: false,
+            disable_notifications: false,
+            disable_rpc: false,
+            selected_hack: "".to_string(),
+            log_level: default_log_level(),
+            injector_timeout_secs: default_injector_timeout_secs(),
+            health_check: default_health_check(),
+            health_check_grace_secs: default_health_check_grace_secs(),
+            launch_options: HashMap::new(),
+            launch_timeout_secs: default_launch_timeout_secs(),
+            catalog_sources: Vec::new(),
+            api_enabled: false,
+            api_port: default_api_port(),
+        }
+    }
+}
+
+impl Config {
+    pub fn load() -> Self {
+        let config_dir = crate::config_dir();
+
+        fs::create_dir_all(&config_dir).ok();
+        let config_path = config_dir.join("config.json");
+
+        let mut config = if let Ok(data) = fs::read_to_string(&config_path) {
+            serde_json::from_str::<Config>(&data).unwrap_or_default()
+        } else {
+            Config::default()
+        };
+
+        if let Err(e) = config.validate() {
+            log::warn!("{} Using the default injector timeout.", e);
+            config.injector_timeout_secs = default_injector_timeout_secs();
+        }
+        config
+    }
+
+    /// Rejects values the loader can't work with, e.g. from a hand-edited config.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.injector_timeout_secs == 0 {
+            return Err("The injector timeout must be at least 1 second.".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn save(&self) {
+        let config_dir = crate::config_dir();
+
+        fs::create_dir_all(&config_dir).ok();
+        let config_path = config_dir.join("config.json");
+
+        if let Ok(data) = serde_json::to_string(&self) {
+            fs::write(config_path, data).ok();
+        }
+    }
+
+    pub fn reset(&mut self) {
+        *self = Config::default();
+        self.save();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_injector_timeout_is_rejected() {
+        assert!(Config::default().validate().is_ok());
+
+        let config = Config {
+            injector_timeout_secs: 0,
+            ..Config::default()
+        };
+        assert!(config.validate().is_err());
+    }
+}
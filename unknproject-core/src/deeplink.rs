@@ -0,0 +1,237 @@
+use std::fmt;
+
+pub const SCHEME: &str = "unknproject";
+
+/// An `unknproject://` link, e.g. from a button on a hack's web page.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DeepLink {
+    /// `unknproject://select/<id>`
+    Select(u32),
+    /// `unknproject://inject/<id>`
+    Inject(u32),
+    /// `unknproject://add-source?url=<https catalog url>`
+    AddSource(String),
+}
+
+impl DeepLink {
+    /// Checks any command line argument, so it must not assume `arg` is ASCII.
+    pub fn is_link(arg: &str) -> bool {
+        let prefix = format!("{}://", SCHEME);
+        arg.len() > prefix.len()
+            && arg
+                .get(..prefix.len())
+                .is_some_and(|start| start.eq_ignore_ascii_case(&prefix))
+    }
+
+    pub fn parse(link: &str) -> Result<Self, String> {
+        if !Self::is_link(link) {
+            return Err(format!("{} is not an {}:// link.", link, SCHEME));
+        }
+
+        let rest = link[SCHEME.len() + 3..].trim_end_matches('/');
+        let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let (action, argument) = path.split_once('/').unwrap_or((path, ""));
+        let invalid = || format!("Invalid link {}.", link);
+
+        match action.to_ascii_lowercase().as_str() {
+            "select" => argument
+                .parse()
+                .map(DeepLink::Select)
+                .map_err(|_| invalid()),
+            "inject" => argument
+                .parse()
+                .map(DeepLink::Inject)
+                .map_err(|_| invalid()),
+            "add-source" => {
+                let url = query
+                    .split('&')
+                    .find_map(|pair| pair.strip_prefix("url="))
+                    .ok_or_else(invalid)?;
+                let url = percent_decode(url).ok_or_else(invalid)?;
+                // The DLLs and the hashes that vouch for them come from the same place,
+                // so a source is only as trustworthy as its transport.
+                if !url.starts_with("https://") {
+                    return Err(format!("Catalog sources must be https URLs, not {}.", url));
+                }
+                Ok(DeepLink::AddSource(url))
+            }
+            _ => Err(format!("Unknown link action {}.", action)),
+        }
+    }
+}
+
+impl fmt::Display for DeepLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeepLink::Select(id) => write!(f, "{}://select/{}", SCHEME, id),
+            DeepLink::Inject(id) => write!(f, "{}://inject/{}", SCHEME, id),
+            DeepLink::AddSource(url) => {
+                write!(f, "{}://add-source?url={}", SCHEME, percent_encode(url))
+            }
+        }
+    }
+}
+
+/// Leaves `/` and `:` readable; everything that could end or alter the query is escaped.
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+fn percent_decode(value: &str) -> Option<String> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let hex = value.get(i + 1..i + 3)?;
+                // from_str_radix would also accept a sign, e.g. `%+1`.
+                if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+                    return None;
+                }
+                decoded.push(u8::from_str_radix(hex, 16).ok()?);
+                i += 3;
+            }
+            b'+' => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            byte => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8(decoded).ok()
+}
+
+/// Registers the loader as the handler for `unknproject://` links of the current user, so
+/// no elevation is needed. Re-run on every start to follow the exe when it's moved.
+#[cfg(windows)]
+pub fn register() -> Result<(), String> {
+    use winreg::{enums::HKEY_CURRENT_USER, RegKey};
+
+    let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+
+    let (key, _) = RegKey::predef(HKEY_CURRENT_USER)
+        .create_subkey(format!("Software\\Classes\\{}", SCHEME))
+        .map_err(|e| e.to_string())?;
+    key.set_value("", &"URL:UnknProject")
+        .map_err(|e| e.to_string())?;
+    key.set_value("URL Protocol", &"")
+        .map_err(|e| e.to_string())?;
+
+    let (command, _) = key
+        .create_subkey("shell\\open\\command")
+        .map_err(|e| e.to_string())?;
+    command
+        .set_value("", &format!("\"{}\" \"%1\"", exe.display()))
+        .map_err(|e| e.to_string())?;
+
+    log::debug!("Registered {}:// links for {}", SCHEME, exe.display());
+    Ok(())
+}
+
+/// Other hosts register URL handlers through desktop files or app bundles we don't ship.
+#[cfg(not(windows))]
+pub fn register() -> Result<(), String> {
+    log::debug!("Not registering {}:// links on this platform", SCHEME);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_link_handles_non_ascii_arguments() {
+        assert!(!DeepLink::is_link("C:\\Users\\André\\Downloads\\hack.dll"));
+        assert!(!DeepLink::is_link("ünknproject://select/1"));
+        assert!(!DeepLink::is_link("unknproject://"));
+        assert!(DeepLink::is_link("UnknProject://select/1"));
+    }
+
+    #[test]
+    fn parses_select_and_inject() {
+        assert_eq!(
+            DeepLink::parse("unknproject://select/12"),
+            Ok(DeepLink::Select(12))
+        );
+        assert_eq!(
+            DeepLink::parse("UNKNPROJECT://Inject/7/"),
+            Ok(DeepLink::Inject(7))
+        );
+        assert!(DeepLink::parse("unknproject://inject/abc").is_err());
+        assert!(DeepLink::parse("unknproject://launch/1").is_err());
+        assert!(DeepLink::parse("https://example.com").is_err());
+    }
+
+    #[test]
+    fn parses_add_source() {
+        assert_eq!(
+            DeepLink::parse(
+                "unknproject://add-source?ref=site&url=https%3A%2F%2Fexample.com%2Fa%20b%2FHacklist.html"
+            ),
+            Ok(DeepLink::AddSource(
+                "https://example.com/a b/Hacklist.html".to_string()
+            ))
+        );
+        assert!(DeepLink::parse("unknproject://add-source").is_err());
+        assert!(DeepLink::parse("unknproject://add-source?url=%zz").is_err());
+    }
+
+    #[test]
+    fn add_source_requires_https() {
+        assert!(
+            DeepLink::parse("unknproject://add-source?url=http://example.com/Hacklist.html")
+                .is_err()
+        );
+        assert!(DeepLink::parse("unknproject://add-source?url=file:///C:/Hacklist.html").is_err());
+        assert!(DeepLink::parse("unknproject://add-source?url=C:%5CHacklist.html").is_err());
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for link in [DeepLink::Select(3), DeepLink::Inject(4)] {
+            assert_eq!(DeepLink::parse(&link.to_string()), Ok(link));
+        }
+    }
+
+    #[test]
+    fn add_source_round_trips() {
+        for url in [
+            "https://example.com/Hacklist.html",
+            "https://example.com/a b/Hacklist.html?user=me&key=a%2Bb",
+            "https://example.com/café/Hacklist.html#top",
+        ] {
+            let link = DeepLink::AddSource(url.to_string());
+            assert_eq!(DeepLink::parse(&link.to_string()), Ok(link));
+        }
+    }
+
+    #[test]
+    fn percent_decodes() {
+        assert_eq!(percent_decode("a%20b+c"), Some("a b c".to_string()));
+        assert_eq!(percent_decode("%C3%A9"), Some("é".to_string()));
+        assert_eq!(percent_decode("caf%C3%A9%2F"), Some("café/".to_string()));
+        assert_eq!(percent_decode("plain"), Some("plain".to_string()));
+        assert_eq!(percent_decode("%4"), None);
+        assert_eq!(percent_decode("%zz"), None);
+        assert_eq!(percent_decode("%+1"), None);
+        assert_eq!(percent_decode("%-1"), None);
+        assert_eq!(percent_decode("%é"), None);
+        // Not valid UTF-8 once decoded.
+        assert_eq!(percent_decode("%FF"), None);
+    }
+}
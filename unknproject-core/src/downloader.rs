@@ -0,0 +1,195 @@
+use std::{error::Error, fs::File, io::copy, thread, time::Duration};
+
+use serde::de::DeserializeOwned;
+
+use crate::{
+    config::Config,
+    http::{Http, HttpResponse},
+};
+
+/// Fetches files from the CDN, falling back to the secondary endpoint, and from absolute
+/// URLs, all through the transport the frontend handed in.
+#[derive(Clone)]
+pub struct Downloader {
+    http: Http,
+    cdn_endpoint: String,
+    cdn_fallback_endpoint: String,
+}
+
+impl Downloader {
+    /// Connection failures and server errors are tried again this often in total.
+    const ATTEMPTS: u64 = 2;
+
+    pub fn new(http: Http, config: &Config) -> Self {
+        Self {
+            http,
+            cdn_endpoint: config.cdn_endpoint.clone(),
+            cdn_fallback_endpoint: config.cdn_fallback_endpoint.clone(),
+        }
+    }
+
+    /// GETs `url`, retrying once with a short pause if there was no answer or a server
+    /// error. Other statuses are up to the caller.
+    pub fn get(&self, url: &str) -> Result<HttpResponse, String> {
+        let mut attempt = 1;
+        loop {
+            let response = self.http.get(url);
+            let failure = match &response {
+                Ok(response) if response.status >= 500 => format!("status {}", response.status),
+                Ok(_) => return response,
+                Err(e) => e.clone(),
+            };
+
+            if attempt >= Self::ATTEMPTS {
+                return response;
+            }
+
+            log::warn!("Request to {} failed ({}), retrying...", url, failure);
+            thread::sleep(Duration::from_millis(500 * attempt));
+            attempt += 1;
+        }
+    }
+
+    /// Requests `file` from the primary CDN, falling back to the secondary one.
+    /// Returns the URL that answered together with the response.
+    fn fetch(&self, file: &str) -> Result<(String, HttpResponse), Box<dyn Error>> {
+        log::info!("Downloading {}...", file);
+
+        let url = format!("{}{}", self.cdn_endpoint, file);
+        match self.get(&url) {
+            Ok(response) if response.status == 200 => return Ok((url, response)),
+            Ok(response) => log::warn!("Primary CDN endpoint answered {}", response.status),
+            Err(e) => log::warn!("Primary CDN endpoint unavailable: {}", e),
+        }
+
+        log::warn!("Trying fallback CDN endpoint...");
+        let url = format!("{}{}", self.cdn_fallback_endpoint, file);
+        let response = self
+            .get(&url)
+            .map_err(|e| format!("Failed to download from both CDN endpoints: {}", e))?;
+
+        if response.status == 200 {
+            Ok((url, response))
+        } else {
+            Err(format!("Cannot download file: {}", response.status).into())
+        }
+    }
+
+    /// Downloads `file` from the CDN to `destination` and returns the URL it came from.
+    pub fn download_file(&self, file: &str, destination: &str) -> Result<String, Box<dyn Error>> {
+        let (url, response) = self.fetch(file)?;
+
+        let mut file = File::create(destination)?;
+        let mut reader = response.body;
+        copy(&mut reader, &mut file)?;
+        Ok(url)
+    }
+
+    /// Downloads an absolute `url` to `destination`, for files that don't live on the CDN.
+    pub fn download_url(&self, url: &str, destination: &str) -> Result<(), Box<dyn Error>> {
+        log::info!("Downloading {}...", url);
+
+        let response = self.get(url)?;
+        if response.status != 200 {
+            return Err(format!("Cannot download file: {}", response.status).into());
+        }
+
+        let mut file = File::create(destination)?;
+        let mut reader = response.body;
+        copy(&mut reader, &mut file)?;
+        Ok(())
+    }
+
+    pub fn fetch_json<T: DeserializeOwned>(&self, file: &str) -> Result<T, Box<dyn Error>> {
+        let (_, response) = self.fetch(file)?;
+        Ok(response.into_json()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::http::StaticTransport;
+
+    const PRIMARY: &str = "https://primary.test/";
+    const FALLBACK: &str = "https://fallback.test/";
+
+    fn cdn(transport: StaticTransport) -> (Downloader, Arc<StaticTransport>) {
+        let transport = Arc::new(transport);
+        let config = Config {
+            cdn_endpoint: PRIMARY.to_string(),
+            cdn_fallback_endpoint: FALLBACK.to_string(),
+            ..Config::default()
+        };
+        (Downloader::new(transport.clone(), &config), transport)
+    }
+
+    #[test]
+    fn fetches_from_the_primary_endpoint() {
+        let (downloader, transport) =
+            cdn(StaticTransport::default().respond("https://primary.test/a.json", 200, "[1]"));
+
+        let value: Vec<u32> = downloader.fetch_json("a.json").unwrap();
+        assert_eq!(value, vec![1]);
+        assert_eq!(transport.requests(), vec!["https://primary.test/a.json"]);
+    }
+
+    #[test]
+    fn falls_back_when_the_primary_fails() {
+        let (downloader, transport) = cdn(StaticTransport::default()
+            .respond("https://primary.test/a.json", 404, "")
+            .respond("https://fallback.test/a.json", 200, "[2]"));
+        let value: Vec<u32> = downloader.fetch_json("a.json").unwrap();
+        assert_eq!(value, vec![2]);
+        assert_eq!(
+            transport.requests(),
+            vec![
+                "https://primary.test/a.json",
+                "https://fallback.test/a.json"
+            ]
+        );
+
+        // Unreachable hosts are retried before falling back.
+        let (downloader, transport) =
+            cdn(StaticTransport::default().respond("https://fallback.test/a.json", 200, "[3]"));
+        let value: Vec<u32> = downloader.fetch_json("a.json").unwrap();
+        assert_eq!(value, vec![3]);
+        assert_eq!(transport.requests().len(), 3);
+    }
+
+    #[test]
+    fn retries_server_errors() {
+        let (downloader, transport) = cdn(StaticTransport::default()
+            .respond("https://primary.test/a.json", 503, "")
+            .respond("https://primary.test/a.json", 200, "[4]"));
+
+        let value: Vec<u32> = downloader.fetch_json("a.json").unwrap();
+        assert_eq!(value, vec![4]);
+        assert_eq!(
+            transport.requests(),
+            vec!["https://primary.test/a.json", "https://primary.test/a.json"]
+        );
+    }
+
+    #[test]
+    fn other_statuses_are_errors() {
+        let (downloader, transport) = cdn(StaticTransport::default()
+            .respond("https://primary.test/a.json", 404, "")
+            .respond("https://fallback.test/a.json", 403, "")
+            .respond("https://other.test/b.dll", 410, ""));
+
+        let error = downloader.fetch_json::<Vec<u32>>("a.json").unwrap_err();
+        assert_eq!(error.to_string(), "Cannot download file: 403");
+
+        let destination = std::env::temp_dir().join("unknproject-downloader-never-written");
+        let error = downloader
+            .download_url("https://other.test/b.dll", &destination.to_string_lossy())
+            .unwrap_err();
+        assert_eq!(error.to_string(), "Cannot download file: 410");
+        assert!(!destination.exists());
+        // 4xx answers aren't retried.
+        assert_eq!(transport.requests().len(), 3);
+    }
+}
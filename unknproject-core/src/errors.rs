@@ -0,0 +1,282 @@
+use std::{fmt, io};
+
+use serde::{Deserialize, Serialize};
+
+use crate::injector::InjectorCode;
+
+/// Broad cause of a failed injection, with a stable code for logs and bug reports.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    AccessDenied,
+    ArchitectureMismatch,
+    FileLocked,
+    FileMissing,
+    ProcessExited,
+    ModuleAlreadyLoaded,
+    InjectorMissing,
+    Unknown,
+}
+
+impl ErrorKind {
+    /// Stable identifier; never renumber these, users paste them into issues.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ErrorKind::Unknown => "UP-000",
+            ErrorKind::AccessDenied => "UP-001",
+            ErrorKind::ArchitectureMismatch => "UP-002",
+            ErrorKind::FileLocked => "UP-003",
+            ErrorKind::FileMissing => "UP-004",
+            ErrorKind::ProcessExited => "UP-005",
+            ErrorKind::ModuleAlreadyLoaded => "UP-006",
+            ErrorKind::InjectorMissing => "UP-007",
+        }
+    }
+
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            ErrorKind::AccessDenied => Some("Restart the loader as administrator."),
+            ErrorKind::ArchitectureMismatch => {
+                Some("Use a DLL built for the game's architecture (x86 or x64).")
+            }
+            ErrorKind::FileLocked => {
+                Some("Close whatever is using the file (often the game itself) and try again.")
+            }
+            ErrorKind::FileMissing => {
+                Some("Redownload the hack, or check your antivirus didn't remove it.")
+            }
+            ErrorKind::ProcessExited => Some("Start the game and wait for the main menu."),
+            ErrorKind::ModuleAlreadyLoaded => {
+                Some("It's already injected; restart the game to inject it again.")
+            }
+            ErrorKind::InjectorMissing => {
+                Some("Redownload the injector in Settings and allow it in your antivirus.")
+            }
+            ErrorKind::Unknown => None,
+        }
+    }
+
+    /// Classifies an OS error, e.g. from reading the DLL.
+    pub fn from_io(error: &io::Error) -> Self {
+        // ERROR_SHARING_VIOLATION and ERROR_LOCK_VIOLATION, which std has no kind for.
+        if cfg!(windows) && matches!(error.raw_os_error(), Some(32 | 33)) {
+            return ErrorKind::FileLocked;
+        }
+
+        match error.kind() {
+            io::ErrorKind::NotFound => ErrorKind::FileMissing,
+            io::ErrorKind::PermissionDenied => ErrorKind::AccessDenied,
+            _ => ErrorKind::Unknown,
+        }
+    }
+
+    /// Best-effort classification of error strings from `dll_syringe`, the OS and other
+    /// places that don't say what went wrong in a structured way. Errors whose cause is
+    /// known are classified where they happen instead.
+    pub fn classify(error: &str) -> Self {
+        let error = error.to_lowercase();
+        let has = |needles: &[&str]| needles.iter().any(|needle| error.contains(needle));
+
+        if has(&[
+            "manual map injector unavailable",
+            "failed to execute injector",
+        ]) {
+            ErrorKind::InjectorMissing
+        } else if has(&["access is denied", "os error 5)", "as administrator"]) {
+            ErrorKind::AccessDenied
+        } else if has(&["architecture mismatch", "32-bit loader"]) {
+            ErrorKind::ArchitectureMismatch
+        } else if has(&[
+            "os error 32)",
+            "os error 33)",
+            "being used by another process",
+        ]) {
+            ErrorKind::FileLocked
+        } else if has(&[
+            "does not exist",
+            "os error 2)",
+            "os error 3)",
+            "cannot find the file",
+            "cannot find the path",
+            "couldn't read the dll",
+        ]) {
+            ErrorKind::FileMissing
+        } else if has(&["already loaded", "already injected"]) {
+            ErrorKind::ModuleAlreadyLoaded
+        } else if has(&[
+            "process not found",
+            "process exited",
+            "not running",
+            "process has terminated",
+            "did not start",
+        ]) {
+            ErrorKind::ProcessExited
+        } else {
+            ErrorKind::Unknown
+        }
+    }
+}
+
+impl From<InjectorCode> for ErrorKind {
+    fn from(code: InjectorCode) -> Self {
+        match code {
+            InjectorCode::InvalidArguments | InjectorCode::UnsupportedProtocol => {
+                ErrorKind::InjectorMissing
+            }
+            InjectorCode::ProcessNotFound => ErrorKind::ProcessExited,
+            InjectorCode::OpenProcessFailed => ErrorKind::AccessDenied,
+            InjectorCode::ReadDllFailed => ErrorKind::FileMissing,
+            InjectorCode::ArchitectureMismatch => ErrorKind::ArchitectureMismatch,
+            InjectorCode::Ok
+            | InjectorCode::InvalidImage
+            | InjectorCode::AllocationFailed
+            | InjectorCode::WriteMemoryFailed
+            | InjectorCode::RelocationFailed
+            | InjectorCode::ImportResolutionFailed
+            | InjectorCode::ThreadCreationFailed
+            | InjectorCode::EntryPointFailed
+            | InjectorCode::Unknown => ErrorKind::Unknown,
+        }
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorKind::AccessDenied => write!(f, "access denied"),
+            ErrorKind::ArchitectureMismatch => write!(f, "architecture mismatch"),
+            ErrorKind::FileLocked => write!(f, "file locked"),
+            ErrorKind::FileMissing => write!(f, "file missing"),
+            ErrorKind::ProcessExited => write!(f, "process exited"),
+            ErrorKind::ModuleAlreadyLoaded => write!(f, "module already loaded"),
+            ErrorKind::InjectorMissing => write!(f, "injector missing"),
+            ErrorKind::Unknown => write!(f, "unknown error"),
+        }
+    }
+}
+
+/// An error message together with its classification.
+#[derive(Clone, Debug)]
+pub struct ClassifiedError {
+    pub kind: ErrorKind,
+    pub message: String,
+}
+
+impl ClassifiedError {
+    /// Classifies `message` by its wording, see [`ErrorKind::classify`].
+    pub fn new(message: impl Into<String>) -> Self {
+        let message = message.into();
+        Self {
+            kind: ErrorKind::classify(&message),
+            message,
+        }
+    }
+
+    pub fn with_kind(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+        }
+    }
+
+    /// Message with the remediation hint appended, for toasts and the status label.
+    pub fn with_hint(&self) -> String {
+        match self.kind.hint() {
+            Some(hint) => format!("{} {}", self.message, hint),
+            None => self.message.clone(),
+        }
+    }
+}
+
+impl From<String> for ClassifiedError {
+    fn from(message: String) -> Self {
+        Self::new(message)
+    }
+}
+
+impl fmt::Display for ClassifiedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.kind.code(), self.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_messages_without_a_known_cause() {
+        let cases = [
+            (
+                "Failed to inject: Access is denied. (os error 5)",
+                ErrorKind::AccessDenied,
+            ),
+            (
+                "The process cannot access the file because it is being used by another process.",
+                ErrorKind::FileLocked,
+            ),
+            ("C:\\hacks\\aim.dll does not exist.", ErrorKind::FileMissing),
+            (
+                "The system cannot find the file specified. (os error 2)",
+                ErrorKind::FileMissing,
+            ),
+            (
+                "aim.dll is already loaded in game.exe",
+                ErrorKind::ModuleAlreadyLoaded,
+            ),
+            (
+                "game.exe did not start within 180 seconds.",
+                ErrorKind::ProcessExited,
+            ),
+            (
+                "Failed to execute injector: not found",
+                ErrorKind::InjectorMissing,
+            ),
+            ("Something odd happened", ErrorKind::Unknown),
+        ];
+
+        for (message, kind) in cases {
+            assert_eq!(ErrorKind::classify(message), kind, "{}", message);
+        }
+    }
+
+    #[test]
+    fn loose_wording_stays_unknown() {
+        // Neither of these says anything about the DLL's architecture or the process.
+        assert_eq!(
+            ErrorKind::classify("The file is not a valid PE image."),
+            ErrorKind::Unknown
+        );
+        assert_eq!(
+            ErrorKind::classify("Steam installation not found"),
+            ErrorKind::Unknown
+        );
+        assert_eq!(ErrorKind::classify("os error 321)"), ErrorKind::Unknown);
+    }
+
+    #[test]
+    fn structured_causes_win() {
+        let missing = io::Error::new(io::ErrorKind::NotFound, "gone");
+        assert_eq!(ErrorKind::from_io(&missing), ErrorKind::FileMissing);
+        let denied = io::Error::new(io::ErrorKind::PermissionDenied, "no");
+        assert_eq!(ErrorKind::from_io(&denied), ErrorKind::AccessDenied);
+
+        assert_eq!(
+            ErrorKind::from(InjectorCode::OpenProcessFailed),
+            ErrorKind::AccessDenied
+        );
+        assert_eq!(
+            ErrorKind::from(InjectorCode::InvalidImage),
+            ErrorKind::Unknown
+        );
+        assert_eq!(
+            ErrorKind::from(InjectorCode::UnsupportedProtocol),
+            ErrorKind::InjectorMissing
+        );
+
+        let error = ClassifiedError::with_kind(ErrorKind::ProcessExited, "access is denied");
+        assert_eq!(error.kind, ErrorKind::ProcessExited);
+        assert_eq!(error.to_string(), "[UP-005] access is denied");
+        assert!(error.with_hint().ends_with("wait for the main menu."));
+    }
+}
@@ -0,0 +1,451 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    catalog,
+    config::Config,
+    downloader::Downloader,
+    hash::{sha256_file, sha256_hex},
+};
+
+/// One catalog entry, as published in `Hacklist.html` and written by `catalog build`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HackApiResponse {
+    #[serde(default)]
+    pub id: u32,
+    pub name: String,
+    pub description: String,
+    pub author: String,
+    pub status: String,
+    pub file: String,
+    pub process: String,
+    pub source: String,
+    pub game: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub steam_app_id: Option<u32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub required_modules: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub required_launch_options: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+}
+
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct Hack {
+    pub id: u32,
+    pub name: String,
+    pub description: String,
+    pub author: String,
+    pub status: String,
+    pub file: String,
+    pub process: String,
+    pub source: String,
+    pub game: String,
+    pub file_path: std::path::PathBuf,
+    pub steam_app_id: Option<u32>,
+    /// Modules the game must have loaded before it's safe to inject after launching it.
+    pub required_modules: Vec<String>,
+    /// Steam launch options the hack needs, e.g. `-insecure`.
+    pub required_launch_options: Vec<String>,
+    /// Published hash of the DLL, checked after downloading it.
+    pub sha256: Option<String>,
+    pub size: Option<u64>,
+    /// Where the DLL is downloaded from for hacks of an extra catalog source; the CDN
+    /// serves everything else.
+    pub origin: Option<String>,
+}
+
+impl Hack {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        id: u32,
+        name: &str,
+        description: &str,
+        author: &str,
+        status: &str,
+        file: &str,
+        process: &str,
+        source: &str,
+        game: &str,
+    ) -> Self {
+        Self {
+            id,
+            name: name.to_string(),
+            description: description.to_string(),
+            author: author.to_string(),
+            status: status.to_string(),
+            file: file.to_string(),
+            process: process.to_string(),
+            source: source.to_string(),
+            game: game.to_string(),
+            file_path: crate::config_dir().join(file),
+            steam_app_id: game_steam_app_id(game),
+            required_modules: game_required_modules(game),
+            required_launch_options: Vec::new(),
+            sha256: None,
+            size: None,
+            origin: None,
+        }
+    }
+
+    pub fn download(&self, downloader: &Downloader, file_path: String) -> Result<(), String> {
+        if !std::path::Path::new(&file_path).exists() {
+            let downloaded = match &self.origin {
+                Some(origin) => {
+                    if let Some(parent) = std::path::Path::new(&file_path).parent() {
+                        std::fs::create_dir_all(parent).ok();
+                    }
+                    if is_remote(origin) {
+                        downloader.download_url(&format!("{}{}", origin, self.file), &file_path)
+                    } else {
+                        std::fs::copy(Path::new(origin).join(&self.file), &file_path)
+                            .map(|_| ())
+                            .map_err(Into::into)
+                    }
+                }
+                None => downloader.download_file(&self.file, &file_path).map(|_| ()),
+            };
+            match downloaded {
+                Ok(_) => self.verify(&file_path),
+                Err(e) => Err(format!("Failed to download file: {}", e)),
+            }
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Removes a fresh download again if it doesn't match the hash the catalog published.
+    fn verify(&self, file_path: &str) -> Result<(), String> {
+        let Some(expected) = &self.sha256 else {
+            return Ok(());
+        };
+
+        let sha256 = sha256_file(std::path::Path::new(file_path))?;
+        if sha256.eq_ignore_ascii_case(expected) {
+            return Ok(());
+        }
+
+        std::fs::remove_file(file_path).ok();
+        log::error!(
+            "{} hash mismatch: expected {}, got {}",
+            self.file,
+            expected,
+            sha256
+        );
+        Err(format!(
+            "Downloaded {} does not match the catalog hash.",
+            self.file
+        ))
+    }
+
+    /// Loads a catalog from an http(s) URL or, for sources imported from an offline
+    /// bundle, from a file on disk.
+    pub fn fetch_hacks(
+        downloader: &Downloader,
+        api_endpoint: &str,
+        lowercase: bool,
+    ) -> Result<Vec<Hack>, String> {
+        let parsed_hacks: Vec<HackApiResponse> = if is_remote(api_endpoint) {
+            match downloader.get(api_endpoint) {
+                Ok(res) if res.status == 200 => res.into_json()?,
+                Ok(res) => return Err(format!("API request failed with status: {}", res.status)),
+                Err(e) => return Err(format!("Failed to connect to API: {}", e)),
+            }
+        } else {
+            catalog::load(Path::new(api_endpoint))?
+        };
+
+        if parsed_hacks.is_empty() {
+            return Err("No hacks available.".to_string());
+        }
+
+        log::debug!("Fetched {} hacks from API.", parsed_hacks.len());
+        Ok(parsed_hacks
+            .into_iter()
+            .map(|hack| Self::from_entry(hack, lowercase))
+            .collect())
+    }
+
+    pub(crate) fn from_entry(hack: HackApiResponse, lowercase: bool) -> Self {
+        let name = if lowercase {
+            hack.name.to_lowercase()
+        } else {
+            hack.name.clone()
+        };
+        let description = if lowercase {
+            hack.description.to_lowercase()
+        } else {
+            hack.description.clone()
+        };
+        let defaults = Hack::new(
+            hack.id,
+            &name,
+            &description,
+            &hack.author,
+            &hack.status,
+            &hack.file,
+            &hack.process,
+            &hack.source,
+            &hack.game,
+        );
+        Hack {
+            steam_app_id: hack.steam_app_id.or(defaults.steam_app_id),
+            required_modules: if hack.required_modules.is_empty() {
+                defaults.required_modules.clone()
+            } else {
+                hack.required_modules
+            },
+            required_launch_options: hack.required_launch_options,
+            sha256: hack.sha256,
+            size: hack.size,
+            ..defaults
+        }
+    }
+
+    /// The configured catalog followed by every extra source. A source that can't be
+    /// reached is skipped rather than failing the whole list.
+    pub fn fetch_catalog(downloader: &Downloader, config: &Config) -> Result<Vec<Hack>, String> {
+        let mut main_error = None;
+        let mut hacks =
+            match Self::fetch_hacks(downloader, &config.api_endpoint, config.lowercase_hacks) {
+                Ok(hacks) => hacks,
+                // Machines without internet still have the sources imported from bundles.
+                Err(e) if !config.catalog_sources.is_empty() => {
+                    log::warn!("Main catalog unavailable: {}", e);
+                    main_error = Some(e);
+                    Vec::new()
+                }
+                Err(e) => return Err(e),
+            };
+
+        for source in &config.catalog_sources {
+            match Self::fetch_hacks(downloader, source, config.lowercase_hacks) {
+                Ok(source_hacks) => {
+                    log::debug!("Fetched {} hacks from {}.", source_hacks.len(), source);
+                    hacks.extend(
+                        source_hacks
+                            .into_iter()
+                            // Third-party catalogs pick the file name the DLL is saved
+                            // under, so it can't be allowed to leave the source folder.
+                            .filter(|hack| {
+                                let plain = is_plain_file_name(&hack.file);
+                                if !plain {
+                                    log::warn!(
+                                        "Skipping {} from {}: invalid file name {}",
+                                        hack.name,
+                                        source,
+                                        hack.file
+                                    );
+                                }
+                                plain
+                            })
+                            .map(|hack| hack.with_source(source)),
+                    );
+                }
+                Err(e) => log::warn!("Skipping catalog source {}: {}", source, e),
+            }
+        }
+
+        match main_error {
+            Some(e) if hacks.is_empty() => Err(e),
+            _ => Ok(hacks),
+        }
+    }
+
+    /// Points a hack at its source's files, kept apart from CDN downloads so equal file
+    /// names from different sources don't clash.
+    pub(crate) fn with_source(self, source: &str) -> Self {
+        let file_path = crate::config_dir()
+            .join("sources")
+            .join(&sha256_hex(source.as_bytes())[..12])
+            .join(&self.file);
+
+        Self {
+            file_path,
+            origin: Some(source_files_url(source)),
+            ..self
+        }
+    }
+}
+
+/// Sources publish their DLLs in `hacks/` next to the catalog, the layout `catalog build`
+/// reads from.
+fn source_files_url(source: &str) -> String {
+    if !is_remote(source) {
+        let dir = Path::new(source).parent().unwrap_or(Path::new("."));
+        return dir.join("hacks").to_string_lossy().to_string();
+    }
+
+    match source.rfind('/') {
+        Some(index) => format!("{}hacks/", &source[..=index]),
+        None => "hacks/".to_string(),
+    }
+}
+
+/// Catalog file names end up in paths on disk, so they must not point anywhere else.
+pub fn is_plain_file_name(file: &str) -> bool {
+    !file.is_empty() && file != "." && file != ".." && !file.contains(['/', '\\', ':'])
+}
+
+fn is_remote(source: &str) -> bool {
+    source.starts_with("http://") || source.starts_with("https://")
+}
+
+/// Steam app id for games the catalog doesn't annotate yet.
+fn game_steam_app_id(game: &str) -> Option<u32> {
+    match game {
+        "CS:GO" | "CS2" => Some(730),
+        game if game.starts_with("CSS") => Some(240),
+        _ => None,
+    }
+}
+
+fn game_required_modules(game: &str) -> Vec<String> {
+    match game {
+        // serverbrowser.dll is the last module CS:GO loads before reaching the main menu.
+        "CS:GO" => vec!["serverbrowser.dll".to_string()],
+        "CS2" => vec!["client.dll".to_string()],
+        _ => Vec::new(),
+    }
+}
+
+pub fn get_hack_by_name(hacks: &[Hack], name: &str) -> Option<Hack> {
+    hacks.iter().find(|&hack| hack.name == name).cloned()
+}
+
+/// Looks `query` up by name, ignoring case, or by catalog id.
+pub fn find_hack<'a>(hacks: &'a [Hack], query: &str) -> Option<&'a Hack> {
+    hacks
+        .iter()
+        .find(|hack| hack.name == query)
+        .or_else(|| {
+            hacks
+                .iter()
+                .find(|hack| hack.name.eq_ignore_ascii_case(query))
+        })
+        .or_else(|| {
+            query
+                .parse::<u32>()
+                .ok()
+                .and_then(|id| hacks.iter().find(|hack| hack.id == id))
+        })
+}
+
+pub fn get_all_processes(hacks: &[Hack]) -> Vec<String> {
+    hacks
+        .iter()
+        .map(|hack| &hack.process)
+        .collect::<std::collections::HashSet<_>>()
+        .into_iter()
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::http::StaticTransport;
+
+    const CATALOG: &str = "https://catalog.test/Hacklist.html";
+    const SOURCE: &str = "https://source.test/catalog/Hacklist.html";
+
+    fn entry(id: u32, name: &str, file: &str) -> String {
+        format!(
+            r#"{{"id":{},"name":"{}","description":"Desc","author":"a","status":"working",
+            "file":"{}","process":"game.exe","source":"src","game":"Game"}}"#,
+            id, name, file
+        )
+    }
+
+    fn downloader(transport: StaticTransport) -> Downloader {
+        Downloader::new(Arc::new(transport), &Config::default())
+    }
+
+    #[test]
+    fn fetches_and_lowercases_hacks() {
+        let body = format!("[{}]", entry(7, "Aim Bot", "aim.dll"));
+        let downloader = downloader(StaticTransport::default().respond(CATALOG, 200, body));
+
+        let hacks = Hack::fetch_hacks(&downloader, CATALOG, true).unwrap();
+        assert_eq!(hacks.len(), 1);
+        assert_eq!(hacks[0].id, 7);
+        assert_eq!(hacks[0].name, "aim bot");
+        assert_eq!(hacks[0].description, "desc");
+        assert_eq!(hacks[0].origin, None);
+
+        let hacks = Hack::fetch_hacks(&downloader, CATALOG, false).unwrap();
+        assert_eq!(hacks[0].name, "Aim Bot");
+    }
+
+    #[test]
+    fn fetch_errors() {
+        let downloader = downloader(
+            StaticTransport::default()
+                .respond(CATALOG, 404, "")
+                .respond(SOURCE, 200, "[]"),
+        );
+
+        assert_eq!(
+            Hack::fetch_hacks(&downloader, CATALOG, true).unwrap_err(),
+            "API request failed with status: 404"
+        );
+        assert_eq!(
+            Hack::fetch_hacks(&downloader, SOURCE, true).unwrap_err(),
+            "No hacks available."
+        );
+        assert!(Hack::fetch_hacks(&downloader, "https://down.test/", true)
+            .unwrap_err()
+            .starts_with("Failed to connect to API"));
+    }
+
+    #[test]
+    fn catalog_sources_are_appended_and_confined() {
+        let main = format!("[{}]", entry(1, "Main", "main.dll"));
+        let source = format!(
+            "[{},{}]",
+            entry(2, "Extra", "extra.dll"),
+            entry(3, "Escape", "../../evil.dll")
+        );
+        let downloader = downloader(
+            StaticTransport::default()
+                .respond(CATALOG, 200, main)
+                .respond(SOURCE, 200, source),
+        );
+        let config = Config {
+            api_endpoint: CATALOG.to_string(),
+            catalog_sources: vec![SOURCE.to_string(), "https://down.test/".to_string()],
+            ..Config::default()
+        };
+
+        let hacks = Hack::fetch_catalog(&downloader, &config).unwrap();
+        let names: Vec<&str> = hacks.iter().map(|hack| hack.name.as_str()).collect();
+        assert_eq!(names, vec!["main", "extra"]);
+        assert_eq!(
+            hacks[1].origin.as_deref(),
+            Some("https://source.test/catalog/hacks/")
+        );
+        assert!(hacks[1].file_path.ends_with("extra.dll"));
+    }
+
+    #[test]
+    fn sources_keep_working_without_the_main_catalog() {
+        let source = format!("[{}]", entry(2, "Extra", "extra.dll"));
+        let downloader = downloader(StaticTransport::default().respond(SOURCE, 200, source));
+        let mut config = Config {
+            api_endpoint: CATALOG.to_string(),
+            catalog_sources: vec![SOURCE.to_string()],
+            ..Config::default()
+        };
+
+        assert_eq!(Hack::fetch_catalog(&downloader, &config).unwrap().len(), 1);
+
+        config.catalog_sources.clear();
+        assert!(Hack::fetch_catalog(&downloader, &config).is_err());
+    }
+}
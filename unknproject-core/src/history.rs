@@ -0,0 +1,332 @@
+use std::{
+    fmt,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::PathBuf,
+    time::Duration,
+};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::ErrorKind;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum InjectionSource {
+    Catalog,
+    DragAndDrop,
+    Chain,
+    Automation,
+    CommandLine,
+    Api,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum InjectionMethod {
+    Standard,
+    ManualMap,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum InjectionOutcome {
+    Success,
+    Failure,
+    /// Injected, but the target process died during the health check.
+    Crashed,
+}
+
+impl fmt::Display for InjectionSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InjectionSource::Catalog => write!(f, "catalog"),
+            InjectionSource::DragAndDrop => write!(f, "drag & drop"),
+            InjectionSource::Chain => write!(f, "chain"),
+            InjectionSource::Automation => write!(f, "automation"),
+            InjectionSource::CommandLine => write!(f, "command line"),
+            InjectionSource::Api => write!(f, "api"),
+        }
+    }
+}
+
+impl fmt::Display for InjectionMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InjectionMethod::Standard => write!(f, "standard"),
+            InjectionMethod::ManualMap => write!(f, "manual map"),
+        }
+    }
+}
+
+impl fmt::Display for InjectionOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InjectionOutcome::Success => write!(f, "success"),
+            InjectionOutcome::Failure => write!(f, "failure"),
+            InjectionOutcome::Crashed => write!(f, "crashed"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StageTiming {
+    pub stage: String,
+    pub duration_ms: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct InjectionRecord {
+    pub timestamp: DateTime<Local>,
+    pub hack_id: Option<u32>,
+    pub hack_name: String,
+    pub source: InjectionSource,
+    pub process: String,
+    pub pid: Option<u32>,
+    pub method: InjectionMethod,
+    pub stages: Vec<StageTiming>,
+    pub outcome: InjectionOutcome,
+    pub error: Option<String>,
+    #[serde(default)]
+    pub error_kind: Option<ErrorKind>,
+}
+
+impl InjectionRecord {
+    pub fn new(
+        hack_id: Option<u32>,
+        hack_name: &str,
+        source: InjectionSource,
+        process: &str,
+        pid: Option<u32>,
+        method: InjectionMethod,
+    ) -> Self {
+        Self {
+            timestamp: Local::now(),
+            hack_id,
+            hack_name: hack_name.to_string(),
+            source,
+            process: process.to_string(),
+            pid,
+            method,
+            stages: Vec::new(),
+            outcome: InjectionOutcome::Failure,
+            error: None,
+            error_kind: None,
+        }
+    }
+
+    pub fn add_stage(&mut self, stage: &str, duration: Duration) {
+        self.stages.push(StageTiming {
+            stage: stage.to_string(),
+            duration_ms: duration.as_millis() as u64,
+        });
+    }
+
+    pub fn total_duration_ms(&self) -> u64 {
+        self.stages.iter().map(|stage| stage.duration_ms).sum()
+    }
+
+    fn to_csv_row(&self) -> String {
+        let stages = self
+            .stages
+            .iter()
+            .map(|stage| format!("{}={}ms", stage.stage, stage.duration_ms))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        [
+            self.timestamp.to_rfc3339(),
+            self.hack_id.map(|id| id.to_string()).unwrap_or_default(),
+            self.hack_name.clone(),
+            self.source.to_string(),
+            self.process.clone(),
+            self.pid.map(|pid| pid.to_string()).unwrap_or_default(),
+            self.method.to_string(),
+            stages,
+            self.outcome.to_string(),
+            self.error.clone().unwrap_or_default(),
+            self.error_kind
+                .map(|kind| kind.code().to_string())
+                .unwrap_or_default(),
+        ]
+        .iter()
+        .map(|field| csv_escape(field))
+        .collect::<Vec<_>>()
+        .join(",")
+    }
+}
+
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Append-only injection log stored as one JSON record per line in `history.jsonl`.
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    pub records: Vec<InjectionRecord>,
+}
+
+impl History {
+    fn dir() -> PathBuf {
+        crate::config_dir()
+    }
+
+    fn path() -> PathBuf {
+        Self::dir().join("history.jsonl")
+    }
+
+    pub fn load() -> Self {
+        let records = fs::read_to_string(Self::path())
+            .map(|data| {
+                data.lines()
+                    .filter(|line| !line.trim().is_empty())
+                    .filter_map(|line| match serde_json::from_str(line) {
+                        Ok(record) => Some(record),
+                        Err(e) => {
+                            log::warn!("Skipping malformed history record: {}", e);
+                            None
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        History { records }
+    }
+
+    pub fn append(&mut self, record: InjectionRecord) {
+        fs::create_dir_all(Self::dir()).ok();
+
+        let line = match serde_json::to_string(&record) {
+            Ok(line) => line,
+            Err(e) => {
+                log::error!("Failed to serialize history record: {}", e);
+                return;
+            }
+        };
+
+        let result = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(Self::path())
+            .and_then(|mut file| writeln!(file, "{}", line));
+
+        if let Err(e) = result {
+            log::error!("Failed to write history: {}", e);
+        }
+
+        self.records.push(record);
+    }
+
+    pub fn export_csv(records: &[&InjectionRecord]) -> Result<PathBuf, String> {
+        let mut data =
+            "timestamp,hack_id,hack,source,process,pid,method,stages,outcome,error,error_code\n"
+                .to_string();
+        for record in records {
+            data += &record.to_csv_row();
+            data.push('\n');
+        }
+
+        Self::write_export("csv", data)
+    }
+
+    pub fn export_json(records: &[&InjectionRecord]) -> Result<PathBuf, String> {
+        let data = serde_json::to_string_pretty(records).map_err(|e| e.to_string())?;
+        Self::write_export("json", data)
+    }
+
+    fn write_export(extension: &str, data: String) -> Result<PathBuf, String> {
+        let path = Self::dir().join(format!(
+            "history-{}.{}",
+            Local::now().format("%Y%m%d-%H%M%S"),
+            extension
+        ));
+
+        fs::write(&path, data).map_err(|e| format!("Failed to export history: {}", e))?;
+        log::info!("Exported history to {}", path.display());
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::testing;
+
+    fn record(name: &str) -> InjectionRecord {
+        let mut record = InjectionRecord::new(
+            Some(7),
+            name,
+            InjectionSource::Catalog,
+            "game.exe",
+            Some(42),
+            InjectionMethod::ManualMap,
+        );
+        record.add_stage("download", Duration::from_millis(120));
+        record.add_stage("inject", Duration::from_millis(30));
+        record
+    }
+
+    #[test]
+    fn appended_records_load_again() {
+        testing::install_config_dir();
+        assert!(History::load().records.is_empty());
+
+        let mut history = History::default();
+        let mut failed = record("Wallhack");
+        failed.error = Some("Access is denied.".to_string());
+        failed.error_kind = Some(ErrorKind::AccessDenied);
+        history.append(record("Aim"));
+        history.append(failed.clone());
+
+        let loaded = History::load();
+        assert_eq!(loaded.records, history.records);
+        assert_eq!(loaded.records[1], failed);
+        assert_eq!(loaded.records[0].total_duration_ms(), 150);
+    }
+
+    #[test]
+    fn malformed_lines_are_skipped() {
+        let dir = testing::install_config_dir();
+        let good = serde_json::to_string(&record("Aim")).unwrap();
+        fs::write(
+            dir.join("history.jsonl"),
+            format!(
+                "{}\n{{\"hack_name\":\"cut off\n\n   \nnot json\n{}\n",
+                good, good
+            ),
+        )
+        .unwrap();
+
+        let records = History::load().records;
+        assert_eq!(records.len(), 2);
+        assert!(records.iter().all(|record| record.hack_name == "Aim"));
+    }
+
+    #[test]
+    fn csv_rows_quote_what_needs_quoting() {
+        assert_eq!(csv_escape("plain"), "plain");
+        assert_eq!(csv_escape("a,b"), "\"a,b\"");
+        assert_eq!(csv_escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_escape("line\nbreak"), "\"line\nbreak\"");
+        assert_eq!(csv_escape("carriage\rreturn"), "\"carriage\rreturn\"");
+
+        let mut failed = record("Aim, v2");
+        failed.pid = None;
+        failed.error = Some("Failed:\r\n\"denied\"".to_string());
+        failed.error_kind = Some(ErrorKind::AccessDenied);
+        let row = failed.to_csv_row();
+        let fields = row.split_once(',').unwrap().1;
+        assert_eq!(
+            fields,
+            "7,\"Aim, v2\",catalog,game.exe,,manual map,download=120ms inject=30ms,failure,\
+             \"Failed:\r\n\"\"denied\"\"\",UP-001"
+        );
+        assert!(row.starts_with(&failed.timestamp.to_rfc3339()));
+    }
+}
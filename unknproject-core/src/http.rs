@@ -0,0 +1,331 @@
+//! Every request the loader makes goes through an [`HttpTransport`] handed in by the
+//! frontend. Next to the real client there are transports that record responses to a
+//! folder and replay them later, so the catalog and download paths run without network:
+//!
+//! ```sh
+//! UNKNPROJECT_HTTP_RECORD=fixtures unknproject list   # record once
+//! UNKNPROJECT_HTTP_REPLAY=fixtures unknproject list   # replay offline
+//! ```
+
+#[cfg(test)]
+use std::{collections::HashMap, sync::Mutex};
+use std::{
+    env, fs,
+    io::{Cursor, Read},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::hash::sha256_hex;
+
+pub struct HttpResponse {
+    pub status: u16,
+    pub body: Box<dyn Read + Send>,
+}
+
+impl HttpResponse {
+    pub fn new(status: u16, body: impl Read + Send + 'static) -> Self {
+        Self {
+            status,
+            body: Box::new(body),
+        }
+    }
+
+    pub fn into_bytes(mut self) -> Result<Vec<u8>, String> {
+        let mut bytes = Vec::new();
+        self.body
+            .read_to_end(&mut bytes)
+            .map_err(|e| format!("Failed to read response: {}", e))?;
+        Ok(bytes)
+    }
+
+    pub fn into_json<T: DeserializeOwned>(self) -> Result<T, String> {
+        serde_json::from_reader(self.body).map_err(|e| format!("Failed to parse response: {}", e))
+    }
+}
+
+pub trait HttpTransport: Send + Sync {
+    /// Whatever status the server answered with is a response; `Err` means there was
+    /// no answer at all.
+    fn get(&self, url: &str) -> Result<HttpResponse, String>;
+}
+
+pub type Http = Arc<dyn HttpTransport>;
+
+/// Picks the transport from the environment: replaying from `UNKNPROJECT_HTTP_REPLAY`,
+/// recording to `UNKNPROJECT_HTTP_RECORD`, or plain network access.
+pub fn from_env() -> Http {
+    if let Some(dir) = env::var_os("UNKNPROJECT_HTTP_REPLAY") {
+        log::info!(
+            "Replaying HTTP responses from {}",
+            PathBuf::from(&dir).display()
+        );
+        return Arc::new(ReplayTransport::new(dir));
+    }
+
+    let transport: Http = Arc::new(UreqTransport::default());
+    match env::var_os("UNKNPROJECT_HTTP_RECORD") {
+        Some(dir) => {
+            log::info!(
+                "Recording HTTP responses to {}",
+                PathBuf::from(&dir).display()
+            );
+            Arc::new(RecordingTransport::new(transport, dir))
+        }
+        None => transport,
+    }
+}
+
+// MARK: Network
+pub struct UreqTransport {
+    agent: ureq::Agent,
+}
+
+impl Default for UreqTransport {
+    fn default() -> Self {
+        Self {
+            agent: ureq::agent(),
+        }
+    }
+}
+
+impl HttpTransport for UreqTransport {
+    fn get(&self, url: &str) -> Result<HttpResponse, String> {
+        match self.agent.get(url).call() {
+            Ok(response) | Err(ureq::Error::Status(_, response)) => {
+                Ok(HttpResponse::new(response.status(), response.into_reader()))
+            }
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
+
+// MARK: Fixtures
+/// One recorded request: `<key>.json` holds this, `<key>.body` the response body.
+#[derive(Serialize, Deserialize, Debug)]
+struct Fixture {
+    url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    status: Option<u16>,
+    /// The request failed without a response, e.g. the host was unreachable.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+fn fixture_key(url: &str) -> String {
+    sha256_hex(url.as_bytes())[..16].to_string()
+}
+
+/// Answers from recorded fixtures. A URL that was never recorded fails like an
+/// unreachable host.
+pub struct ReplayTransport {
+    dir: PathBuf,
+}
+
+impl ReplayTransport {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+impl HttpTransport for ReplayTransport {
+    fn get(&self, url: &str) -> Result<HttpResponse, String> {
+        let key = fixture_key(url);
+        let fixture = fs::read_to_string(self.dir.join(format!("{}.json", key)))
+            .ok()
+            .and_then(|data| serde_json::from_str::<Fixture>(&data).ok())
+            .filter(|fixture| fixture.url == url)
+            .ok_or_else(|| format!("No recorded response for {}", url))?;
+
+        if let Some(error) = fixture.error {
+            return Err(error);
+        }
+
+        let body = fs::read(self.dir.join(format!("{}.body", key)))
+            .map_err(|e| format!("Recorded response for {} has no body: {}", url, e))?;
+        Ok(HttpResponse::new(
+            fixture.status.unwrap_or(200),
+            Cursor::new(body),
+        ))
+    }
+}
+
+/// Passes requests on to `inner` and writes every answer, or failure, to `dir` in the
+/// layout [`ReplayTransport`] reads.
+pub struct RecordingTransport {
+    inner: Http,
+    dir: PathBuf,
+}
+
+impl RecordingTransport {
+    pub fn new(inner: Http, dir: impl Into<PathBuf>) -> Self {
+        let dir = dir.into();
+        fs::create_dir_all(&dir).ok();
+        Self { inner, dir }
+    }
+
+    fn save(&self, fixture: &Fixture, body: Option<&[u8]>) {
+        let key = fixture_key(&fixture.url);
+        if let Ok(data) = serde_json::to_string_pretty(fixture) {
+            fs::write(self.dir.join(format!("{}.json", key)), data).ok();
+        }
+        if let Some(body) = body {
+            fs::write(self.dir.join(format!("{}.body", key)), body).ok();
+        }
+    }
+}
+
+impl HttpTransport for RecordingTransport {
+    fn get(&self, url: &str) -> Result<HttpResponse, String> {
+        match self.inner.get(url) {
+            Ok(response) => {
+                let status = response.status;
+                let body = response.into_bytes()?;
+                self.save(
+                    &Fixture {
+                        url: url.to_string(),
+                        status: Some(status),
+                        error: None,
+                    },
+                    Some(&body),
+                );
+                Ok(HttpResponse::new(status, Cursor::new(body)))
+            }
+            Err(e) => {
+                self.save(
+                    &Fixture {
+                        url: url.to_string(),
+                        status: None,
+                        error: Some(e.clone()),
+                    },
+                    None,
+                );
+                Err(e)
+            }
+        }
+    }
+}
+
+// MARK: Testing
+#[cfg(test)]
+type StaticResponse = Result<(u16, Vec<u8>), String>;
+
+/// Answers from responses registered per URL, in order, repeating the last one. URLs
+/// without responses fail like an unreachable host.
+#[cfg(test)]
+#[derive(Default)]
+pub(crate) struct StaticTransport {
+    responses: Mutex<HashMap<String, Vec<StaticResponse>>>,
+    requests: Mutex<Vec<String>>,
+}
+
+#[cfg(test)]
+impl StaticTransport {
+    pub fn respond(self, url: &str, status: u16, body: impl Into<Vec<u8>>) -> Self {
+        self.push(url, Ok((status, body.into())))
+    }
+
+    pub fn fail(self, url: &str, error: &str) -> Self {
+        self.push(url, Err(error.to_string()))
+    }
+
+    fn push(self, url: &str, response: StaticResponse) -> Self {
+        self.responses
+            .lock()
+            .unwrap()
+            .entry(url.to_string())
+            .or_default()
+            .push(response);
+        self
+    }
+
+    /// Every URL requested so far, in order.
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+impl HttpTransport for StaticTransport {
+    fn get(&self, url: &str) -> Result<HttpResponse, String> {
+        self.requests.lock().unwrap().push(url.to_string());
+
+        let mut responses = self.responses.lock().unwrap();
+        let queue = responses
+            .get_mut(url)
+            .ok_or_else(|| format!("{} is unreachable", url))?;
+        let response = if queue.len() > 1 {
+            queue.remove(0)
+        } else {
+            queue[0].clone()
+        };
+
+        response.map(|(status, body)| HttpResponse::new(status, Cursor::new(body)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("unknproject-http-{}-{}", name, std::process::id()));
+        fs::remove_dir_all(&dir).ok();
+        dir
+    }
+
+    #[test]
+    fn replays_what_was_recorded() {
+        let dir = fixture_dir("replay");
+        let inner = StaticTransport::default()
+            .respond("https://a.test/list", 200, "[1,2]")
+            .respond("https://a.test/gone", 404, "missing")
+            .fail("https://a.test/down", "connection refused");
+        let recorder = RecordingTransport::new(Arc::new(inner), &dir);
+        for url in [
+            "https://a.test/list",
+            "https://a.test/gone",
+            "https://a.test/down",
+        ] {
+            let _ = recorder.get(url);
+        }
+
+        let replay = ReplayTransport::new(&dir);
+        let list = replay.get("https://a.test/list").unwrap();
+        assert_eq!(list.status, 200);
+        assert_eq!(list.into_bytes().unwrap(), b"[1,2]");
+
+        let gone = replay.get("https://a.test/gone").unwrap();
+        assert_eq!(gone.status, 404);
+        assert_eq!(gone.into_bytes().unwrap(), b"missing");
+
+        assert_eq!(
+            replay.get("https://a.test/down").err().unwrap(),
+            "connection refused"
+        );
+        assert!(replay.get("https://a.test/never").is_err());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn replay_without_a_body_is_an_error() {
+        let dir = fixture_dir("nobody");
+        let recorder = RecordingTransport::new(
+            Arc::new(StaticTransport::default().respond("https://a.test/", 200, "body")),
+            &dir,
+        );
+        recorder.get("https://a.test/").unwrap();
+        fs::remove_file(dir.join(format!("{}.body", fixture_key("https://a.test/")))).unwrap();
+
+        let error = ReplayTransport::new(&dir)
+            .get("https://a.test/")
+            .err()
+            .unwrap();
+        assert!(error.contains("has no body"));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
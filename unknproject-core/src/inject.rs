@@ -0,0 +1,1390 @@
+use std::{
+    fs,
+    io::{BufRead, BufReader},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, RecvTimeoutError, Sender},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use chrono::Local;
+
+use crate::{
+    chains::{ChainTarget, InjectionChain},
+    config::Config,
+    downloader::Downloader,
+    errors::{ClassifiedError, ErrorKind},
+    hacks::{get_hack_by_name, Hack},
+    hash::sha256_file,
+    history::{InjectionMethod, InjectionOutcome, InjectionRecord, InjectionSource},
+    http::Http,
+    injector::{injector_args, InjectorOutput, InjectorResult},
+    injector_assets::{self, InjectorAssets, InjectorStatus},
+    modules::{self, InjectedModule, ModuleTracker},
+    pe::PeInfo,
+    platform,
+    processes::{has_exited, is_alive, open_process, ProcessArch},
+    steam::SteamAccount,
+    Notify,
+};
+
+/// Messages sent from injection threads back to the frontend.
+#[derive(Debug, Clone)]
+pub enum InjectionEvent {
+    Succeeded(String),
+    Failed(String),
+    /// Hack name and message; the game died during the post-injection health check.
+    Crashed(String, String),
+    Recorded(InjectionRecord),
+    ModuleLoaded(InjectedModule),
+    /// Outcome of ejecting a module, which happens off the UI thread too.
+    Ejected(InjectedModule, Result<(), String>),
+    /// The request looks like a second injection of the same DLL and asked to be
+    /// confirmed first, see [`OnDuplicate::Ask`]. Carries the reason.
+    Duplicate(Box<InjectionRequest>, String),
+}
+
+/// What to do when the DLL already seems to be loaded in the target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OnDuplicate {
+    /// Record a failure, for unattended runs like chains and automation.
+    #[default]
+    Fail,
+    /// Send [`InjectionEvent::Duplicate`] and stop, so the user can decide.
+    Ask,
+    /// Inject anyway.
+    Inject,
+}
+
+/// Everything needed to run one injection, whether it came from the catalog or a dropped file.
+#[derive(Debug, Clone)]
+pub struct InjectionRequest {
+    pub name: String,
+    pub hack: Option<Hack>,
+    pub source: InjectionSource,
+    pub dll_path: PathBuf,
+    pub process: String,
+    pub pid: Option<u32>,
+    pub method: InjectionMethod,
+    pub on_duplicate: OnDuplicate,
+}
+
+impl InjectionRequest {
+    pub fn from_hack(hack: &Hack, pid: Option<u32>) -> Self {
+        Self {
+            name: hack.name.clone(),
+            hack: Some(hack.clone()),
+            source: InjectionSource::Catalog,
+            dll_path: hack.file_path.clone(),
+            process: hack.process.clone(),
+            pid,
+            method: InjectionMethod::for_game(&hack.game),
+            on_duplicate: OnDuplicate::default(),
+        }
+    }
+
+    pub fn from_file(dll_path: PathBuf, process: &str, pid: Option<u32>) -> Self {
+        Self {
+            name: dll_path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+            hack: None,
+            source: InjectionSource::DragAndDrop,
+            dll_path,
+            process: process.to_string(),
+            pid,
+            method: InjectionMethod::for_process(process),
+            on_duplicate: OnDuplicate::default(),
+        }
+    }
+
+    fn injected_module(&self, pid: u32, base: Option<usize>) -> InjectedModule {
+        InjectedModule {
+            name: self.name.clone(),
+            path: self.dll_path.clone(),
+            sha256: sha256_file(&self.dll_path).ok(),
+            method: self.method,
+            process: self.process.clone(),
+            pid,
+            base,
+            injected_at: Local::now(),
+        }
+    }
+
+    fn record(&self) -> InjectionRecord {
+        InjectionRecord::new(
+            self.hack.as_ref().map(|hack| hack.id),
+            &self.name,
+            self.source,
+            &self.process,
+            self.pid,
+            self.method,
+        )
+    }
+}
+
+impl InjectionMethod {
+    pub fn for_game(game: &str) -> Self {
+        match game {
+            "CS:GO" | "CS2" => InjectionMethod::ManualMap,
+            _ => InjectionMethod::Standard,
+        }
+    }
+
+    pub fn for_process(process: &str) -> Self {
+        if process.eq_ignore_ascii_case("csgo.exe") || process.eq_ignore_ascii_case("cs2.exe") {
+            InjectionMethod::ManualMap
+        } else {
+            InjectionMethod::Standard
+        }
+    }
+}
+
+/// Status label, notifications, events and visual delays shared by every stage of a
+/// running injection.
+pub struct InjectionProgress {
+    status_message: Arc<Mutex<String>>,
+    message_sender: Sender<InjectionEvent>,
+    /// Called whenever the status changes, e.g. to repaint a window.
+    notify: Notify,
+    skip_delay: bool,
+    injector_timeout: Duration,
+    injectors: Arc<Mutex<InjectorAssets>>,
+    /// What the frontend injected so far, to catch double injections.
+    modules: Arc<Mutex<ModuleTracker>>,
+    /// Grace period for the post-injection health check, `None` when disabled.
+    health_check: Option<Duration>,
+    downloader: Downloader,
+}
+
+impl InjectionProgress {
+    pub fn new(
+        config: &Config,
+        http: Http,
+        status_message: Arc<Mutex<String>>,
+        message_sender: Sender<InjectionEvent>,
+        notify: Notify,
+        injectors: Arc<Mutex<InjectorAssets>>,
+        modules: Arc<Mutex<ModuleTracker>>,
+    ) -> Self {
+        Self {
+            status_message,
+            message_sender,
+            notify,
+            skip_delay: config.skip_injects_delay,
+            injector_timeout: Duration::from_secs(config.injector_timeout_secs),
+            injectors,
+            modules,
+            health_check: config
+                .health_check
+                .then(|| Duration::from_secs(config.health_check_grace_secs)),
+            downloader: Downloader::new(http, config),
+        }
+    }
+
+    pub fn send(&self, event: InjectionEvent) {
+        let _ = self.message_sender.send(event);
+    }
+
+    pub fn set_status(&self, status: impl Into<String>) {
+        *self.status_message.lock().unwrap() = status.into();
+        (self.notify)();
+    }
+
+    fn delay(&self, secs: u64) {
+        if !self.skip_delay {
+            thread::sleep(Duration::from_secs(secs));
+        }
+    }
+}
+
+/// Clears the in-progress flag when an injection thread ends.
+pub struct InProgressGuard {
+    flag: Arc<AtomicBool>,
+    notify: Notify,
+}
+
+impl InProgressGuard {
+    pub fn start(flag: &Arc<AtomicBool>, progress: &InjectionProgress) -> Self {
+        flag.store(true, Ordering::SeqCst);
+        Self {
+            flag: Arc::clone(flag),
+            notify: Arc::clone(&progress.notify),
+        }
+    }
+}
+
+impl Drop for InProgressGuard {
+    fn drop(&mut self) {
+        self.flag.store(false, Ordering::SeqCst);
+        (self.notify)();
+    }
+}
+
+fn timed<T>(record: &mut InjectionRecord, stage: &str, f: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let result = f();
+    record.add_stage(stage, start.elapsed());
+    result
+}
+
+/// Checks that `dll_path` is a DLL built for `target_arch` before anything touches the process.
+pub fn validate_dll(
+    dll_path: &Path,
+    target_arch: ProcessArch,
+    manual_map: bool,
+) -> Result<PeInfo, ClassifiedError> {
+    let file_name = dll_path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    let data = fs::read(dll_path).map_err(|e| {
+        ClassifiedError::with_kind(
+            ErrorKind::from_io(&e),
+            format!("Failed to read {}: {}", dll_path.display(), e),
+        )
+    })?;
+    let info = PeInfo::parse(&data).map_err(|e| {
+        ClassifiedError::with_kind(ErrorKind::Unknown, format!("{}: {}", file_name, e))
+    })?;
+
+    if !info.is_dll() {
+        return Err(ClassifiedError::with_kind(
+            ErrorKind::Unknown,
+            format!("{} is not a DLL.", file_name),
+        ));
+    }
+
+    let dll_arch = info.arch();
+    if target_arch != ProcessArch::Unknown && dll_arch != target_arch {
+        return Err(ClassifiedError::with_kind(
+            ErrorKind::ArchitectureMismatch,
+            format!(
+                "Architecture mismatch: {} is {}, but the target process is {}.",
+                file_name, dll_arch, target_arch
+            ),
+        ));
+    }
+
+    if !manual_map && ProcessArch::current() == ProcessArch::X86 && target_arch == ProcessArch::X64
+    {
+        return Err(ClassifiedError::with_kind(
+            ErrorKind::ArchitectureMismatch,
+            "The 32-bit loader can't inject into a 64-bit process, use the 64-bit build.",
+        ));
+    }
+
+    Ok(info)
+}
+
+/// The target of `request` is no longer around to inject into.
+fn process_not_found(request: &InjectionRequest) -> ClassifiedError {
+    ClassifiedError::with_kind(
+        ErrorKind::ProcessExited,
+        match request.pid {
+            Some(pid) => format!(
+                "Process '{}' ({}) is no longer running.",
+                request.process, pid
+            ),
+            None => format!("Process '{}' not found.", request.process),
+        },
+    )
+}
+
+fn run_injection(
+    request: &InjectionRequest,
+    progress: &InjectionProgress,
+    record: &mut InjectionRecord,
+) -> Result<Option<usize>, ClassifiedError> {
+    progress.delay(1);
+
+    if !request.dll_path.exists() {
+        let hack = request.hack.as_ref().ok_or_else(|| {
+            ClassifiedError::with_kind(
+                ErrorKind::FileMissing,
+                format!("{} does not exist.", request.dll_path.display()),
+            )
+        })?;
+
+        progress.set_status(format!("Downloading {}...", hack.name));
+        timed(record, "download", || {
+            hack.download(
+                &progress.downloader,
+                request.dll_path.to_string_lossy().to_string(),
+            )
+        })?;
+        progress.set_status("Downloaded.");
+        log::debug!("Downloaded {}", hack.name);
+    }
+
+    progress.delay(1);
+    progress.set_status("Injecting...");
+    progress.delay(1);
+
+    match request.method {
+        InjectionMethod::Standard => inject_standard(request, record).map(Some),
+        // Manual-mapped images never show up as modules, so there's no base to keep.
+        InjectionMethod::ManualMap => inject_manual_map(request, progress, record).map(|()| None),
+    }
+}
+
+/// Returns the base address of the loaded module.
+fn inject_standard(
+    request: &InjectionRequest,
+    record: &mut InjectionRecord,
+) -> Result<usize, ClassifiedError> {
+    let pid =
+        open_process(&request.process, request.pid).ok_or_else(|| process_not_found(request))?;
+    record.pid = Some(pid);
+
+    timed(record, "validate", || {
+        validate_dll(&request.dll_path, platform::processes().arch(pid), false)
+    })?;
+
+    let base = timed(record, "inject", || {
+        platform::injector().inject(pid, &request.dll_path)
+    })?;
+
+    Ok(base)
+}
+
+fn inject_manual_map(
+    request: &InjectionRequest,
+    progress: &InjectionProgress,
+    record: &mut InjectionRecord,
+) -> Result<(), ClassifiedError> {
+    let pid =
+        open_process(&request.process, request.pid).ok_or_else(|| process_not_found(request))?;
+    record.pid = Some(pid);
+    let target_arch = Some(platform::processes().arch(pid))
+        .filter(|arch| *arch != ProcessArch::Unknown)
+        .or_else(|| {
+            PeInfo::from_file(&request.dll_path)
+                .ok()
+                .map(|info| info.arch())
+        })
+        .unwrap_or(ProcessArch::X86);
+
+    timed(record, "validate", || {
+        validate_dll(&request.dll_path, target_arch, true)
+    })?;
+
+    log::debug!("Using {} injector", target_arch);
+
+    let status = progress.injectors.lock().unwrap().status(target_arch);
+    if matches!(status, InjectorStatus::Missing | InjectorStatus::Modified) {
+        progress.set_status("Downloading manual map injector...");
+        progress.delay(2);
+    }
+    let file_path = timed(record, "injector_download", || {
+        injector_assets::ensure(&progress.injectors, &progress.downloader, target_arch)
+    })
+    .map_err(|e| {
+        ClassifiedError::with_kind(
+            ErrorKind::InjectorMissing,
+            format!("Manual map injector unavailable: {}", e),
+        )
+    })?;
+
+    progress.delay(1);
+    progress.set_status("Injecting with manual map injector...");
+
+    let output = timed(record, "inject", || {
+        run_injector(&file_path, &injector_args(pid, &request.dll_path), progress)
+    })?;
+
+    let result = InjectorResult::parse(&output)
+        .map_err(|e| ClassifiedError::with_kind(ErrorKind::InjectorMissing, e))?;
+    for timing in &result.timings {
+        record.add_stage(
+            &format!("injector_{}", timing.stage),
+            Duration::from_millis(timing.duration_ms),
+        );
+    }
+    log::info!("Manual map injector: {} {}", result.code, result.message);
+
+    let code = result.code;
+    result
+        .into_result()
+        .map_err(|e| match ErrorKind::from(code) {
+            // A code this loader doesn't know yet only leaves its message to go by.
+            ErrorKind::Unknown => ClassifiedError::new(e),
+            kind => ClassifiedError::with_kind(kind, e),
+        })?;
+    Ok(())
+}
+
+/// Runs the injector, forwarding its output to the log and status label as it arrives.
+/// Kills it if it runs past the configured timeout.
+fn run_injector(
+    file_path: &Path,
+    args: &[String],
+    progress: &InjectionProgress,
+) -> Result<InjectorOutput, ClassifiedError> {
+    let mut child = Command::new(file_path)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| {
+            ClassifiedError::with_kind(
+                ErrorKind::InjectorMissing,
+                format!("Failed to execute injector: {}", e),
+            )
+        })?;
+
+    let (line_sender, line_receiver) = mpsc::channel();
+    let stdout_sender = line_sender.clone();
+    let stdout = child.stdout.take().map(|stdout| {
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                let _ = stdout_sender.send((false, line));
+            }
+        })
+    });
+    let stderr = child.stderr.take().map(|stderr| {
+        thread::spawn(move || {
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                let _ = line_sender.send((true, line));
+            }
+        })
+    });
+
+    let deadline = Instant::now() + progress.injector_timeout;
+    let mut output = InjectorOutput::default();
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        match line_receiver.recv_timeout(remaining) {
+            Ok((is_stderr, line)) => {
+                let line = line.trim().to_string();
+                if line.is_empty() {
+                    continue;
+                }
+
+                if is_stderr {
+                    log::warn!("[injector] {}", line);
+                    output.stderr.push_str(&line);
+                    output.stderr.push('\n');
+                } else {
+                    log::info!("[injector] {}", line);
+                    // The final JSON result is parsed by the caller, don't show it as status.
+                    if !line.starts_with('{') {
+                        progress.set_status(format!("Injector: {}", line));
+                    }
+                    output.stdout.push_str(&line);
+                    output.stdout.push('\n');
+                }
+            }
+            // Both pipes closed, the injector is done writing.
+            Err(RecvTimeoutError::Disconnected) => break,
+            Err(RecvTimeoutError::Timeout) => {
+                log::error!(
+                    "Injector did not finish within {} s, killing it",
+                    progress.injector_timeout.as_secs()
+                );
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!(
+                    "The injector timed out after {} seconds.",
+                    progress.injector_timeout.as_secs()
+                )
+                .into());
+            }
+        }
+    }
+
+    for reader in [stdout, stderr].into_iter().flatten() {
+        let _ = reader.join();
+    }
+
+    // Closing its output doesn't mean it exited, so keep holding it to the deadline.
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() < deadline => thread::sleep(Duration::from_millis(50)),
+            Ok(None) => {
+                log::error!(
+                    "Injector closed its output but didn't exit within {} s, killing it",
+                    progress.injector_timeout.as_secs()
+                );
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!(
+                    "The injector timed out after {} seconds.",
+                    progress.injector_timeout.as_secs()
+                )
+                .into());
+            }
+            Err(e) => return Err(format!("Failed to wait for injector: {}", e).into()),
+        }
+    };
+    log::debug!("Injector exited with {}", status);
+    output.exit_code = status.code();
+
+    Ok(output)
+}
+
+/// Describes why `request` looks like a second injection of the same DLL, either one we
+/// injected earlier or one the target already has loaded. Hashes the DLL and looks through
+/// the target's modules, so keep it off the UI thread.
+fn find_duplicate(modules: &Mutex<ModuleTracker>, request: &InjectionRequest) -> Option<String> {
+    let pid = open_process(&request.process, request.pid)?;
+    let sha256 = sha256_file(&request.dll_path).ok();
+
+    let tracked = modules
+        .lock()
+        .unwrap()
+        .find(pid, &request.dll_path, sha256.as_deref())
+        .cloned();
+    if let Some(module) = tracked {
+        return Some(format!(
+            "{} was already injected into {} ({}) at {}.",
+            module.name,
+            module.process,
+            pid,
+            module.injected_at.format("%H:%M:%S")
+        ));
+    }
+
+    modules::find_loaded(pid, &request.dll_path, sha256.as_deref()).map(|loaded| {
+        format!(
+            "{} is already loaded in {} ({}) from {}.",
+            request.name,
+            request.process,
+            pid,
+            loaded.display()
+        )
+    })
+}
+
+// MARK: Launch & Inject
+/// Starts `hack`'s game through Steam with `options`, waits up to `timeout` for it to
+/// load and injects into it.
+pub fn launch_and_inject(
+    hack: &Hack,
+    app_id: u32,
+    options: &str,
+    timeout: Duration,
+    progress: &InjectionProgress,
+) {
+    let deadline = Instant::now() + timeout;
+
+    let launched = SteamAccount::launch_game(app_id, options)
+        .and_then(|()| wait_for_process(&hack.process, deadline, progress))
+        .and_then(|pid| {
+            wait_for_modules(
+                &hack.process,
+                pid,
+                &hack.required_modules,
+                deadline,
+                progress,
+            )
+            .map(|()| pid)
+        });
+
+    match launched {
+        Ok(pid) => {
+            execute_request(&InjectionRequest::from_hack(hack, Some(pid)), progress);
+        }
+        Err(e) => {
+            let request = InjectionRequest::from_hack(hack, None);
+            let mut record = request.record();
+            report_failure(progress, &mut record, "Failed to launch", e);
+            progress.send(InjectionEvent::Recorded(record));
+        }
+    }
+}
+
+// MARK: Chains
+/// Resolves every step of `chain` against `hacks`. `pid_for` picks the instance a step's
+/// process should be injected into.
+pub fn chain_requests(
+    chain: &InjectionChain,
+    hacks: &[Hack],
+    source: InjectionSource,
+    pid_for: impl Fn(&str) -> Option<u32>,
+) -> Result<Vec<(InjectionRequest, u64)>, String> {
+    let mut requests = Vec::new();
+    for step in &chain.steps {
+        let mut request = match &step.target {
+            ChainTarget::Catalog { id, source, name } => {
+                let hack = find_step_hack(hacks, *id, source.as_deref(), name)
+                    .ok_or_else(|| format!("{} is not in the catalog.", name))?;
+                InjectionRequest::from_hack(&hack, pid_for(&hack.process))
+            }
+            ChainTarget::Local { path } => {
+                InjectionRequest::from_file(path.clone(), &chain.process, pid_for(&chain.process))
+            }
+        };
+        request.source = source;
+        requests.push((request, step.delay_ms));
+    }
+
+    if requests.is_empty() {
+        return Err(format!("{} has no steps.", chain.name));
+    }
+
+    Ok(requests)
+}
+
+/// Finds the hack of a catalog step by its id, which survives renames. Ids are only unique
+/// within one catalog source, so if the id still fits several hacks, e.g. a step saved
+/// before the source was stored, the name decides.
+fn find_step_hack(
+    hacks: &[Hack],
+    id: Option<u32>,
+    source: Option<&str>,
+    name: &str,
+) -> Option<Hack> {
+    let Some(id) = id else {
+        return get_hack_by_name(hacks, name);
+    };
+
+    let candidates = hacks
+        .iter()
+        .filter(|hack| hack.id == id && source.is_none_or(|source| hack.source == source))
+        .collect::<Vec<_>>();
+    match candidates.as_slice() {
+        [hack] => Some((*hack).clone()),
+        [] => None,
+        _ => candidates
+            .iter()
+            .find(|hack| hack.name == name)
+            .map(|hack| (*hack).clone())
+            .or_else(|| get_hack_by_name(hacks, name)),
+    }
+}
+
+/// Runs the resolved steps of `chain` one after another, waiting each step's delay first.
+pub fn run_chain(
+    chain: &InjectionChain,
+    requests: &[(InjectionRequest, u64)],
+    progress: &InjectionProgress,
+) {
+    let total = requests.len();
+    let mut succeeded = 0;
+
+    for (index, (request, delay_ms)) in requests.iter().enumerate() {
+        if *delay_ms > 0 {
+            progress.set_status(format!(
+                "Waiting {} ms before step {}/{}...",
+                delay_ms,
+                index + 1,
+                total
+            ));
+            thread::sleep(Duration::from_millis(*delay_ms));
+        }
+
+        progress.set_status(format!("Step {}/{}: {}", index + 1, total, request.name));
+
+        if execute_request(request, progress) {
+            succeeded += 1;
+        } else if chain.stop_on_failure {
+            log::warn!("Chain {} stopped at step {}", chain.name, index + 1);
+            break;
+        }
+    }
+
+    let summary = format!(
+        "Chain {} finished: {}/{} injected.",
+        chain.name, succeeded, total
+    );
+    if succeeded == total {
+        progress.set_status(summary);
+    } else {
+        progress.set_status(format!("Failed: {}", summary));
+    }
+}
+
+enum InjectionFailure {
+    Failed(ClassifiedError),
+    /// The target died during the post-injection health check.
+    Crashed,
+}
+
+/// Confirms the injection took: a standard-injected module must show up in the target's
+/// module list, and the process must survive the grace period. Manual-mapped DLLs are
+/// never in the module list, so only the liveness check applies to them.
+fn check_health(
+    request: &InjectionRequest,
+    pid: u32,
+    grace: Duration,
+    progress: &InjectionProgress,
+) -> Result<(), InjectionFailure> {
+    progress.set_status("Confirming injection...");
+
+    if has_exited(pid) {
+        return Err(InjectionFailure::Crashed);
+    }
+
+    if request.method == InjectionMethod::Standard {
+        match platform::processes().module_by_path(pid, &request.dll_path) {
+            Ok(Some(_)) => {}
+            Ok(None) => {
+                return Err(InjectionFailure::Failed(ClassifiedError::with_kind(
+                    ErrorKind::Unknown,
+                    format!(
+                        "{} is not in the module list of {}.",
+                        request.name, request.process
+                    ),
+                )))
+            }
+            Err(e) => log::warn!("Couldn't list modules of {}: {}", request.process, e),
+        }
+    }
+
+    let deadline = Instant::now() + grace;
+    while Instant::now() < deadline {
+        if has_exited(pid) {
+            return Err(InjectionFailure::Crashed);
+        }
+        thread::sleep(Duration::from_millis(250));
+    }
+
+    if has_exited(pid) {
+        Err(InjectionFailure::Crashed)
+    } else {
+        Ok(())
+    }
+}
+
+/// Polls until `process` is running, returning its pid.
+fn wait_for_process(
+    process: &str,
+    deadline: Instant,
+    progress: &InjectionProgress,
+) -> Result<u32, String> {
+    let started = Instant::now();
+    loop {
+        if let Some(pid) = open_process(process, None) {
+            log::info!("{} started with pid {}", process, pid);
+            return Ok(pid);
+        }
+
+        if Instant::now() >= deadline {
+            return Err(format!("{} did not start in time.", process));
+        }
+
+        progress.set_status(format!(
+            "Waiting for {} to start... ({} s)",
+            process,
+            started.elapsed().as_secs()
+        ));
+        thread::sleep(Duration::from_secs(1));
+    }
+}
+
+/// Polls until every module in `modules` is loaded, so the hack doesn't go in too early.
+fn wait_for_modules(
+    process: &str,
+    pid: u32,
+    modules: &[String],
+    deadline: Instant,
+    progress: &InjectionProgress,
+) -> Result<(), String> {
+    loop {
+        if !is_alive(pid) {
+            return Err(format!("{} exited while loading.", process));
+        }
+
+        let missing = modules
+            .iter()
+            .filter(|module| {
+                !matches!(
+                    platform::processes().module_by_name(pid, module),
+                    Ok(Some(_))
+                )
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        if Instant::now() >= deadline {
+            return Err(format!(
+                "{} did not finish loading in time (missing {}).",
+                process,
+                missing.join(", ")
+            ));
+        }
+
+        progress.set_status(format!(
+            "Waiting for {} to load {}...",
+            process,
+            missing.join(", ")
+        ));
+        thread::sleep(Duration::from_secs(1));
+    }
+}
+
+/// Waits up to `timeout` for the target of `request` to start and finish loading, then
+/// injects into that instance.
+pub fn execute_when_running(
+    mut request: InjectionRequest,
+    timeout: Duration,
+    progress: &InjectionProgress,
+) {
+    let deadline = Instant::now() + timeout;
+    let modules = request
+        .hack
+        .as_ref()
+        .map(|hack| hack.required_modules.clone())
+        .unwrap_or_default();
+
+    let started = wait_for_process(&request.process, deadline, progress).and_then(|pid| {
+        wait_for_modules(&request.process, pid, &modules, deadline, progress).map(|()| pid)
+    });
+
+    match started {
+        Ok(pid) => {
+            request.pid = Some(pid);
+            execute_request(&request, progress);
+        }
+        Err(e) => {
+            let mut record = request.record();
+            report_failure(progress, &mut record, "Failed to inject", e);
+            progress.send(InjectionEvent::Recorded(record));
+        }
+    }
+}
+
+/// Classifies `error`, shows it with its remediation hint and logs it with its code.
+fn report_failure(
+    progress: &InjectionProgress,
+    record: &mut InjectionRecord,
+    context: &str,
+    error: impl Into<ClassifiedError>,
+) {
+    let error = error.into();
+    let message = format!("{}: {}", context, error.with_hint());
+
+    progress.set_status(&message);
+    log::error!("{}: {}", context, error);
+    record.error = Some(error.message);
+    record.error_kind = Some(error.kind);
+    progress.send(InjectionEvent::Failed(message));
+}
+
+/// Runs one request to completion, reporting the outcome to the UI and history.
+/// Returns whether the injection succeeded.
+pub fn execute_request(request: &InjectionRequest, progress: &InjectionProgress) -> bool {
+    let mut record = request.record();
+
+    if request.on_duplicate != OnDuplicate::Inject {
+        if let Some(reason) = find_duplicate(&progress.modules, request) {
+            log::warn!("{}", reason);
+            if request.on_duplicate == OnDuplicate::Ask {
+                progress.set_status("Waiting for confirmation...");
+                progress.send(InjectionEvent::Duplicate(Box::new(request.clone()), reason));
+            } else {
+                let error = ClassifiedError::with_kind(ErrorKind::ModuleAlreadyLoaded, reason);
+                report_failure(progress, &mut record, "Not injected", error);
+                progress.send(InjectionEvent::Recorded(record));
+            }
+            return false;
+        }
+    }
+
+    let injected = run_injection(request, progress, &mut record).map_err(InjectionFailure::Failed);
+    let result = match (injected, progress.health_check, record.pid) {
+        (Ok(base), Some(grace), Some(pid)) => timed(&mut record, "health_check", || {
+            check_health(request, pid, grace, progress)
+        })
+        .map(|()| base),
+        (injected, ..) => injected,
+    };
+
+    let succeeded = match result {
+        Ok(base) => {
+            if let Some(pid) = record.pid {
+                let module = request.injected_module(pid, base);
+                progress.send(InjectionEvent::ModuleLoaded(module));
+            }
+            progress.set_status("Injection successful.");
+            log::info!("Injected {} into {}", request.name, request.process);
+            record.outcome = InjectionOutcome::Success;
+            progress.send(InjectionEvent::Succeeded(request.name.clone()));
+            true
+        }
+        Err(InjectionFailure::Crashed) => {
+            let message = format!("{} crashed after injection.", request.process);
+            progress.set_status(format!("Failed: {}", message));
+            log::error!(
+                "{} crashed after injecting {}",
+                request.process,
+                request.name
+            );
+            record.outcome = InjectionOutcome::Crashed;
+            record.error = Some(message.clone());
+            record.error_kind = Some(ErrorKind::ProcessExited);
+            progress.send(InjectionEvent::Crashed(request.name.clone(), message));
+            false
+        }
+        Err(InjectionFailure::Failed(e)) => {
+            report_failure(progress, &mut record, "Failed to inject", e);
+            false
+        }
+    };
+
+    progress.send(InjectionEvent::Recorded(record));
+    succeeded
+}
+
+// MARK: Headless
+/// Runs `request` to completion without a window, optionally waiting up to `wait` for the
+/// target to start first. Status changes are passed to `on_status` as they happen.
+/// Returns the history record, which the caller is responsible for persisting.
+pub fn inject_headless(
+    request: InjectionRequest,
+    config: &Config,
+    http: Http,
+    wait: Option<Duration>,
+    mut on_status: impl FnMut(&str),
+) -> Result<InjectionRecord, String> {
+    let (message_sender, message_receiver) = mpsc::channel();
+    let status_message = Arc::new(Mutex::new(String::new()));
+    let progress = InjectionProgress::new(
+        config,
+        http,
+        Arc::clone(&status_message),
+        message_sender,
+        Arc::new(|| {}),
+        Arc::new(Mutex::new(InjectorAssets::load())),
+        Arc::new(Mutex::new(ModuleTracker::default())),
+    );
+
+    let worker = thread::spawn(move || match wait {
+        Some(timeout) => execute_when_running(request, timeout, &progress),
+        None => {
+            execute_request(&request, &progress);
+        }
+    });
+
+    let mut last_status = String::new();
+    let mut report_status = |last_status: &mut String| {
+        let status = status_message.lock().unwrap().clone();
+        if !status.is_empty() && status != *last_status {
+            on_status(&status);
+            *last_status = status;
+        }
+    };
+
+    while !worker.is_finished() {
+        report_status(&mut last_status);
+        thread::sleep(Duration::from_millis(100));
+    }
+    report_status(&mut last_status);
+
+    worker
+        .join()
+        .map_err(|_| "The injection thread panicked.".to_string())?;
+
+    message_receiver
+        .try_iter()
+        .find_map(|event| match event {
+            InjectionEvent::Recorded(record) => Some(record),
+            _ => None,
+        })
+        .ok_or_else(|| "The injection finished without a result.".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, sync::mpsc::Receiver};
+
+    use super::*;
+    use crate::{
+        chains::ChainStep,
+        http::StaticTransport,
+        pe::fixtures,
+        platform::testing::{self, FakeProcesses},
+    };
+
+    fn progress() -> (InjectionProgress, Receiver<InjectionEvent>) {
+        let config = Config {
+            skip_injects_delay: true,
+            health_check: true,
+            health_check_grace_secs: 0,
+            ..Config::default()
+        };
+        let (sender, receiver) = mpsc::channel();
+        let progress = InjectionProgress::new(
+            &config,
+            Arc::new(StaticTransport::default()),
+            Arc::new(Mutex::new(String::new())),
+            sender,
+            Arc::new(|| {}),
+            Arc::new(Mutex::new(InjectorAssets::default())),
+            Arc::new(Mutex::new(ModuleTracker::default())),
+        );
+        (progress, receiver)
+    }
+
+    fn dll(name: &str, machine: u16) -> PathBuf {
+        let dir = env::temp_dir().join(format!("unknproject-inject-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        fs::write(&path, fixtures::dll(machine)).unwrap();
+        path
+    }
+
+    fn game(processes: &FakeProcesses) {
+        processes.spawn(100, "game.exe", ProcessArch::X86, 1_000);
+    }
+
+    fn recorded(receiver: &Receiver<InjectionEvent>) -> InjectionRecord {
+        receiver
+            .try_iter()
+            .find_map(|event| match event {
+                InjectionEvent::Recorded(record) => Some(record),
+                _ => None,
+            })
+            .expect("no record was sent")
+    }
+
+    #[test]
+    fn manual_map_targets_have_a_published_injector() {
+        use crate::{
+            hash::sha256_hex,
+            injector_assets::{injector_file_name, InjectorManifest},
+        };
+
+        let manifest: InjectorManifest =
+            serde_json::from_str(include_str!("../../resources/hacks/injector-manifest.json"))
+                .unwrap();
+        let injector = include_bytes!("../../resources/hacks/unknproject.exe");
+
+        for game in ["CS:GO", "CS2"] {
+            assert_eq!(InjectionMethod::for_game(game), InjectionMethod::ManualMap);
+        }
+        for arch in [ProcessArch::X86, ProcessArch::X64, ProcessArch::Unknown] {
+            let published = manifest
+                .get(arch)
+                .unwrap_or_else(|| panic!("no manifest entry for {} targets", arch));
+            assert_eq!(injector_file_name(arch), "unknproject.exe");
+            assert_eq!(published.sha256, sha256_hex(injector));
+        }
+    }
+
+    #[test]
+    fn standard_injection_reports_the_loaded_module() {
+        let (processes, injector) = testing::install();
+        game(processes);
+        let (progress, receiver) = progress();
+        let request = InjectionRequest::from_file(dll("ok.dll", fixtures::I386), "game.exe", None);
+
+        assert!(execute_request(&request, &progress));
+        assert_eq!(injector.injected.lock().unwrap().len(), 1);
+
+        let events: Vec<InjectionEvent> = receiver.try_iter().collect();
+        assert!(events.iter().any(|event| matches!(
+            event,
+            InjectionEvent::ModuleLoaded(module) if module.pid == 100
+        )));
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, InjectionEvent::Succeeded(name) if name == "ok.dll")));
+        let record = events
+            .into_iter()
+            .find_map(|event| match event {
+                InjectionEvent::Recorded(record) => Some(record),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(record.outcome, InjectionOutcome::Success);
+        assert_eq!(record.pid, Some(100));
+    }
+
+    #[test]
+    fn modules_are_ejected_by_their_base_address() {
+        let (processes, _) = testing::install();
+        game(processes);
+        let (progress, receiver) = progress();
+        let path = dll("eject.dll", fixtures::I386);
+        let request = InjectionRequest::from_file(path.clone(), "game.exe", None);
+
+        assert!(execute_request(&request, &progress));
+        let module = receiver
+            .try_iter()
+            .find_map(|event| match event {
+                InjectionEvent::ModuleLoaded(module) => Some(module),
+                _ => None,
+            })
+            .unwrap();
+        assert!(module.ejectable());
+        let tracker = Mutex::new(ModuleTracker::default());
+        tracker.lock().unwrap().track(module.clone());
+
+        modules::eject(&tracker, &module).unwrap();
+        assert_eq!(platform::processes().module_by_path(100, &path), Ok(None));
+        assert!(tracker.lock().unwrap().for_process("game.exe").is_empty());
+
+        // Unloaded behind our back: reported, and no longer tracked either.
+        tracker.lock().unwrap().track(module.clone());
+        let error = modules::eject(&tracker, &module).unwrap_err();
+        assert_eq!(error, "eject.dll is no longer loaded.");
+        assert!(tracker.lock().unwrap().for_process("game.exe").is_empty());
+    }
+
+    #[test]
+    fn architecture_mismatch_never_reaches_the_injector() {
+        let (processes, injector) = testing::install();
+        game(processes);
+        let (progress, receiver) = progress();
+        let request =
+            InjectionRequest::from_file(dll("x64.dll", fixtures::AMD64), "game.exe", Some(100));
+
+        assert!(!execute_request(&request, &progress));
+        assert!(injector.injected.lock().unwrap().is_empty());
+        let record = recorded(&receiver);
+        assert_eq!(record.outcome, InjectionOutcome::Failure);
+        assert_eq!(record.error_kind, Some(ErrorKind::ArchitectureMismatch));
+        assert!(record.error.unwrap().contains("Architecture mismatch"));
+    }
+
+    #[test]
+    fn missing_process_and_injector_errors_fail() {
+        let (processes, injector) = testing::install();
+        let (progress, receiver) = progress();
+        let request =
+            InjectionRequest::from_file(dll("fail.dll", fixtures::I386), "game.exe", None);
+
+        assert!(!execute_request(&request, &progress));
+        let record = recorded(&receiver);
+        assert_eq!(record.error_kind, Some(ErrorKind::ProcessExited));
+        assert!(record
+            .error
+            .unwrap()
+            .contains("Process 'game.exe' not found."));
+
+        game(processes);
+        *injector.fail_with.lock().unwrap() = Some("Access is denied.".to_string());
+        assert!(!execute_request(&request, &progress));
+        assert!(recorded(&receiver)
+            .error
+            .unwrap()
+            .contains("Access is denied."));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn injectors_that_close_their_output_but_hang_are_killed() {
+        let (sender, _receiver) = mpsc::channel();
+        let progress = InjectionProgress::new(
+            &Config {
+                injector_timeout_secs: 1,
+                ..Config::default()
+            },
+            Arc::new(StaticTransport::default()),
+            Arc::new(Mutex::new(String::new())),
+            sender,
+            Arc::new(|| {}),
+            Arc::new(Mutex::new(InjectorAssets::default())),
+            Arc::new(Mutex::new(ModuleTracker::default())),
+        );
+        let args = ["-c", "echo started; exec >&- 2>&-; sleep 30"].map(String::from);
+
+        let started = Instant::now();
+        let error = run_injector(Path::new("/bin/sh"), &args, &progress).unwrap_err();
+        assert!(error.message.contains("timed out"));
+        assert!(started.elapsed() < Duration::from_secs(10));
+
+        let args = ["-c", "echo done; exit 3"].map(String::from);
+        let output = run_injector(Path::new("/bin/sh"), &args, &progress).unwrap();
+        assert_eq!(output.stdout, "done\n");
+        assert_eq!(output.exit_code, Some(3));
+    }
+
+    #[test]
+    fn duplicates_fail_ask_or_inject_as_requested() {
+        let (processes, injector) = testing::install();
+        game(processes);
+        let (progress, receiver) = progress();
+        let mut request =
+            InjectionRequest::from_file(dll("twice.dll", fixtures::I386), "game.exe", None);
+
+        assert!(execute_request(&request, &progress));
+        // The frontend tracks what was injected, and the target has it loaded as well.
+        let events: Vec<InjectionEvent> = receiver.try_iter().collect();
+        let module = events
+            .into_iter()
+            .find_map(|event| match event {
+                InjectionEvent::ModuleLoaded(module) => Some(module),
+                _ => None,
+            })
+            .unwrap();
+        progress.modules.lock().unwrap().track(module);
+
+        assert!(!execute_request(&request, &progress));
+        let record = recorded(&receiver);
+        assert_eq!(record.error_kind, Some(ErrorKind::ModuleAlreadyLoaded));
+        assert!(record.error.unwrap().contains("already injected"));
+
+        request.on_duplicate = OnDuplicate::Ask;
+        assert!(!execute_request(&request, &progress));
+        let events: Vec<InjectionEvent> = receiver.try_iter().collect();
+        assert!(
+            matches!(&events[..], [InjectionEvent::Duplicate(asked, _)] if asked.name == "twice.dll")
+        );
+
+        // Loaded by someone else, so only the target's module list knows.
+        {
+            let mut modules = progress.modules.lock().unwrap();
+            let tracked = modules.for_process("game.exe");
+            modules.untrack(&tracked[0]);
+        }
+        request.on_duplicate = OnDuplicate::Fail;
+        assert!(!execute_request(&request, &progress));
+        assert!(recorded(&receiver)
+            .error
+            .unwrap()
+            .contains("already loaded"));
+
+        request.on_duplicate = OnDuplicate::Inject;
+        assert!(execute_request(&request, &progress));
+        assert_eq!(injector.injected.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn crashes_after_injection_are_detected() {
+        let (processes, injector) = testing::install();
+        game(processes);
+        *injector.crash.lock().unwrap() = true;
+        let (progress, receiver) = progress();
+        let request =
+            InjectionRequest::from_file(dll("crash.dll", fixtures::I386), "game.exe", None);
+
+        assert!(!execute_request(&request, &progress));
+        let events: Vec<InjectionEvent> = receiver.try_iter().collect();
+        assert!(!events
+            .iter()
+            .any(|event| matches!(event, InjectionEvent::ModuleLoaded(_))));
+        let record = events
+            .into_iter()
+            .find_map(|event| match event {
+                InjectionEvent::Recorded(record) => Some(record),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(record.outcome, InjectionOutcome::Crashed);
+        assert_eq!(record.error_kind, Some(ErrorKind::ProcessExited));
+    }
+
+    #[test]
+    fn health_check_without_access_is_not_a_crash() {
+        let (processes, _) = testing::install();
+        game(processes);
+        let (progress, _receiver) = progress();
+        let request =
+            InjectionRequest::from_file(dll("denied.dll", fixtures::I386), "game.exe", None);
+        processes.deny_access(100);
+
+        assert!(check_health(&request, 100, Duration::ZERO, &progress).is_ok());
+        assert!(matches!(
+            check_health(&request, 200, Duration::ZERO, &progress),
+            Err(InjectionFailure::Crashed)
+        ));
+    }
+
+    #[test]
+    fn unreadable_and_invalid_dlls_are_classified() {
+        let (processes, _) = testing::install();
+        game(processes);
+        let (progress, receiver) = progress();
+
+        let missing = env::temp_dir().join("unknproject-inject-missing.dll");
+        let request = InjectionRequest::from_file(missing, "game.exe", None);
+        assert!(!execute_request(&request, &progress));
+        assert_eq!(recorded(&receiver).error_kind, Some(ErrorKind::FileMissing));
+
+        let path = dll("garbage.dll", fixtures::I386);
+        fs::write(&path, b"MZ not a valid pe").unwrap();
+        let request = InjectionRequest::from_file(path, "game.exe", None);
+        assert!(!execute_request(&request, &progress));
+        assert_eq!(recorded(&receiver).error_kind, Some(ErrorKind::Unknown));
+    }
+
+    #[test]
+    fn chain_steps_find_hacks_by_id_and_old_steps_by_name() {
+        let hacks = [Hack::new(
+            7, "Aim v2", "", "", "", "aim.dll", "game.exe", "", "CS2",
+        )];
+        let step = |id, name: &str| ChainStep {
+            target: ChainTarget::Catalog {
+                id,
+                source: None,
+                name: name.to_string(),
+            },
+            delay_ms: 0,
+        };
+        let mut chain = InjectionChain::new("Chain", "game.exe");
+        chain.steps = vec![step(Some(7), "Aim"), step(None, "Aim v2")];
+
+        let requests = chain_requests(&chain, &hacks, InjectionSource::Chain, |_| None).unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(requests.iter().all(|(request, _)| request.name == "Aim v2"));
+
+        chain.steps = vec![step(Some(8), "Aim v2")];
+        let error = chain_requests(&chain, &hacks, InjectionSource::Chain, |_| None).unwrap_err();
+        assert_eq!(error, "Aim v2 is not in the catalog.");
+    }
+
+    #[test]
+    fn chain_steps_tell_hacks_with_the_same_id_apart() {
+        let hack = |id, name: &str, source: &str| {
+            Hack::new(id, name, "", "", "", "a.dll", "game.exe", source, "CS2")
+        };
+        let hacks = [
+            hack(3, "Aim", "https://one.test/"),
+            hack(3, "Wallhack", "https://two.test/"),
+            hack(0, "Radar", "https://two.test/"),
+            hack(0, "Bhop", "https://two.test/"),
+        ];
+        let name_of = |target: ChainTarget| {
+            let mut chain = InjectionChain::new("Chain", "game.exe");
+            chain.steps = vec![ChainStep {
+                target,
+                delay_ms: 0,
+            }];
+            chain_requests(&chain, &hacks, InjectionSource::Chain, |_| None)
+                .map(|requests| requests[0].0.name.clone())
+        };
+
+        // The source picks out the hack, even after it was renamed.
+        let wallhack = ChainTarget::catalog(&hacks[1]);
+        assert_eq!(name_of(wallhack.clone()).unwrap(), "Wallhack");
+        let ChainTarget::Catalog { id, source, .. } = wallhack else {
+            unreachable!()
+        };
+        let renamed = ChainTarget::Catalog {
+            id,
+            source,
+            name: "Old name".to_string(),
+        };
+        assert_eq!(name_of(renamed).unwrap(), "Wallhack");
+
+        // Without a source the id is ambiguous and the name decides.
+        let without_source = ChainTarget::Catalog {
+            id: Some(3),
+            source: None,
+            name: "Wallhack".to_string(),
+        };
+        assert_eq!(name_of(without_source).unwrap(), "Wallhack");
+
+        // Hacks without an id are only found by name.
+        let radar = ChainTarget::catalog(&hacks[2]);
+        assert!(matches!(radar, ChainTarget::Catalog { id: None, .. }));
+        assert_eq!(name_of(radar).unwrap(), "Radar");
+    }
+}
@@ -0,0 +1,294 @@
+//! Command-line contract for the external manual map injector.
+//!
+//! The loader runs the injector as
+//! `unknproject[64].exe --protocol 1 --pid <pid> --dll <path>` and expects exactly one
+//! JSON object on the last line of stdout:
+//!
+//! ```json
+//! {"protocol":1,"code":"ok","message":"Injected","timings":[{"stage":"map","duration_ms":12}]}
+//! ```
+//!
+//! Anything printed before that line is treated as progress output. Injectors that
+//! predate the protocol can't parse these arguments and print no result, so a run
+//! without one fails, whatever its exit code says.
+
+use std::{fmt, path::Path};
+
+use serde::Deserialize;
+
+use crate::history::StageTiming;
+
+pub const PROTOCOL_VERSION: u32 = 1;
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum InjectorCode {
+    Ok,
+    InvalidArguments,
+    UnsupportedProtocol,
+    ProcessNotFound,
+    OpenProcessFailed,
+    ReadDllFailed,
+    InvalidImage,
+    ArchitectureMismatch,
+    AllocationFailed,
+    WriteMemoryFailed,
+    RelocationFailed,
+    ImportResolutionFailed,
+    ThreadCreationFailed,
+    EntryPointFailed,
+    #[serde(other)]
+    Unknown,
+}
+
+impl InjectorCode {
+    pub fn user_message(&self) -> &'static str {
+        match self {
+            InjectorCode::Ok => "Injected successfully.",
+            InjectorCode::InvalidArguments => {
+                "The injector rejected its arguments. Try redownloading the injector."
+            }
+            InjectorCode::UnsupportedProtocol => {
+                "The injector is outdated or too new for this loader. Redownload the injector."
+            }
+            InjectorCode::ProcessNotFound => {
+                "The target process exited before the injector could attach."
+            }
+            InjectorCode::OpenProcessFailed => {
+                "Couldn't open the target process. Try running the loader as administrator."
+            }
+            InjectorCode::ReadDllFailed => "The injector couldn't read the DLL file.",
+            InjectorCode::InvalidImage => "The DLL is not a valid PE image.",
+            InjectorCode::ArchitectureMismatch => {
+                "The DLL architecture doesn't match the target process."
+            }
+            InjectorCode::AllocationFailed => "Couldn't allocate memory in the target process.",
+            InjectorCode::WriteMemoryFailed => "Couldn't write the DLL into the target process.",
+            InjectorCode::RelocationFailed => "Couldn't apply relocations to the mapped DLL.",
+            InjectorCode::ImportResolutionFailed => {
+                "A dependency of the DLL couldn't be resolved in the target process."
+            }
+            InjectorCode::ThreadCreationFailed => "Couldn't start a thread in the target process.",
+            InjectorCode::EntryPointFailed => "The DLL entry point reported a failure.",
+            InjectorCode::Unknown => "The injector reported an unknown error.",
+        }
+    }
+}
+
+/// Prints the wire code, e.g. `open_process_failed`.
+impl fmt::Display for InjectorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let code = match self {
+            InjectorCode::Ok => "ok",
+            InjectorCode::InvalidArguments => "invalid_arguments",
+            InjectorCode::UnsupportedProtocol => "unsupported_protocol",
+            InjectorCode::ProcessNotFound => "process_not_found",
+            InjectorCode::OpenProcessFailed => "open_process_failed",
+            InjectorCode::ReadDllFailed => "read_dll_failed",
+            InjectorCode::InvalidImage => "invalid_image",
+            InjectorCode::ArchitectureMismatch => "architecture_mismatch",
+            InjectorCode::AllocationFailed => "allocation_failed",
+            InjectorCode::WriteMemoryFailed => "write_memory_failed",
+            InjectorCode::RelocationFailed => "relocation_failed",
+            InjectorCode::ImportResolutionFailed => "import_resolution_failed",
+            InjectorCode::ThreadCreationFailed => "thread_creation_failed",
+            InjectorCode::EntryPointFailed => "entry_point_failed",
+            InjectorCode::Unknown => "unknown",
+        };
+        write!(f, "{}", code)
+    }
+}
+
+/// Everything a finished injector run left behind.
+#[derive(Clone, Debug, Default)]
+pub struct InjectorOutput {
+    pub stdout: String,
+    pub stderr: String,
+    /// `None` if the injector was killed by a signal.
+    pub exit_code: Option<i32>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct InjectorResult {
+    pub protocol: u32,
+    pub code: InjectorCode,
+    #[serde(default)]
+    pub message: String,
+    #[serde(default)]
+    pub timings: Vec<StageTiming>,
+}
+
+impl InjectorResult {
+    /// Finds the result object on the last non-empty line of the injector's stdout.
+    pub fn parse(output: &InjectorOutput) -> Result<Self, String> {
+        let last_line = |text: &str| {
+            text.lines()
+                .map(str::trim)
+                .rfind(|line| !line.is_empty())
+                .map(str::to_string)
+        };
+
+        let line = last_line(&output.stdout).filter(|line| line.starts_with('{'));
+        let Some(line) = line else {
+            return Err(Self::missing_result(
+                output,
+                last_line(&output.stderr).or_else(|| last_line(&output.stdout)),
+            ));
+        };
+
+        let result = serde_json::from_str::<InjectorResult>(&line)
+            .map_err(|e| format!("Failed to parse injector result: {}", e))?;
+
+        if result.protocol != PROTOCOL_VERSION {
+            return Err(format!(
+                "Injector speaks protocol {}, expected {}. Redownload the injector.",
+                result.protocol, PROTOCOL_VERSION
+            ));
+        }
+
+        Ok(result)
+    }
+
+    /// An exit code of 0 proves nothing here: an injector that predates the protocol may
+    /// have ignored its arguments altogether.
+    fn missing_result(output: &InjectorOutput, last_output: Option<String>) -> String {
+        let Some(exit_code) = output.exit_code else {
+            return "The injector was terminated before it reported a result.".to_string();
+        };
+        log::warn!(
+            "Injector exited with {} without reporting a result",
+            exit_code
+        );
+
+        let mut message = format!(
+            "The injector didn't report a result (exit code {}), it may be too old for \
+             protocol {}. Redownload the injector.",
+            exit_code, PROTOCOL_VERSION
+        );
+        if let Some(last_output) = last_output {
+            message = format!("{} Last output: {}", message, last_output);
+        }
+        message
+    }
+
+    pub fn into_result(self) -> Result<Self, String> {
+        if self.code == InjectorCode::Ok {
+            return Ok(self);
+        }
+
+        log::error!("Injector failed with {}: {}", self.code, self.message);
+        if self.message.is_empty() {
+            Err(self.code.user_message().to_string())
+        } else {
+            Err(format!("{} ({})", self.code.user_message(), self.message))
+        }
+    }
+}
+
+pub fn injector_args(pid: u32, dll_path: &Path) -> Vec<String> {
+    vec![
+        "--protocol".to_string(),
+        PROTOCOL_VERSION.to_string(),
+        "--pid".to_string(),
+        pid.to_string(),
+        "--dll".to_string(),
+        dll_path.to_string_lossy().to_string(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output(stdout: &str, stderr: &str, exit_code: Option<i32>) -> InjectorOutput {
+        InjectorOutput {
+            stdout: stdout.to_string(),
+            stderr: stderr.to_string(),
+            exit_code,
+        }
+    }
+
+    #[test]
+    fn parses_the_last_line_after_progress_output() {
+        let stdout = "Mapping sections\nResolving imports\n\n\
+            {\"protocol\":1,\"code\":\"ok\",\"message\":\"Injected\",\
+            \"timings\":[{\"stage\":\"map\",\"duration_ms\":12}]}\n";
+        let result = InjectorResult::parse(&output(stdout, "", Some(0))).unwrap();
+
+        assert_eq!(result.code, InjectorCode::Ok);
+        assert_eq!(result.message, "Injected");
+        assert_eq!(result.timings.len(), 1);
+        assert_eq!(result.timings[0].duration_ms, 12);
+    }
+
+    #[test]
+    fn reported_codes_win_over_the_exit_code() {
+        let stdout = r#"{"protocol":1,"code":"open_process_failed","message":"access denied"}"#;
+        let result = InjectorResult::parse(&output(stdout, "", Some(0))).unwrap();
+
+        assert_eq!(result.code, InjectorCode::OpenProcessFailed);
+        let error = result.into_result().unwrap_err();
+        assert!(error.contains("administrator"));
+        assert!(error.contains("access denied"));
+    }
+
+    #[test]
+    fn unknown_codes_parse_as_unknown() {
+        let stdout = r#"{"protocol":1,"code":"something_new"}"#;
+        let result = InjectorResult::parse(&output(stdout, "", Some(1))).unwrap();
+        assert_eq!(result.code, InjectorCode::Unknown);
+    }
+
+    #[test]
+    fn rejects_other_protocols_and_broken_json() {
+        let newer = r#"{"protocol":2,"code":"ok"}"#;
+        assert!(InjectorResult::parse(&output(newer, "", Some(0)))
+            .unwrap_err()
+            .contains("protocol 2"));
+
+        let broken = r#"{"protocol":1,"code":"#;
+        assert!(InjectorResult::parse(&output(broken, "", Some(0)))
+            .unwrap_err()
+            .contains("Failed to parse"));
+    }
+
+    #[test]
+    fn runs_without_a_result_fail_whatever_the_exit_code() {
+        let error = InjectorResult::parse(&output("Injected!\n", "", Some(0))).unwrap_err();
+        assert!(
+            error.contains("didn't report a result (exit code 0)"),
+            "{}",
+            error
+        );
+        assert!(error.ends_with("Last output: Injected!"), "{}", error);
+
+        let error =
+            InjectorResult::parse(&output("Starting\n", "Could not open process\n", Some(5)))
+                .unwrap_err();
+        assert!(error.contains("exit code 5"), "{}", error);
+        assert!(
+            error.ends_with("Last output: Could not open process"),
+            "{}",
+            error
+        );
+
+        let error = InjectorResult::parse(&output("", "", Some(3))).unwrap_err();
+        assert!(!error.contains("Last output"), "{}", error);
+
+        assert!(InjectorResult::parse(&output("", "", None))
+            .unwrap_err()
+            .contains("terminated"));
+    }
+
+    #[test]
+    fn codes_display_as_wire_codes() {
+        assert_eq!(InjectorCode::Ok.to_string(), "ok");
+        assert_eq!(
+            InjectorCode::ImportResolutionFailed.to_string(),
+            "import_resolution_failed"
+        );
+
+        let parsed: InjectorCode = serde_json::from_str("\"entry_point_failed\"").unwrap();
+        assert_eq!(parsed.to_string(), "entry_point_failed");
+    }
+}
@@ -0,0 +1,606 @@
+use std::{
+    fmt, fs,
+    path::PathBuf,
+    sync::{Mutex, PoisonError},
+};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    downloader::Downloader,
+    hash::{sha256_file, sha256_hex},
+    processes::ProcessArch,
+};
+
+/// Published next to the injectors on the CDN.
+const REMOTE_MANIFEST: &str = "injector-manifest.json";
+
+/// Serializes downloads and file replacements, which happen without holding the
+/// `InjectorAssets` lock so the UI can keep reading it.
+static INSTALLING: Mutex<()> = Mutex::new(());
+
+/// Architectures with an injector of their own. Until `unknproject64.exe` is published,
+/// x64 targets are handled by the x86 injector, as they always have been.
+pub const INJECTOR_ARCHES: [ProcessArch; 1] = [ProcessArch::X86];
+
+/// The injector architecture that handles targets of `arch`.
+pub fn injector_arch(arch: ProcessArch) -> ProcessArch {
+    if INJECTOR_ARCHES.contains(&arch) {
+        arch
+    } else {
+        ProcessArch::X86
+    }
+}
+
+/// Manual map injector binary for the given target architecture.
+pub fn injector_file_name(arch: ProcessArch) -> &'static str {
+    match injector_arch(arch) {
+        ProcessArch::X64 => "unknproject64.exe",
+        _ => "unknproject.exe",
+    }
+}
+
+fn assets_dir() -> PathBuf {
+    let dir = crate::config_dir();
+
+    fs::create_dir_all(&dir).ok();
+    dir
+}
+
+pub fn injector_path(arch: ProcessArch) -> PathBuf {
+    assets_dir().join(injector_file_name(arch))
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RemoteInjector {
+    pub version: String,
+    pub sha256: String,
+}
+
+/// Hashes of the published injectors. An architecture without an entry has no
+/// published injector to verify against.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct InjectorManifest {
+    #[serde(default)]
+    pub x86: Option<RemoteInjector>,
+    #[serde(default)]
+    pub x64: Option<RemoteInjector>,
+}
+
+impl InjectorManifest {
+    pub fn fetch(downloader: &Downloader) -> Result<Self, String> {
+        downloader
+            .fetch_json(REMOTE_MANIFEST)
+            .map_err(|e| format!("Failed to fetch injector manifest: {}", e))
+    }
+
+    pub fn get(&self, arch: ProcessArch) -> Option<&RemoteInjector> {
+        match injector_arch(arch) {
+            ProcessArch::X64 => self.x64.as_ref(),
+            _ => self.x86.as_ref(),
+        }
+    }
+}
+
+/// What we know about an injector on disk.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct InjectorAsset {
+    pub version: String,
+    pub sha256: String,
+    pub source: String,
+    pub installed_at: DateTime<Local>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum InjectorStatus {
+    Missing,
+    /// Present but not installed by the asset manager, e.g. left over from an older loader.
+    Unverified,
+    Verified,
+    /// The file no longer matches the hash recorded when it was installed.
+    Modified,
+    UpdateAvailable(String),
+}
+
+impl fmt::Display for InjectorStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InjectorStatus::Missing => write!(f, "not downloaded"),
+            InjectorStatus::Unverified => write!(f, "unverified"),
+            InjectorStatus::Verified => write!(f, "verified"),
+            InjectorStatus::Modified => write!(f, "hash mismatch"),
+            InjectorStatus::UpdateAvailable(version) => write!(f, "update available ({})", version),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct InjectorAssets {
+    pub x86: Option<InjectorAsset>,
+    pub x64: Option<InjectorAsset>,
+    #[serde(skip)]
+    pub latest: Option<InjectorManifest>,
+}
+
+impl InjectorAssets {
+    fn path() -> PathBuf {
+        assets_dir().join("injectors.json")
+    }
+
+    pub fn load() -> Self {
+        if let Ok(data) = fs::read_to_string(Self::path()) {
+            serde_json::from_str::<InjectorAssets>(&data).unwrap_or_default()
+        } else {
+            InjectorAssets::default()
+        }
+    }
+
+    pub fn save(&self) {
+        if let Ok(data) = serde_json::to_string_pretty(&self) {
+            fs::write(Self::path(), data).ok();
+        }
+    }
+
+    pub fn get(&self, arch: ProcessArch) -> Option<&InjectorAsset> {
+        match injector_arch(arch) {
+            ProcessArch::X64 => self.x64.as_ref(),
+            _ => self.x86.as_ref(),
+        }
+    }
+
+    fn set(&mut self, arch: ProcessArch, asset: Option<InjectorAsset>) {
+        match injector_arch(arch) {
+            ProcessArch::X64 => self.x64 = asset,
+            _ => self.x86 = asset,
+        }
+    }
+
+    fn record(&mut self, arch: ProcessArch, asset: &InjectorAsset) {
+        log::info!(
+            "Installed {} {} from {} ({})",
+            injector_file_name(arch),
+            asset.version,
+            asset.source,
+            asset.sha256
+        );
+        self.set(arch, Some(asset.clone()));
+        self.save();
+    }
+
+    /// Hashes the file on disk, so call it on demand rather than every frame.
+    pub fn status(&self, arch: ProcessArch) -> InjectorStatus {
+        let path = injector_path(arch);
+        if !path.exists() {
+            return InjectorStatus::Missing;
+        }
+
+        let Some(asset) = self.get(arch) else {
+            return InjectorStatus::Unverified;
+        };
+
+        match sha256_file(&path) {
+            Ok(hash) if hash.eq_ignore_ascii_case(&asset.sha256) => {}
+            _ => return InjectorStatus::Modified,
+        }
+
+        match self.latest.as_ref().and_then(|latest| latest.get(arch)) {
+            Some(latest) if latest.version != asset.version => {
+                InjectorStatus::UpdateAvailable(latest.version.clone())
+            }
+            _ => InjectorStatus::Verified,
+        }
+    }
+
+    /// Trusts an injector that's on disk without a published hash. Only on the user's
+    /// explicit say-so, never implicitly.
+    pub fn trust(&mut self, arch: ProcessArch) -> Result<InjectorAsset, String> {
+        let sha256 = sha256_file(&injector_path(arch))?;
+        let asset = InjectorAsset {
+            version: "unknown".to_string(),
+            sha256,
+            source: "trusted by user".to_string(),
+            installed_at: Local::now(),
+        };
+        log::warn!(
+            "Trusting unverified {} ({})",
+            injector_file_name(arch),
+            asset.sha256
+        );
+        self.set(arch, Some(asset.clone()));
+        self.save();
+        Ok(asset)
+    }
+}
+
+// MARK: Installing
+// These take the shared assets rather than `&mut self`: files are replaced without
+// holding its lock and only the result is recorded, so concurrent readers aren't
+// blocked and concurrent updates aren't lost. `INSTALLING` is always locked first.
+
+/// Installs an injector that arrived some other way, e.g. in an offline bundle. It's only
+/// recorded as verified if it matches `published`; anything else is left unverified until
+/// the user trusts it, so `ensure` won't run it. Returns the recorded asset, if any.
+pub fn install_bytes(
+    injectors: &Mutex<InjectorAssets>,
+    arch: ProcessArch,
+    data: &[u8],
+    published: Option<&RemoteInjector>,
+    source: &str,
+) -> Result<Option<InjectorAsset>, String> {
+    let _installing = INSTALLING.lock().unwrap_or_else(PoisonError::into_inner);
+    let file_name = injector_file_name(arch);
+    let path = injector_path(arch);
+    let partial = path.with_extension("part");
+
+    fs::write(&partial, data).map_err(|e| format!("Failed to write {}: {}", file_name, e))?;
+    fs::rename(&partial, &path).map_err(|e| format!("Failed to replace {}: {}", file_name, e))?;
+
+    let sha256 = sha256_hex(data);
+    let Some(published) =
+        published.filter(|published| published.sha256.eq_ignore_ascii_case(&sha256))
+    else {
+        log::warn!(
+            "Installed {} from {} without a matching published hash ({})",
+            file_name,
+            source,
+            sha256
+        );
+        let mut assets = injectors.lock().unwrap();
+        assets.set(arch, None);
+        assets.save();
+        return Ok(None);
+    };
+
+    let asset = InjectorAsset {
+        version: published.version.clone(),
+        sha256,
+        source: source.to_string(),
+        installed_at: Local::now(),
+    };
+    injectors.lock().unwrap().record(arch, &asset);
+    Ok(Some(asset))
+}
+
+pub fn delete(injectors: &Mutex<InjectorAssets>, arch: ProcessArch) -> Result<(), String> {
+    let _installing = INSTALLING.lock().unwrap_or_else(PoisonError::into_inner);
+    let file_name = injector_file_name(arch);
+    let path = injector_path(arch);
+
+    if path.exists() {
+        fs::remove_file(&path).map_err(|e| {
+            log::error!("Failed to delete {} injector: {}", file_name, e);
+            format!("Failed to delete {} injector: {}", file_name, e)
+        })?;
+        log::info!("Deleted {}", file_name);
+    }
+
+    let mut assets = injectors.lock().unwrap();
+    assets.set(arch, None);
+    assets.save();
+    Ok(())
+}
+
+/// Downloads the injector, checks it against the manifest hash and records it. Without
+/// a published hash nothing is installed, see [`install_unverified`].
+pub fn install(
+    injectors: &Mutex<InjectorAssets>,
+    downloader: &Downloader,
+    arch: ProcessArch,
+    manifest: &InjectorManifest,
+) -> Result<InjectorAsset, String> {
+    let expected = manifest.get(arch).ok_or_else(|| unpublished(arch))?;
+    let _installing = INSTALLING.lock().unwrap_or_else(PoisonError::into_inner);
+    let asset = download(downloader, arch, Some(expected))?;
+    injectors.lock().unwrap().record(arch, &asset);
+    Ok(asset)
+}
+
+/// Downloads the injector without a hash to check it against. Only for when the user
+/// confirmed it.
+pub fn install_unverified(
+    injectors: &Mutex<InjectorAssets>,
+    downloader: &Downloader,
+    arch: ProcessArch,
+) -> Result<InjectorAsset, String> {
+    let _installing = INSTALLING.lock().unwrap_or_else(PoisonError::into_inner);
+    log::warn!(
+        "Installing {} without a published hash",
+        injector_file_name(arch)
+    );
+    let asset = download(downloader, arch, None)?;
+    injectors.lock().unwrap().record(arch, &asset);
+    Ok(asset)
+}
+
+/// Returns a verified injector, downloading it if it's missing or was tampered with.
+/// Files the loader can't verify are refused until the user trusts them in the settings.
+pub fn ensure(
+    injectors: &Mutex<InjectorAssets>,
+    downloader: &Downloader,
+    arch: ProcessArch,
+) -> Result<PathBuf, String> {
+    let _installing = INSTALLING.lock().unwrap_or_else(PoisonError::into_inner);
+    let file_name = injector_file_name(arch);
+
+    let status = injectors.lock().unwrap().status(arch);
+    match status {
+        InjectorStatus::Verified | InjectorStatus::UpdateAvailable(_) => {}
+        InjectorStatus::Unverified => {
+            return Err(format!(
+                "{} wasn't installed by the loader and can't be verified. Trust or \
+                 redownload it in the settings.",
+                file_name
+            ));
+        }
+        status => {
+            if status == InjectorStatus::Modified {
+                log::warn!("{} was modified, redownloading", file_name);
+            }
+            let manifest = InjectorManifest::fetch(downloader)?;
+            let expected = manifest.get(arch).ok_or_else(|| unpublished(arch))?;
+            let asset = download(downloader, arch, Some(expected))?;
+
+            let mut assets = injectors.lock().unwrap();
+            assets.latest = Some(manifest.clone());
+            assets.record(arch, &asset);
+        }
+    }
+
+    Ok(injector_path(arch))
+}
+
+/// The published injector manifest, or the last one fetched if the CDN can't be reached,
+/// e.g. when importing an offline bundle.
+pub fn published_manifest(
+    injectors: &Mutex<InjectorAssets>,
+    downloader: &Downloader,
+) -> Option<InjectorManifest> {
+    match InjectorManifest::fetch(downloader) {
+        Ok(manifest) => {
+            injectors.lock().unwrap().latest = Some(manifest.clone());
+            Some(manifest)
+        }
+        Err(e) => {
+            log::warn!("{}, using the last known one", e);
+            injectors.lock().unwrap().latest.clone()
+        }
+    }
+}
+
+fn unpublished(arch: ProcessArch) -> String {
+    format!(
+        "No published hash for {}. Install it unverified in the settings if you trust it.",
+        injector_file_name(arch)
+    )
+}
+
+/// Downloads next to the injector and only replaces it once the hash matched. Callers
+/// hold `INSTALLING`.
+fn download(
+    downloader: &Downloader,
+    arch: ProcessArch,
+    expected: Option<&RemoteInjector>,
+) -> Result<InjectorAsset, String> {
+    let file_name = injector_file_name(arch);
+    let path = injector_path(arch);
+    let partial = path.with_extension("part");
+
+    let source = downloader
+        .download_file(file_name, &partial.to_string_lossy())
+        .map_err(|e| format!("Failed to download {}: {}", file_name, e))?;
+    let sha256 = sha256_file(&partial)?;
+
+    let version = match expected {
+        Some(expected) => {
+            if !expected.sha256.eq_ignore_ascii_case(&sha256) {
+                fs::remove_file(&partial).ok();
+                log::error!(
+                    "{} hash mismatch: expected {}, got {}",
+                    file_name,
+                    expected.sha256,
+                    sha256
+                );
+                return Err(format!(
+                    "Downloaded {} does not match the published hash.",
+                    file_name
+                ));
+            }
+            expected.version.clone()
+        }
+        None => "unknown".to_string(),
+    };
+
+    fs::rename(&partial, &path).map_err(|e| format!("Failed to replace {}: {}", file_name, e))?;
+
+    Ok(InjectorAsset {
+        version,
+        sha256,
+        source,
+        installed_at: Local::now(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{config::Config, http::StaticTransport, platform::testing};
+
+    const CDN: &str = "https://cdn.test/";
+    const INJECTOR: &[u8] = b"MZ published injector";
+
+    fn downloader(transport: StaticTransport) -> (Downloader, Arc<StaticTransport>) {
+        let transport = Arc::new(transport);
+        let config = Config {
+            cdn_endpoint: CDN.to_string(),
+            cdn_fallback_endpoint: CDN.to_string(),
+            ..Config::default()
+        };
+        (Downloader::new(transport.clone(), &config), transport)
+    }
+
+    fn manifest(version: &str) -> InjectorManifest {
+        InjectorManifest {
+            x86: Some(RemoteInjector {
+                version: version.to_string(),
+                sha256: sha256_hex(INJECTOR),
+            }),
+            x64: None,
+        }
+    }
+
+    /// Serves the published manifest and `injector` as the download.
+    fn cdn(injector: &[u8]) -> (Downloader, Arc<StaticTransport>) {
+        downloader(
+            StaticTransport::default()
+                .respond(
+                    &format!("{}{}", CDN, REMOTE_MANIFEST),
+                    200,
+                    serde_json::to_vec(&manifest("1.0.0")).unwrap(),
+                )
+                .respond(&format!("{}unknproject.exe", CDN), 200, injector.to_vec()),
+        )
+    }
+
+    #[test]
+    fn status_follows_the_file_on_disk() {
+        testing::install_config_dir();
+        let path = injector_path(ProcessArch::X86);
+        let mut assets = InjectorAssets::default();
+        assert_eq!(assets.status(ProcessArch::X86), InjectorStatus::Missing);
+
+        fs::write(&path, INJECTOR).unwrap();
+        assert_eq!(assets.status(ProcessArch::X86), InjectorStatus::Unverified);
+
+        let trusted = assets.trust(ProcessArch::X86).unwrap();
+        assert_eq!(trusted.sha256, sha256_hex(INJECTOR));
+        assert_eq!(assets.status(ProcessArch::X86), InjectorStatus::Verified);
+        // x64 targets share the x86 injector.
+        assert_eq!(assets.status(ProcessArch::X64), InjectorStatus::Verified);
+        assert_eq!(InjectorAssets::load().get(ProcessArch::X86), Some(&trusted));
+
+        assets.latest = Some(manifest("2.0.0"));
+        assert_eq!(
+            assets.status(ProcessArch::X86),
+            InjectorStatus::UpdateAvailable("2.0.0".to_string())
+        );
+
+        fs::write(&path, b"MZ patched").unwrap();
+        assert_eq!(assets.status(ProcessArch::X86), InjectorStatus::Modified);
+    }
+
+    #[test]
+    fn ensure_refuses_unverified_injectors() {
+        testing::install_config_dir();
+        let path = injector_path(ProcessArch::X86);
+        fs::write(&path, b"MZ unknown").unwrap();
+        let injectors = Mutex::new(InjectorAssets::default());
+        let (downloader, transport) = cdn(INJECTOR);
+
+        let error = ensure(&injectors, &downloader, ProcessArch::X86).unwrap_err();
+        assert!(error.contains("can't be verified"), "{}", error);
+        assert!(transport.requests().is_empty());
+        assert_eq!(fs::read(&path).unwrap(), b"MZ unknown");
+    }
+
+    #[test]
+    fn ensure_downloads_missing_and_modified_injectors() {
+        testing::install_config_dir();
+        let injectors = Mutex::new(InjectorAssets::default());
+        let (downloader, _) = cdn(INJECTOR);
+
+        let path = ensure(&injectors, &downloader, ProcessArch::X64).unwrap();
+        assert_eq!(path, injector_path(ProcessArch::X86));
+        assert_eq!(fs::read(&path).unwrap(), INJECTOR);
+        assert!(!path.with_extension("part").exists());
+        let asset = injectors
+            .lock()
+            .unwrap()
+            .get(ProcessArch::X86)
+            .cloned()
+            .unwrap();
+        assert_eq!(asset.version, "1.0.0");
+        assert_eq!(asset.source, format!("{}unknproject.exe", CDN));
+
+        fs::write(&path, b"MZ patched").unwrap();
+        ensure(&injectors, &downloader, ProcessArch::X86).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), INJECTOR);
+        assert_eq!(
+            injectors.lock().unwrap().status(ProcessArch::X86),
+            InjectorStatus::Verified
+        );
+    }
+
+    #[test]
+    fn downloads_not_matching_the_published_hash_are_discarded() {
+        testing::install_config_dir();
+        let path = injector_path(ProcessArch::X86);
+        fs::write(&path, INJECTOR).unwrap();
+        let injectors = Mutex::new(InjectorAssets::default());
+        let trusted = injectors.lock().unwrap().trust(ProcessArch::X86).unwrap();
+        let (downloader, _) = cdn(b"MZ tampered");
+
+        let error = install(
+            &injectors,
+            &downloader,
+            ProcessArch::X86,
+            &manifest("1.0.0"),
+        )
+        .unwrap_err();
+        assert!(
+            error.contains("does not match the published hash"),
+            "{}",
+            error
+        );
+        assert_eq!(fs::read(&path).unwrap(), INJECTOR);
+        assert!(!path.with_extension("part").exists());
+        assert_eq!(
+            injectors.lock().unwrap().get(ProcessArch::X86),
+            Some(&trusted)
+        );
+
+        let unpublished = InjectorManifest {
+            x86: None,
+            x64: None,
+        };
+        let error = install(&injectors, &downloader, ProcessArch::X86, &unpublished).unwrap_err();
+        assert!(error.contains("No published hash"), "{}", error);
+    }
+
+    #[test]
+    fn unverified_installs_are_recorded_without_a_version() {
+        testing::install_config_dir();
+        let injectors = Mutex::new(InjectorAssets::default());
+        let (downloader, _) = cdn(b"MZ unpublished");
+
+        let asset = install_unverified(&injectors, &downloader, ProcessArch::X86).unwrap();
+        assert_eq!(asset.version, "unknown");
+        assert_eq!(asset.sha256, sha256_hex(b"MZ unpublished"));
+        assert_eq!(
+            fs::read(injector_path(ProcessArch::X86)).unwrap(),
+            b"MZ unpublished"
+        );
+    }
+
+    #[test]
+    fn delete_removes_the_file_and_its_record() {
+        testing::install_config_dir();
+        let path = injector_path(ProcessArch::X86);
+        fs::write(&path, INJECTOR).unwrap();
+        let injectors = Mutex::new(InjectorAssets::default());
+        injectors.lock().unwrap().trust(ProcessArch::X86).unwrap();
+
+        delete(&injectors, ProcessArch::X86).unwrap();
+        assert!(!path.exists());
+        assert_eq!(injectors.lock().unwrap().get(ProcessArch::X86), None);
+        assert_eq!(InjectorAssets::load().get(ProcessArch::X86), None);
+        assert_eq!(
+            injectors.lock().unwrap().status(ProcessArch::X86),
+            InjectorStatus::Missing
+        );
+        // Deleting what's already gone is fine.
+        delete(&injectors, ProcessArch::X86).unwrap();
+    }
+}
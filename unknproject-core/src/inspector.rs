@@ -0,0 +1,45 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{hash::sha256_hex, pe::PeInfo};
+
+/// Everything the inspector window shows about a DLL on disk.
+#[derive(Debug, Clone)]
+pub struct DllInspection {
+    pub path: PathBuf,
+    pub size: u64,
+    pub sha256: String,
+    pub pe: Result<PeInfo, String>,
+}
+
+impl DllInspection {
+    pub fn inspect(path: &Path) -> Result<Self, String> {
+        let data =
+            fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+        Ok(DllInspection {
+            path: path.to_path_buf(),
+            size: data.len() as u64,
+            sha256: sha256_hex(&data),
+            pe: PeInfo::parse(&data),
+        })
+    }
+
+    pub fn file_name(&self) -> String {
+        self.path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string()
+    }
+}
+
+pub fn format_size(size: u64) -> String {
+    match size {
+        s if s >= 1024 * 1024 => format!("{:.2} MB", s as f64 / (1024.0 * 1024.0)),
+        s if s >= 1024 => format!("{:.1} KB", s as f64 / 1024.0),
+        s => format!("{} B", s),
+    }
+}
@@ -0,0 +1,310 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    net::{Ipv4Addr, TcpListener, TcpStream},
+    path::{Path, PathBuf},
+    sync::mpsc::Sender,
+    thread,
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{hash::random_token, Notify};
+
+fn instance_dir() -> PathBuf {
+    let dir = crate::config_dir();
+
+    fs::create_dir_all(&dir).ok();
+    dir
+}
+
+/// Where the running instance can be reached. Kept apart from the lock file because
+/// Windows doesn't let other processes read a locked file.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct InstanceInfo {
+    port: u16,
+    /// Keeps other local users and stray connections from driving the loader.
+    token: String,
+}
+
+impl InstanceInfo {
+    fn path() -> PathBuf {
+        instance_dir().join("instance.json")
+    }
+}
+
+/// Arguments another launch of the loader handed over.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct ForwardedArgs {
+    token: String,
+    args: Vec<String>,
+}
+
+pub enum Instance {
+    /// No other loader is running; this one owns the config directory now.
+    Primary(InstanceLock),
+    /// Another loader holds the lock and accepts forwarded arguments.
+    Running(RunningInstance),
+}
+
+impl Instance {
+    fn lock_file() -> Result<(File, PathBuf), String> {
+        let path = instance_dir().join("instance.lock");
+        OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .map(|file| (file, path.clone()))
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))
+    }
+
+    pub fn acquire() -> Result<Self, String> {
+        let (file, path) = Self::lock_file()?;
+
+        match file.try_lock() {
+            Ok(()) => InstanceLock::listen(file).map(Instance::Primary),
+            Err(fs::TryLockError::WouldBlock) => {
+                let data = fs::read_to_string(InstanceInfo::path()).map_err(|e| {
+                    format!("The loader is already running, but unreachable: {}", e)
+                })?;
+                let info = serde_json::from_str(&data).map_err(|e| {
+                    format!("The loader is already running, but unreachable: {}", e)
+                })?;
+                Ok(Instance::Running(RunningInstance { info }))
+            }
+            Err(fs::TryLockError::Error(e)) => {
+                Err(format!("Failed to lock {}: {}", path.display(), e))
+            }
+        }
+    }
+
+    /// Whether a loader window holds the lock right now, without taking it over.
+    pub fn is_running() -> bool {
+        Self::lock_file()
+            .is_ok_and(|(file, _)| matches!(file.try_lock(), Err(fs::TryLockError::WouldBlock)))
+    }
+}
+
+/// Held by the primary instance for as long as it runs; the OS releases the lock when
+/// the process exits, even if it crashes.
+pub struct InstanceLock {
+    _file: File,
+    listener: TcpListener,
+    token: String,
+}
+
+impl InstanceLock {
+    fn listen(file: File) -> Result<Self, String> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+            .map_err(|e| format!("Failed to listen for other instances: {}", e))?;
+        let port = listener
+            .local_addr()
+            .map_err(|e| format!("Failed to listen for other instances: {}", e))?
+            .port();
+
+        let info = InstanceInfo {
+            port,
+            token: random_token()?,
+        };
+        let data = serde_json::to_string(&info).map_err(|e| e.to_string())?;
+        fs::write(InstanceInfo::path(), data)
+            .map_err(|e| format!("Failed to write {}: {}", InstanceInfo::path().display(), e))?;
+
+        log::debug!("Listening for other instances on port {}", port);
+        Ok(Self {
+            _file: file,
+            listener,
+            token: info.token,
+        })
+    }
+
+    /// Passes arguments forwarded by later launches to `sender`, then calls `notify`.
+    pub fn spawn_listener(&self, sender: Sender<Vec<String>>, notify: Notify) {
+        let listener = match self.listener.try_clone() {
+            Ok(listener) => listener,
+            Err(e) => {
+                log::error!("Failed to listen for other instances: {}", e);
+                return;
+            }
+        };
+        let token = self.token.clone();
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else {
+                    continue;
+                };
+
+                match read_forwarded(stream, &token) {
+                    Ok(args) => {
+                        log::info!("Another instance forwarded {:?}", args);
+                        if sender.send(args).is_err() {
+                            return;
+                        }
+                        notify();
+                    }
+                    Err(e) => log::warn!("Ignoring connection from another instance: {}", e),
+                }
+            }
+        });
+    }
+}
+
+fn read_forwarded(stream: TcpStream, token: &str) -> Result<Vec<String>, String> {
+    stream
+        .set_read_timeout(Some(Duration::from_secs(2)))
+        .map_err(|e| e.to_string())?;
+
+    let mut line = String::new();
+    BufReader::new(&stream)
+        .read_line(&mut line)
+        .map_err(|e| e.to_string())?;
+    let forwarded: ForwardedArgs = serde_json::from_str(&line).map_err(|e| e.to_string())?;
+
+    if forwarded.token != token {
+        return Err("wrong token".to_string());
+    }
+
+    writeln!(&stream, "ok").map_err(|e| e.to_string())?;
+    Ok(forwarded.args)
+}
+
+pub struct RunningInstance {
+    info: InstanceInfo,
+}
+
+impl RunningInstance {
+    /// Hands `args` to the running loader, which also brings its window to the front.
+    /// Paths are made absolute since the running loader has its own working directory.
+    pub fn forward(&self, args: &[String]) -> Result<(), String> {
+        let unreachable =
+            |e: std::io::Error| format!("The loader is already running, but unreachable: {}", e);
+
+        let args = args
+            .iter()
+            .map(|arg| match Path::new(arg).canonicalize() {
+                Ok(path) if !arg.starts_with('-') => path.to_string_lossy().to_string(),
+                _ => arg.clone(),
+            })
+            .collect();
+        let message = serde_json::to_string(&ForwardedArgs {
+            token: self.info.token.clone(),
+            args,
+        })
+        .map_err(|e| e.to_string())?;
+
+        // Windows only lets the foreground process hand focus to another one.
+        #[cfg(windows)]
+        unsafe {
+            use winapi::um::winuser::{AllowSetForegroundWindow, ASFW_ANY};
+            AllowSetForegroundWindow(ASFW_ANY);
+        }
+
+        let stream = TcpStream::connect_timeout(
+            &(Ipv4Addr::LOCALHOST, self.info.port).into(),
+            Duration::from_secs(2),
+        )
+        .map_err(unreachable)?;
+        stream
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .map_err(unreachable)?;
+        writeln!(&stream, "{}", message).map_err(unreachable)?;
+
+        let mut reply = String::new();
+        BufReader::new(&stream)
+            .read_line(&mut reply)
+            .map_err(unreachable)?;
+        if reply.trim() != "ok" {
+            return Err("The running loader rejected the request.".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{mpsc, Arc};
+
+    use super::*;
+    use crate::platform::testing;
+
+    fn primary() -> InstanceLock {
+        match Instance::acquire().unwrap() {
+            Instance::Primary(lock) => lock,
+            Instance::Running(_) => panic!("expected to be the first instance"),
+        }
+    }
+
+    fn info() -> InstanceInfo {
+        serde_json::from_str(&fs::read_to_string(InstanceInfo::path()).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn later_launches_forward_to_the_first() {
+        testing::install_config_dir();
+        assert!(!Instance::is_running());
+
+        let lock = primary();
+        let (sender, receiver) = mpsc::channel();
+        lock.spawn_listener(sender, Arc::new(|| {}));
+        assert!(Instance::is_running());
+
+        let Instance::Running(running) = Instance::acquire().unwrap() else {
+            panic!("expected the loader to be running");
+        };
+        let args = vec!["inject".to_string(), "Hack".to_string()];
+        running.forward(&args).unwrap();
+        assert_eq!(receiver.recv_timeout(Duration::from_secs(5)).unwrap(), args);
+
+        drop(lock);
+        assert!(!Instance::is_running());
+    }
+
+    #[test]
+    fn forwarding_needs_the_token() {
+        testing::install_config_dir();
+        let lock = primary();
+        let (sender, receiver) = mpsc::channel();
+        lock.spawn_listener(sender, Arc::new(|| {}));
+
+        let impostor = RunningInstance {
+            info: InstanceInfo {
+                token: "guessed".to_string(),
+                ..info()
+            },
+        };
+        assert_eq!(
+            impostor.forward(&["inject".to_string()]).unwrap_err(),
+            "The running loader rejected the request."
+        );
+
+        let stream = TcpStream::connect((Ipv4Addr::LOCALHOST, info().port)).unwrap();
+        writeln!(&stream, "not json").unwrap();
+        let mut reply = String::new();
+        BufReader::new(&stream).read_line(&mut reply).ok();
+        assert_eq!(reply, "");
+
+        assert!(receiver.recv_timeout(Duration::from_millis(200)).is_err());
+    }
+
+    #[test]
+    fn a_stale_instance_file_is_replaced() {
+        testing::install_config_dir();
+        // Left behind by a loader that crashed; nobody holds the lock anymore.
+        let stale = InstanceInfo {
+            port: 1,
+            token: "stale".to_string(),
+        };
+        fs::write(InstanceInfo::path(), serde_json::to_string(&stale).unwrap()).unwrap();
+        assert!(!Instance::is_running());
+
+        let lock = primary();
+        let current = info();
+        assert_eq!(current.port, lock.listener.local_addr().unwrap().port());
+        assert_eq!(current.token, lock.token);
+        assert_ne!(current.token, "stale");
+    }
+}
@@ -0,0 +1,44 @@
+//! Everything the UnknProject loader does besides drawing: the catalog, downloads, config,
+//! history, Steam and injection. Frontends such as the eframe app and the command line
+//! build on top of it.
+
+use std::{path::PathBuf, sync::Arc};
+
+pub mod automation;
+pub mod bundle;
+pub mod catalog;
+pub mod chains;
+pub mod config;
+pub mod deeplink;
+pub mod downloader;
+pub mod errors;
+pub mod hacks;
+pub mod hash;
+pub mod history;
+pub mod http;
+pub mod inject;
+pub mod injector;
+pub mod injector_assets;
+pub mod inspector;
+pub mod instance;
+pub mod modules;
+pub mod pe;
+pub mod platform;
+pub mod processes;
+pub mod statistics;
+pub mod steam;
+
+/// Called from background threads when they have news for the frontend, e.g. to repaint
+/// a window.
+pub type Notify = Arc<dyn Fn() + Send + Sync>;
+
+/// Folder the loader keeps its config, history, injectors and downloaded hacks in.
+pub fn config_dir() -> PathBuf {
+    #[cfg(test)]
+    if let Some(dir) = platform::testing::CONFIG_DIR.with(|dir| dir.borrow().clone()) {
+        return dir;
+    }
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("unknproject")
+}
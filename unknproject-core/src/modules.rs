@@ -0,0 +1,142 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::{hash::sha256_file, history::InjectionMethod, platform, processes::is_alive};
+use chrono::{DateTime, Local};
+
+/// A DLL we injected. Only ones loaded with the standard (LoadLibrary) method can be
+/// unloaded again; manual-mapped ones are tracked so we notice double injections.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InjectedModule {
+    pub name: String,
+    pub path: PathBuf,
+    pub sha256: Option<String>,
+    pub method: InjectionMethod,
+    pub process: String,
+    pub pid: u32,
+    /// Where a standard-injected module was loaded; manual-mapped ones have none.
+    pub base: Option<usize>,
+    pub injected_at: DateTime<Local>,
+}
+
+impl InjectedModule {
+    pub fn ejectable(&self) -> bool {
+        self.method == InjectionMethod::Standard && self.base.is_some()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ModuleTracker {
+    modules: HashMap<u32, Vec<InjectedModule>>,
+    last_pruned: Option<Instant>,
+}
+
+impl ModuleTracker {
+    pub fn track(&mut self, module: InjectedModule) {
+        let modules = self.modules.entry(module.pid).or_default();
+        modules.retain(|tracked| tracked.path != module.path);
+        modules.push(module);
+    }
+
+    pub fn for_process(&self, process: &str) -> Vec<InjectedModule> {
+        self.modules
+            .values()
+            .flatten()
+            .filter(|module| module.process.eq_ignore_ascii_case(process))
+            .cloned()
+            .collect()
+    }
+
+    /// A module we injected into `pid` that is the same file as `path` or has the same hash.
+    pub fn find(&self, pid: u32, path: &Path, sha256: Option<&str>) -> Option<&InjectedModule> {
+        self.modules.get(&pid)?.iter().find(|module| {
+            module.path == path
+                || sha256.is_some_and(|sha256| module.sha256.as_deref() == Some(sha256))
+        })
+    }
+
+    pub fn untrack(&mut self, module: &InjectedModule) {
+        if let Some(modules) = self.modules.get_mut(&module.pid) {
+            modules.retain(|tracked| tracked.path != module.path);
+            if modules.is_empty() {
+                self.modules.remove(&module.pid);
+            }
+        }
+    }
+
+    /// Forgets modules whose process has exited. Runs at most every couple of seconds.
+    pub fn prune_exited(&mut self) {
+        if self
+            .last_pruned
+            .is_some_and(|t| t.elapsed() < Duration::from_secs(2))
+        {
+            return;
+        }
+        self.last_pruned = Some(Instant::now());
+
+        self.modules.retain(|pid, modules| {
+            let alive = is_alive(*pid);
+
+            if !alive {
+                for module in modules.iter() {
+                    log::debug!(
+                        "{} ({}) exited, forgetting {}",
+                        module.process,
+                        module.pid,
+                        module.name
+                    );
+                }
+            }
+
+            alive
+        });
+    }
+}
+
+/// Looks through the target's module list for `path`, or a module with the same file name
+/// and hash. Returns the path of the loaded copy.
+pub fn find_loaded(pid: u32, path: &Path, sha256: Option<&str>) -> Option<PathBuf> {
+    let processes = platform::processes();
+    if let Ok(Some(loaded_path)) = processes.module_by_path(pid, path) {
+        return Some(loaded_path);
+    }
+
+    let sha256 = sha256?;
+    let loaded_path = processes
+        .module_by_name(pid, path.file_name()?.to_str()?)
+        .ok()
+        .flatten()?;
+
+    sha256_file(&loaded_path)
+        .ok()
+        .filter(|hash| hash == sha256)
+        .map(|_| loaded_path)
+}
+
+/// Unloads a standard-injected module and stops tracking it, also when it turns out to be
+/// gone already.
+pub fn eject(tracker: &Mutex<ModuleTracker>, module: &InjectedModule) -> Result<(), String> {
+    let base = module
+        .base
+        .ok_or_else(|| format!("{} can't be ejected.", module.name))?;
+
+    let ejected = platform::injector()
+        .eject(module.pid, base)
+        .map_err(|e| format!("Failed to eject {}: {}", module.name, e))?;
+    tracker.lock().unwrap().untrack(module);
+    if !ejected {
+        return Err(format!("{} is no longer loaded.", module.name));
+    }
+
+    log::info!(
+        "Ejected {} from {} ({})",
+        module.name,
+        module.process,
+        module.pid
+    );
+    Ok(())
+}
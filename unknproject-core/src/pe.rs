@@ -0,0 +1,660 @@
+use std::{fmt, fs, path::Path};
+
+use crate::processes::ProcessArch;
+
+const IMAGE_FILE_EXECUTABLE_IMAGE: u16 = 0x0002;
+const IMAGE_FILE_DLL: u16 = 0x2000;
+
+const PE32_MAGIC: u16 = 0x10b;
+const PE32_PLUS_MAGIC: u16 = 0x20b;
+
+const EXPORT_DIRECTORY: usize = 0;
+const IMPORT_DIRECTORY: usize = 1;
+const RESOURCE_DIRECTORY: usize = 2;
+
+const RT_VERSION: u32 = 16;
+const VS_FIXEDFILEINFO_SIGNATURE: u32 = 0xfeef04bd;
+
+// Upper bounds so a corrupt header can't make us walk gigabytes of garbage.
+const MAX_SECTIONS: usize = 96;
+const MAX_NAMES: usize = 65536;
+const MAX_NAME_LENGTH: usize = 512;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Machine {
+    I386,
+    Amd64,
+    Arm64,
+    Other(u16),
+}
+
+impl Machine {
+    fn from_raw(raw: u16) -> Self {
+        match raw {
+            0x014c => Machine::I386,
+            0x8664 => Machine::Amd64,
+            0xaa64 => Machine::Arm64,
+            other => Machine::Other(other),
+        }
+    }
+
+    pub fn arch(&self) -> ProcessArch {
+        match self {
+            Machine::I386 => ProcessArch::X86,
+            Machine::Amd64 => ProcessArch::X64,
+            _ => ProcessArch::Unknown,
+        }
+    }
+}
+
+impl fmt::Display for Machine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Machine::I386 => write!(f, "x86 (i386)"),
+            Machine::Amd64 => write!(f, "x64 (AMD64)"),
+            Machine::Arm64 => write!(f, "ARM64"),
+            Machine::Other(raw) => write!(f, "unknown (0x{:04x})", raw),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section {
+    pub name: String,
+    pub virtual_address: u32,
+    pub virtual_size: u32,
+    pub raw_offset: u32,
+    pub raw_size: u32,
+    pub characteristics: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeInfo {
+    pub machine: Machine,
+    pub characteristics: u16,
+    pub timestamp: u32,
+    pub is_pe32_plus: bool,
+    pub sections: Vec<Section>,
+    pub exports: Vec<String>,
+    pub imports: Vec<String>,
+    pub version: Option<VersionInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct VersionInfo {
+    pub file_version: Option<String>,
+    pub product_version: Option<String>,
+    pub strings: Vec<(String, String)>,
+}
+
+impl PeInfo {
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let data =
+            fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Self::parse(&data)
+    }
+
+    pub fn parse(data: &[u8]) -> Result<Self, String> {
+        if data.get(0..2) != Some(&b"MZ"[..]) {
+            return Err("Not a PE file (missing MZ signature).".to_string());
+        }
+
+        let pe_offset = read_u32(data, 0x3c).ok_or("Truncated DOS header.")? as usize;
+        if data.get(pe_offset..pe_offset.saturating_add(4)) != Some(&b"PE\0\0"[..]) {
+            return Err("Not a PE file (missing PE signature).".to_string());
+        }
+
+        let coff = pe_offset + 4;
+        let truncated = || "Truncated COFF header.".to_string();
+        let machine = Machine::from_raw(read_u16(data, coff).ok_or_else(truncated)?);
+        let section_count = read_u16(data, coff + 2).ok_or_else(truncated)? as usize;
+        let timestamp = read_u32(data, coff + 4).ok_or_else(truncated)?;
+        let optional_header_size = read_u16(data, coff + 16).ok_or_else(truncated)? as usize;
+        let characteristics = read_u16(data, coff + 18).ok_or_else(truncated)?;
+
+        let optional_header = coff + 20;
+        let is_pe32_plus = match read_u16(data, optional_header) {
+            Some(PE32_MAGIC) => false,
+            Some(PE32_PLUS_MAGIC) => true,
+            Some(magic) => return Err(format!("Unknown optional header magic 0x{:x}.", magic)),
+            None => return Err("Truncated optional header.".to_string()),
+        };
+
+        let (directory_count_offset, directories_offset) = if is_pe32_plus {
+            (optional_header + 108, optional_header + 112)
+        } else {
+            (optional_header + 92, optional_header + 96)
+        };
+        let directory_count = read_u32(data, directory_count_offset).unwrap_or(0) as usize;
+        let directory = |index: usize| -> Option<(u32, u32)> {
+            if index >= directory_count {
+                return None;
+            }
+            let offset = directories_offset + index * 8;
+            let rva = read_u32(data, offset)?;
+            let size = read_u32(data, offset + 4)?;
+            (rva != 0 && size != 0).then_some((rva, size))
+        };
+
+        let sections_offset = optional_header + optional_header_size;
+        let sections = (0..section_count.min(MAX_SECTIONS))
+            .map_while(|index| read_section(data, sections_offset + index * 40))
+            .collect::<Vec<_>>();
+
+        let exports = directory(EXPORT_DIRECTORY)
+            .map(|(rva, _)| read_exports(data, &sections, rva))
+            .unwrap_or_default();
+        let imports = directory(IMPORT_DIRECTORY)
+            .map(|(rva, _)| read_imports(data, &sections, rva))
+            .unwrap_or_default();
+        let version = directory(RESOURCE_DIRECTORY)
+            .and_then(|(rva, _)| read_version_resource(data, &sections, rva));
+
+        Ok(PeInfo {
+            machine,
+            characteristics,
+            timestamp,
+            is_pe32_plus,
+            sections,
+            exports,
+            imports,
+            version,
+        })
+    }
+
+    pub fn is_dll(&self) -> bool {
+        self.characteristics & IMAGE_FILE_DLL != 0
+            && self.characteristics & IMAGE_FILE_EXECUTABLE_IMAGE != 0
+    }
+
+    pub fn arch(&self) -> ProcessArch {
+        self.machine.arch()
+    }
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    data.get(offset..offset.checked_add(2)?)
+        .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset.checked_add(4)?)
+        .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+fn read_cstring(data: &[u8], offset: usize) -> Option<String> {
+    let bytes = data.get(offset..)?;
+    let bytes = &bytes[..bytes.len().min(MAX_NAME_LENGTH)];
+    let end = bytes.iter().position(|&b| b == 0)?;
+    Some(String::from_utf8_lossy(&bytes[..end]).to_string())
+}
+
+fn read_section(data: &[u8], offset: usize) -> Option<Section> {
+    let name = data.get(offset..offset.checked_add(8)?)?;
+    let name_end = name.iter().position(|&b| b == 0).unwrap_or(8);
+
+    Some(Section {
+        name: String::from_utf8_lossy(&name[..name_end]).to_string(),
+        virtual_size: read_u32(data, offset + 8)?,
+        virtual_address: read_u32(data, offset + 12)?,
+        raw_size: read_u32(data, offset + 16)?,
+        raw_offset: read_u32(data, offset + 20)?,
+        characteristics: read_u32(data, offset + 36)?,
+    })
+}
+
+fn rva_to_offset(sections: &[Section], rva: u32) -> Option<usize> {
+    sections
+        .iter()
+        .find(|section| {
+            let size = section.virtual_size.max(section.raw_size);
+            rva >= section.virtual_address && rva < section.virtual_address.saturating_add(size)
+        })
+        .map(|section| (rva - section.virtual_address) as usize + section.raw_offset as usize)
+}
+
+fn read_exports(data: &[u8], sections: &[Section], rva: u32) -> Vec<String> {
+    let Some(directory) = rva_to_offset(sections, rva) else {
+        return Vec::new();
+    };
+    let name_count = read_u32(data, directory + 24).unwrap_or(0) as usize;
+    let Some(names) = read_u32(data, directory + 32).and_then(|rva| rva_to_offset(sections, rva))
+    else {
+        return Vec::new();
+    };
+
+    (0..name_count.min(MAX_NAMES))
+        .filter_map(|index| {
+            let name_rva = read_u32(data, names + index * 4)?;
+            read_cstring(data, rva_to_offset(sections, name_rva)?)
+        })
+        .collect()
+}
+
+fn read_imports(data: &[u8], sections: &[Section], rva: u32) -> Vec<String> {
+    let Some(mut descriptor) = rva_to_offset(sections, rva) else {
+        return Vec::new();
+    };
+    let mut imports = Vec::new();
+
+    // Import descriptors are 20 bytes each, terminated by an all-zero entry.
+    while imports.len() < MAX_NAMES {
+        let Some(name_rva) = read_u32(data, descriptor + 12) else {
+            break;
+        };
+        if name_rva == 0 {
+            break;
+        }
+        if let Some(name) = rva_to_offset(sections, name_rva).and_then(|o| read_cstring(data, o)) {
+            imports.push(name);
+        }
+        descriptor += 20;
+    }
+
+    imports
+}
+
+fn read_version_resource(data: &[u8], sections: &[Section], rva: u32) -> Option<VersionInfo> {
+    // Resource tree is type -> name -> language -> data entry; we take the first
+    // name and language under RT_VERSION.
+    let root = rva_to_offset(sections, rva)?;
+    let names = resource_child(data, root, root, Some(RT_VERSION))?;
+    let languages = resource_child(data, root, names, None)?;
+    let entry = resource_child(data, root, languages, None)?;
+
+    let start = rva_to_offset(sections, read_u32(data, entry)?)?;
+    let size = read_u32(data, entry + 4)? as usize;
+    parse_version_info(data.get(start..start.checked_add(size)?)?)
+}
+
+fn resource_child(data: &[u8], root: usize, directory: usize, id: Option<u32>) -> Option<usize> {
+    let named = read_u16(data, directory + 12)? as usize;
+    let ids = read_u16(data, directory + 14)? as usize;
+
+    (0..(named + ids).min(MAX_NAMES)).find_map(|index| {
+        let entry = directory + 16 + index * 8;
+        let name = read_u32(data, entry)?;
+        let target = read_u32(data, entry + 4)?;
+        id.is_none_or(|id| name == id)
+            .then_some(root + (target & 0x7fff_ffff) as usize)
+    })
+}
+
+struct VersionBlock<'a> {
+    key: String,
+    value: &'a [u8],
+    children: &'a [u8],
+}
+
+fn align4(offset: usize) -> usize {
+    (offset + 3) & !3
+}
+
+fn read_utf16z(data: &[u8], offset: usize) -> Option<(String, usize)> {
+    let mut units = Vec::new();
+    let mut position = offset;
+
+    loop {
+        let unit = read_u16(data, position)?;
+        position += 2;
+        if unit == 0 {
+            break;
+        }
+        units.push(unit);
+    }
+
+    Some((String::from_utf16_lossy(&units), position))
+}
+
+fn version_blocks(data: &[u8]) -> Vec<VersionBlock<'_>> {
+    let mut blocks = Vec::new();
+    let mut offset = 0;
+
+    while offset < data.len() {
+        let Some(block) = data.get(offset..) else {
+            break;
+        };
+        let Some(length) = read_u16(block, 0).map(usize::from) else {
+            break;
+        };
+        if length == 0 {
+            break;
+        }
+        let Some(block) = block.get(..length.min(block.len())) else {
+            break;
+        };
+
+        let value_length = read_u16(block, 2).unwrap_or(0) as usize;
+        let is_text = read_u16(block, 4) == Some(1);
+        let Some((key, key_end)) = read_utf16z(block, 6) else {
+            break;
+        };
+
+        let value_start = align4(key_end).min(block.len());
+        let value_size = if is_text {
+            value_length * 2
+        } else {
+            value_length
+        };
+        let value_end = (value_start + value_size).min(block.len());
+
+        blocks.push(VersionBlock {
+            key,
+            value: &block[value_start..value_end],
+            children: &block[align4(value_end).min(block.len())..],
+        });
+
+        offset += align4(length);
+    }
+
+    blocks
+}
+
+fn parse_version_info(data: &[u8]) -> Option<VersionInfo> {
+    let root = version_blocks(data).into_iter().next()?;
+    if root.key != "VS_VERSION_INFO" {
+        return None;
+    }
+
+    let mut info = VersionInfo::default();
+
+    if read_u32(root.value, 0) == Some(VS_FIXEDFILEINFO_SIGNATURE) {
+        let version = |offset: usize| -> Option<String> {
+            let ms = read_u32(root.value, offset)?;
+            let ls = read_u32(root.value, offset + 4)?;
+            Some(format!(
+                "{}.{}.{}.{}",
+                ms >> 16,
+                ms & 0xffff,
+                ls >> 16,
+                ls & 0xffff
+            ))
+        };
+        info.file_version = version(8);
+        info.product_version = version(16);
+    }
+
+    for file_info in version_blocks(root.children) {
+        if file_info.key != "StringFileInfo" {
+            continue;
+        }
+        for table in version_blocks(file_info.children) {
+            for string in version_blocks(table.children) {
+                let units = string
+                    .value
+                    .chunks_exact(2)
+                    .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+                    .take_while(|&unit| unit != 0)
+                    .collect::<Vec<_>>();
+                info.strings
+                    .push((string.key, String::from_utf16_lossy(&units)));
+            }
+        }
+    }
+
+    Some(info)
+}
+
+/// Smallest images `PeInfo::parse` accepts, for tests elsewhere in the crate.
+#[cfg(test)]
+pub(crate) mod fixtures {
+    pub const I386: u16 = 0x014c;
+    pub const AMD64: u16 = 0x8664;
+
+    /// A section-less image for `machine` with the DLL flag set if `dll`.
+    pub fn image(machine: u16, pe32_plus: bool, dll: bool) -> Vec<u8> {
+        let optional_header_size: u16 = if pe32_plus { 240 } else { 224 };
+        let mut characteristics = super::IMAGE_FILE_EXECUTABLE_IMAGE;
+        if dll {
+            characteristics |= super::IMAGE_FILE_DLL;
+        }
+
+        let mut data = vec![0u8; 0x40];
+        data[0..2].copy_from_slice(b"MZ");
+        data[0x3c..0x40].copy_from_slice(&0x40u32.to_le_bytes());
+
+        data.extend_from_slice(b"PE\0\0");
+        data.extend_from_slice(&machine.to_le_bytes());
+        data.extend_from_slice(&0u16.to_le_bytes()); // sections
+        data.extend_from_slice(&0x5f5e_1000u32.to_le_bytes()); // timestamp
+        data.extend_from_slice(&[0; 8]); // symbol table
+        data.extend_from_slice(&optional_header_size.to_le_bytes());
+        data.extend_from_slice(&characteristics.to_le_bytes());
+
+        let optional_header = data.len();
+        data.resize(optional_header + optional_header_size as usize, 0);
+        let magic = if pe32_plus {
+            super::PE32_PLUS_MAGIC
+        } else {
+            super::PE32_MAGIC
+        };
+        data[optional_header..optional_header + 2].copy_from_slice(&magic.to_le_bytes());
+        let directory_count = optional_header + if pe32_plus { 108 } else { 92 };
+        data[directory_count..directory_count + 4].copy_from_slice(&16u32.to_le_bytes());
+
+        data
+    }
+
+    pub fn dll(machine: u16) -> Vec<u8> {
+        image(machine, machine == AMD64, true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fixtures::*, *};
+
+    #[test]
+    fn parses_pe32_dlls() {
+        let info = PeInfo::parse(&image(I386, false, true)).unwrap();
+        assert_eq!(info.machine, Machine::I386);
+        assert_eq!(info.arch(), ProcessArch::X86);
+        assert!(!info.is_pe32_plus);
+        assert!(info.is_dll());
+        assert_eq!(info.timestamp, 0x5f5e_1000);
+        assert!(info.sections.is_empty() && info.exports.is_empty() && info.imports.is_empty());
+        assert_eq!(info.version, None);
+    }
+
+    #[test]
+    fn parses_pe32_plus_dlls() {
+        let info = PeInfo::parse(&image(AMD64, true, true)).unwrap();
+        assert_eq!(info.machine, Machine::Amd64);
+        assert_eq!(info.arch(), ProcessArch::X64);
+        assert!(info.is_pe32_plus);
+        assert!(info.is_dll());
+    }
+
+    #[test]
+    fn executables_and_other_machines_are_told_apart() {
+        let info = PeInfo::parse(&image(I386, false, false)).unwrap();
+        assert!(!info.is_dll());
+
+        let info = PeInfo::parse(&image(0xaa64, true, true)).unwrap();
+        assert_eq!(info.machine, Machine::Arm64);
+        assert_eq!(info.arch(), ProcessArch::Unknown);
+        let info = PeInfo::parse(&image(0x1234, false, true)).unwrap();
+        assert_eq!(info.machine.to_string(), "unknown (0x1234)");
+    }
+
+    #[test]
+    fn rejects_files_that_are_not_pe() {
+        assert_eq!(
+            PeInfo::parse(b"\x7fELF").unwrap_err(),
+            "Not a PE file (missing MZ signature)."
+        );
+
+        let mut data = dll(I386);
+        data[0x40..0x44].copy_from_slice(b"NE\0\0");
+        assert_eq!(
+            PeInfo::parse(&data).unwrap_err(),
+            "Not a PE file (missing PE signature)."
+        );
+
+        let mut data = dll(I386);
+        data[0x58..0x5a].copy_from_slice(&0x107u16.to_le_bytes());
+        assert_eq!(
+            PeInfo::parse(&data).unwrap_err(),
+            "Unknown optional header magic 0x107."
+        );
+    }
+
+    #[test]
+    fn truncated_images_fail_without_panicking() {
+        let data = dll(AMD64);
+        // Everything before the optional header magic is required.
+        for len in 0..0x5a {
+            assert!(PeInfo::parse(&data[..len]).is_err(), "{} bytes", len);
+        }
+        assert_eq!(
+            PeInfo::parse(&data[..0x30]).unwrap_err(),
+            "Truncated DOS header."
+        );
+        assert_eq!(
+            PeInfo::parse(&data[..0x50]).unwrap_err(),
+            "Truncated COFF header."
+        );
+        assert_eq!(
+            PeInfo::parse(&data[..0x59]).unwrap_err(),
+            "Truncated optional header."
+        );
+        // The data directories are optional, a cut-off table just has none.
+        for len in 0x5a..data.len() {
+            assert!(PeInfo::parse(&data[..len]).is_ok(), "{} bytes", len);
+        }
+    }
+
+    fn utf16z(text: &str) -> Vec<u8> {
+        text.encode_utf16()
+            .chain([0])
+            .flat_map(u16::to_le_bytes)
+            .collect()
+    }
+
+    fn pad4(data: &mut Vec<u8>) {
+        data.resize(align4(data.len()), 0);
+    }
+
+    /// One VS_VERSIONINFO-style block: header, key, value, then `children`.
+    fn block(key: &str, value: &[u8], is_text: bool, children: &[u8]) -> Vec<u8> {
+        let value_length = if is_text {
+            value.len() / 2
+        } else {
+            value.len()
+        };
+        let mut data = vec![0u8; 2];
+        data.extend_from_slice(&(value_length as u16).to_le_bytes());
+        data.extend_from_slice(&(is_text as u16).to_le_bytes());
+        data.extend(utf16z(key));
+        pad4(&mut data);
+        data.extend_from_slice(value);
+        pad4(&mut data);
+        data.extend_from_slice(children);
+        pad4(&mut data);
+        let length = data.len() as u16;
+        data[0..2].copy_from_slice(&length.to_le_bytes());
+        data
+    }
+
+    fn version_info(root_key: &str) -> Vec<u8> {
+        let mut fixed = vec![0u8; 52];
+        for (offset, value) in [
+            (0, VS_FIXEDFILEINFO_SIGNATURE),
+            (8, 0x0001_0002),
+            (12, 0x0003_0004),
+            (16, 0x0005_0000),
+            (20, 0x0000_0007),
+        ] {
+            fixed[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+        }
+
+        let strings = [
+            block("CompanyName", &utf16z("Unknown"), true, &[]),
+            block("ProductName", &utf16z("Cheat"), true, &[]),
+        ]
+        .concat();
+        let table = block("040904b0", &[], true, &strings);
+        let file_info = block("StringFileInfo", &[], true, &table);
+        let var_info = block("VarFileInfo", &[], true, &[]);
+
+        block(root_key, &fixed, false, &[file_info, var_info].concat())
+    }
+
+    fn expected_version() -> VersionInfo {
+        VersionInfo {
+            file_version: Some("1.2.3.4".to_string()),
+            product_version: Some("5.0.0.7".to_string()),
+            strings: vec![
+                ("CompanyName".to_string(), "Unknown".to_string()),
+                ("ProductName".to_string(), "Cheat".to_string()),
+            ],
+        }
+    }
+
+    #[test]
+    fn parses_version_info() {
+        assert_eq!(
+            parse_version_info(&version_info("VS_VERSION_INFO")),
+            Some(expected_version())
+        );
+        assert_eq!(parse_version_info(&version_info("SOMETHING_ELSE")), None);
+        assert_eq!(parse_version_info(&[]), None);
+
+        // Cut-off blocks lose their tail, they don't panic.
+        let data = version_info("VS_VERSION_INFO");
+        for len in 0..data.len() {
+            let _ = parse_version_info(&data[..len]);
+        }
+    }
+
+    #[test]
+    fn reads_the_version_resource_of_an_image() {
+        const RSRC_RVA: u32 = 0x1000;
+        let version = version_info("VS_VERSION_INFO");
+
+        // type (RT_VERSION) -> name -> language -> data entry -> VS_VERSION_INFO
+        let mut rsrc = Vec::new();
+        for (id, target) in [
+            (RT_VERSION, 0x8000_0000 | 24),
+            (1, 0x8000_0000 | 48),
+            (0x409, 72),
+        ] {
+            let mut directory = [0u8; 24];
+            directory[14..16].copy_from_slice(&1u16.to_le_bytes());
+            directory[16..20].copy_from_slice(&u32::to_le_bytes(id));
+            directory[20..24].copy_from_slice(&u32::to_le_bytes(target));
+            rsrc.extend_from_slice(&directory);
+        }
+        rsrc.extend_from_slice(&(RSRC_RVA + 88).to_le_bytes());
+        rsrc.extend_from_slice(&(version.len() as u32).to_le_bytes());
+        rsrc.extend_from_slice(&[0; 8]);
+        rsrc.extend_from_slice(&version);
+
+        let mut data = dll(I386);
+        data[0x46..0x48].copy_from_slice(&1u16.to_le_bytes());
+        let resource_directory = 0x58 + 96 + RESOURCE_DIRECTORY * 8;
+        data[resource_directory..resource_directory + 4].copy_from_slice(&RSRC_RVA.to_le_bytes());
+        data[resource_directory + 4..resource_directory + 8]
+            .copy_from_slice(&(rsrc.len() as u32).to_le_bytes());
+
+        let raw_offset = (data.len() + 40) as u32;
+        let mut section = [0u8; 40];
+        section[0..5].copy_from_slice(b".rsrc");
+        section[8..12].copy_from_slice(&(rsrc.len() as u32).to_le_bytes());
+        section[12..16].copy_from_slice(&RSRC_RVA.to_le_bytes());
+        section[16..20].copy_from_slice(&(rsrc.len() as u32).to_le_bytes());
+        section[20..24].copy_from_slice(&raw_offset.to_le_bytes());
+        data.extend_from_slice(&section);
+        data.extend_from_slice(&rsrc);
+
+        let info = PeInfo::parse(&data).unwrap();
+        assert_eq!(info.sections.len(), 1);
+        assert_eq!(info.sections[0].name, ".rsrc");
+        assert_eq!(info.version, Some(expected_version()));
+
+        // A resource section without RT_VERSION just has no version.
+        let type_id = raw_offset as usize + 16;
+        data[type_id..type_id + 4].copy_from_slice(&3u32.to_le_bytes());
+        assert_eq!(PeInfo::parse(&data).unwrap().version, None);
+    }
+}
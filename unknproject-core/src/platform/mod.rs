@@ -0,0 +1,103 @@
+//! The parts of the loader that only exist on Windows: finding Steam, looking into other
+//! processes and injecting into them. Each sits behind a trait with a Windows backend and
+//! a stand-in for other hosts, so the rest of the crate builds and runs anywhere. Unit
+//! tests swap in the in-memory backends from `testing`.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{Local, TimeZone};
+use sysinfo::{PidExt, ProcessExt, System, SystemExt};
+
+use crate::processes::{ProcessArch, ProcessInstance};
+
+#[cfg(test)]
+pub(crate) mod testing;
+#[cfg(not(windows))]
+mod unsupported;
+#[cfg(windows)]
+mod windows;
+
+#[cfg(not(windows))]
+use unsupported as native;
+#[cfg(windows)]
+use windows as native;
+
+pub trait SteamLocator: Send + Sync {
+    /// Folder the Steam client is installed in.
+    fn install_path(&self) -> Result<PathBuf, String>;
+}
+
+pub trait ProcessBackend: Send + Sync {
+    /// Every running process whose executable name matches `name`, in no particular order.
+    fn list(&self, name: &str) -> Vec<ProcessInstance>;
+
+    /// Pids of the running processes named `name`. Cheaper than `list`.
+    fn find_all(&self, name: &str) -> Vec<u32>;
+
+    /// Executable name of `pid`, `None` once it has exited.
+    fn name(&self, pid: u32) -> Option<String>;
+
+    /// Whether `pid` is still running, `None` if that can't be told, e.g. because we may
+    /// not open it.
+    fn alive(&self, pid: u32) -> Option<bool>;
+
+    fn arch(&self, pid: u32) -> ProcessArch;
+
+    /// Path of the module `pid` loaded from `path`, if any.
+    fn module_by_path(&self, pid: u32, path: &Path) -> Result<Option<PathBuf>, String>;
+
+    /// Path of the module named `name` (e.g. `client.dll`) in `pid`, if any.
+    fn module_by_name(&self, pid: u32, name: &str) -> Result<Option<PathBuf>, String>;
+}
+
+/// Standard (LoadLibrary) injection. Manual mapping runs the external injector instead.
+pub trait Injector: Send + Sync {
+    /// Returns the base address the module was loaded at, which `eject` takes.
+    fn inject(&self, pid: u32, dll_path: &Path) -> Result<usize, String>;
+
+    /// Unloads the module loaded at `base`. `Ok(false)` if nothing is loaded there anymore.
+    fn eject(&self, pid: u32, base: usize) -> Result<bool, String>;
+}
+
+pub fn steam() -> &'static dyn SteamLocator {
+    #[cfg(test)]
+    if let Some(steam) = testing::OVERRIDES.with(|overrides| overrides.get().steam) {
+        return steam;
+    }
+    &native::Steam
+}
+
+pub fn processes() -> &'static dyn ProcessBackend {
+    #[cfg(test)]
+    if let Some(processes) = testing::OVERRIDES.with(|overrides| overrides.get().processes) {
+        return processes;
+    }
+    &native::Processes
+}
+
+pub fn injector() -> &'static dyn Injector {
+    #[cfg(test)]
+    if let Some(injector) = testing::OVERRIDES.with(|overrides| overrides.get().injector) {
+        return injector;
+    }
+    &native::Injector
+}
+
+/// Running processes named `name` as sysinfo sees them, without architecture or window.
+fn running(name: &str) -> Vec<ProcessInstance> {
+    let mut system = System::new();
+    system.refresh_processes();
+
+    system
+        .processes()
+        .values()
+        .filter(|process| process.name().eq_ignore_ascii_case(name))
+        .map(|process| ProcessInstance {
+            pid: process.pid().as_u32(),
+            name: process.name().to_string(),
+            start_time: Local.timestamp_opt(process.start_time() as i64, 0).single(),
+            arch: ProcessArch::Unknown,
+            window_title: None,
+        })
+        .collect()
+}
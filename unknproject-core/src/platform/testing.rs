@@ -0,0 +1,247 @@
+//! In-memory backends for unit tests. Overrides are per thread, so tests running in
+//! parallel don't see each other's processes.
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+};
+
+use chrono::{Local, TimeZone};
+
+use crate::processes::{ProcessArch, ProcessInstance};
+
+#[derive(Default, Clone, Copy)]
+pub struct Overrides {
+    pub steam: Option<&'static dyn super::SteamLocator>,
+    pub processes: Option<&'static dyn super::ProcessBackend>,
+    pub injector: Option<&'static dyn super::Injector>,
+}
+
+thread_local! {
+    pub static OVERRIDES: Cell<Overrides> = Cell::default();
+    /// Answered by `crate::config_dir` instead of the user's config folder.
+    pub static CONFIG_DIR: RefCell<Option<PathBuf>> = const { RefCell::new(None) };
+}
+
+/// Makes the platform functions on this thread answer from fresh fakes. They're leaked,
+/// which is fine for the lifetime of a test run.
+pub fn install() -> (&'static FakeProcesses, &'static FakeInjector) {
+    let processes: &'static FakeProcesses = Box::leak(Box::default());
+    let injector: &'static FakeInjector = Box::leak(Box::new(FakeInjector {
+        processes,
+        fail_with: Mutex::new(None),
+        crash: Mutex::new(false),
+        injected: Mutex::new(Vec::new()),
+    }));
+
+    OVERRIDES.with(|overrides| {
+        overrides.set(Overrides {
+            processes: Some(processes),
+            injector: Some(injector),
+            ..overrides.get()
+        })
+    });
+    (processes, injector)
+}
+
+/// Points `crate::config_dir` on this thread to a fresh, empty folder and returns it.
+pub fn install_config_dir() -> PathBuf {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    let dir = env::temp_dir().join(format!(
+        "unknproject-config-{}-{}",
+        process::id(),
+        NEXT.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::remove_dir_all(&dir).ok();
+    fs::create_dir_all(&dir).unwrap();
+
+    CONFIG_DIR.with(|config_dir| *config_dir.borrow_mut() = Some(dir.clone()));
+    dir
+}
+
+pub fn install_steam(path: PathBuf) {
+    let steam: &'static FakeSteam = Box::leak(Box::new(FakeSteam(path)));
+    OVERRIDES.with(|overrides| {
+        overrides.set(Overrides {
+            steam: Some(steam),
+            ..overrides.get()
+        })
+    });
+}
+
+pub struct FakeSteam(PathBuf);
+
+impl super::SteamLocator for FakeSteam {
+    fn install_path(&self) -> Result<PathBuf, String> {
+        Ok(self.0.clone())
+    }
+}
+
+#[derive(Default)]
+pub struct FakeProcesses {
+    processes: Mutex<Vec<ProcessInstance>>,
+    /// Base address and path of every loaded module.
+    modules: Mutex<HashMap<u32, Vec<(usize, PathBuf)>>>,
+    /// Running, but can't be opened, like elevated processes for a normal user.
+    denied: Mutex<Vec<u32>>,
+}
+
+impl FakeProcesses {
+    /// Starts a process that has been running since `started_at` (seconds since the epoch).
+    pub fn spawn(&self, pid: u32, name: &str, arch: ProcessArch, started_at: i64) {
+        self.processes.lock().unwrap().push(ProcessInstance {
+            pid,
+            name: name.to_string(),
+            start_time: Local.timestamp_opt(started_at, 0).single(),
+            arch,
+            window_title: None,
+        });
+    }
+
+    pub fn kill(&self, pid: u32) {
+        self.processes
+            .lock()
+            .unwrap()
+            .retain(|process| process.pid != pid);
+        self.modules.lock().unwrap().remove(&pid);
+    }
+
+    pub fn deny_access(&self, pid: u32) {
+        self.denied.lock().unwrap().push(pid);
+    }
+
+    /// Returns the base address the module got.
+    pub fn load(&self, pid: u32, path: &Path) -> usize {
+        let mut modules = self.modules.lock().unwrap();
+        let base = 0x1000_0000 + modules.values().flatten().count() * 0x10_0000;
+        modules
+            .entry(pid)
+            .or_default()
+            .push((base, path.to_path_buf()));
+        base
+    }
+
+    /// Whether a module was loaded at `base` and is gone now.
+    pub fn unload(&self, pid: u32, base: usize) -> bool {
+        let mut modules = self.modules.lock().unwrap();
+        let Some(modules) = modules.get_mut(&pid) else {
+            return false;
+        };
+        let before = modules.len();
+        modules.retain(|(loaded, _)| *loaded != base);
+        modules.len() < before
+    }
+
+    fn get(&self, pid: u32) -> Option<ProcessInstance> {
+        if self.denied.lock().unwrap().contains(&pid) {
+            return None;
+        }
+        self.processes
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|process| process.pid == pid)
+            .cloned()
+    }
+}
+
+impl super::ProcessBackend for FakeProcesses {
+    fn list(&self, name: &str) -> Vec<ProcessInstance> {
+        self.processes
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|process| process.name.eq_ignore_ascii_case(name))
+            .cloned()
+            .collect()
+    }
+
+    fn find_all(&self, name: &str) -> Vec<u32> {
+        self.list(name)
+            .into_iter()
+            .map(|process| process.pid)
+            .collect()
+    }
+
+    fn name(&self, pid: u32) -> Option<String> {
+        self.get(pid).map(|process| process.name)
+    }
+
+    fn alive(&self, pid: u32) -> Option<bool> {
+        if self.denied.lock().unwrap().contains(&pid) {
+            return None;
+        }
+        Some(self.get(pid).is_some())
+    }
+
+    fn arch(&self, pid: u32) -> ProcessArch {
+        self.get(pid)
+            .map(|process| process.arch)
+            .unwrap_or(ProcessArch::Unknown)
+    }
+
+    fn module_by_path(&self, pid: u32, path: &Path) -> Result<Option<PathBuf>, String> {
+        self.get(pid).ok_or("Process not found.")?;
+        Ok(self.modules.lock().unwrap().get(&pid).and_then(|modules| {
+            modules
+                .iter()
+                .find(|(_, module)| module == path)
+                .map(|(_, module)| module.clone())
+        }))
+    }
+
+    fn module_by_name(&self, pid: u32, name: &str) -> Result<Option<PathBuf>, String> {
+        self.get(pid).ok_or("Process not found.")?;
+        Ok(self.modules.lock().unwrap().get(&pid).and_then(|modules| {
+            modules
+                .iter()
+                .map(|(_, module)| module)
+                .find(|module| {
+                    module
+                        .file_name()
+                        .is_some_and(|file| file.to_string_lossy().eq_ignore_ascii_case(name))
+                })
+                .cloned()
+        }))
+    }
+}
+
+/// Loads DLLs into the fake processes. `fail_with` makes every injection fail, and
+/// `crash` kills the target as soon as something is injected.
+pub struct FakeInjector {
+    processes: &'static FakeProcesses,
+    pub fail_with: Mutex<Option<String>>,
+    pub crash: Mutex<bool>,
+    pub injected: Mutex<Vec<(u32, PathBuf)>>,
+}
+
+impl super::Injector for FakeInjector {
+    fn inject(&self, pid: u32, dll_path: &Path) -> Result<usize, String> {
+        if let Some(error) = self.fail_with.lock().unwrap().clone() {
+            return Err(error);
+        }
+        self.processes.get(pid).ok_or("Process not found.")?;
+
+        self.injected
+            .lock()
+            .unwrap()
+            .push((pid, dll_path.to_path_buf()));
+        let base = self.processes.load(pid, dll_path);
+        if *self.crash.lock().unwrap() {
+            self.processes.kill(pid);
+        }
+        Ok(base)
+    }
+
+    fn eject(&self, pid: u32, base: usize) -> Result<bool, String> {
+        self.processes.get(pid).ok_or("Process not found.")?;
+        Ok(self.processes.unload(pid, base))
+    }
+}
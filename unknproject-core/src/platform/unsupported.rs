@@ -0,0 +1,83 @@
+//! Stand-ins for hosts other than Windows. Processes are still listed through sysinfo,
+//! but nothing can be looked into or injected.
+
+use std::path::{Path, PathBuf};
+
+use sysinfo::{Pid, PidExt, ProcessExt, System, SystemExt};
+
+use crate::processes::{ProcessArch, ProcessInstance};
+
+const UNSUPPORTED: &str = "Injection is only supported on Windows.";
+
+pub struct Steam;
+
+impl super::SteamLocator for Steam {
+    fn install_path(&self) -> Result<PathBuf, String> {
+        let home = dirs::home_dir().ok_or("Failed to locate the home directory")?;
+
+        [
+            ".steam/steam",
+            ".local/share/Steam",
+            "Library/Application Support/Steam",
+        ]
+        .iter()
+        .map(|path| home.join(path))
+        .find(|path| path.is_dir())
+        .ok_or_else(|| "Steam installation not found".to_string())
+    }
+}
+
+pub struct Processes;
+
+impl super::ProcessBackend for Processes {
+    fn list(&self, name: &str) -> Vec<ProcessInstance> {
+        super::running(name)
+    }
+
+    fn find_all(&self, name: &str) -> Vec<u32> {
+        super::running(name)
+            .into_iter()
+            .map(|instance| instance.pid)
+            .collect()
+    }
+
+    fn name(&self, pid: u32) -> Option<String> {
+        let pid = Pid::from_u32(pid);
+        let mut system = System::new();
+        if !system.refresh_process(pid) {
+            return None;
+        }
+
+        system
+            .process(pid)
+            .map(|process| process.name().to_string())
+    }
+
+    fn alive(&self, pid: u32) -> Option<bool> {
+        Some(self.name(pid).is_some())
+    }
+
+    fn arch(&self, _pid: u32) -> ProcessArch {
+        ProcessArch::Unknown
+    }
+
+    fn module_by_path(&self, _pid: u32, _path: &Path) -> Result<Option<PathBuf>, String> {
+        Err(UNSUPPORTED.to_string())
+    }
+
+    fn module_by_name(&self, _pid: u32, _name: &str) -> Result<Option<PathBuf>, String> {
+        Err(UNSUPPORTED.to_string())
+    }
+}
+
+pub struct Injector;
+
+impl super::Injector for Injector {
+    fn inject(&self, _pid: u32, _dll_path: &Path) -> Result<usize, String> {
+        Err(UNSUPPORTED.to_string())
+    }
+
+    fn eject(&self, _pid: u32, _base: usize) -> Result<bool, String> {
+        Err(UNSUPPORTED.to_string())
+    }
+}
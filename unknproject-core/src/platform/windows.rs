@@ -0,0 +1,185 @@
+use std::path::{Path, PathBuf};
+
+use dll_syringe::{
+    process::{OwnedProcess, Process},
+    Syringe,
+};
+use winreg::{
+    enums::{HKEY_LOCAL_MACHINE, KEY_READ},
+    RegKey,
+};
+
+use crate::processes::{ProcessArch, ProcessInstance};
+
+pub struct Steam;
+
+impl super::SteamLocator for Steam {
+    fn install_path(&self) -> Result<PathBuf, String> {
+        let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+        let installation_regkey = hklm
+            .open_subkey_with_flags("SOFTWARE\\Wow6432Node\\Valve\\Steam", KEY_READ)
+            .or_else(|_| hklm.open_subkey_with_flags("SOFTWARE\\Valve\\Steam", KEY_READ))
+            .map_err(|e| format!("Failed to open Steam registry key: {e}"))?;
+
+        installation_regkey
+            .get_value::<String, _>("InstallPath")
+            .map(PathBuf::from)
+            .map_err(|e| format!("Failed to get InstallPath: {e}"))
+    }
+}
+
+pub struct Processes;
+
+impl super::ProcessBackend for Processes {
+    fn list(&self, name: &str) -> Vec<ProcessInstance> {
+        super::running(name)
+            .into_iter()
+            .map(|instance| ProcessInstance {
+                arch: self.arch(instance.pid),
+                window_title: main_window_title(instance.pid),
+                ..instance
+            })
+            .collect()
+    }
+
+    fn find_all(&self, name: &str) -> Vec<u32> {
+        OwnedProcess::find_all_by_name(name)
+            .into_iter()
+            .filter_map(|process| process.pid().ok().map(|pid| pid.get()))
+            .collect()
+    }
+
+    fn name(&self, pid: u32) -> Option<String> {
+        OwnedProcess::from_pid(pid)
+            .ok()
+            .filter(|process| process.is_alive())?
+            .base_name()
+            .ok()
+    }
+
+    fn alive(&self, pid: u32) -> Option<bool> {
+        match OwnedProcess::from_pid(pid) {
+            Ok(process) => Some(process.is_alive()),
+            // ERROR_INVALID_PARAMETER: no process has that id anymore.
+            Err(e) if e.raw_os_error() == Some(87) => Some(false),
+            Err(e) => {
+                log::debug!("Couldn't open {} to check it's running: {}", pid, e);
+                None
+            }
+        }
+    }
+
+    fn arch(&self, pid: u32) -> ProcessArch {
+        match OwnedProcess::from_pid(pid).map(|process| process.is_x64()) {
+            Ok(Ok(true)) => ProcessArch::X64,
+            Ok(Ok(false)) => ProcessArch::X86,
+            _ => ProcessArch::Unknown,
+        }
+    }
+
+    fn module_by_path(&self, pid: u32, path: &Path) -> Result<Option<PathBuf>, String> {
+        let process = OwnedProcess::from_pid(pid).map_err(|e| e.to_string())?;
+        let module = process
+            .borrowed()
+            .find_module_by_path(path)
+            .map_err(|e| e.to_string())?;
+
+        Ok(module.map(|module| module.path().unwrap_or_else(|_| path.to_path_buf())))
+    }
+
+    fn module_by_name(&self, pid: u32, name: &str) -> Result<Option<PathBuf>, String> {
+        let process = OwnedProcess::from_pid(pid).map_err(|e| e.to_string())?;
+        let module = process
+            .borrowed()
+            .find_module_by_name(name)
+            .map_err(|e| e.to_string())?;
+
+        module
+            .map(|module| module.path().map_err(|e| e.to_string()))
+            .transpose()
+    }
+}
+
+fn main_window_title(pid: u32) -> Option<String> {
+    use winapi::{
+        shared::{
+            minwindef::{BOOL, DWORD, LPARAM, TRUE},
+            windef::HWND,
+        },
+        um::winuser::{
+            EnumWindows, GetWindow, GetWindowTextLengthW, GetWindowTextW, GetWindowThreadProcessId,
+            IsWindowVisible, GW_OWNER,
+        },
+    };
+
+    struct Search {
+        pid: DWORD,
+        title: Option<String>,
+    }
+
+    unsafe extern "system" fn callback(hwnd: HWND, lparam: LPARAM) -> BOOL {
+        let search = &mut *(lparam as *mut Search);
+
+        let mut window_pid: DWORD = 0;
+        GetWindowThreadProcessId(hwnd, &mut window_pid);
+
+        if window_pid != search.pid
+            || IsWindowVisible(hwnd) == 0
+            || !GetWindow(hwnd, GW_OWNER).is_null()
+        {
+            return TRUE;
+        }
+
+        let length = GetWindowTextLengthW(hwnd);
+        if length <= 0 {
+            return TRUE;
+        }
+
+        let mut buffer = vec![0u16; length as usize + 1];
+        let copied = GetWindowTextW(hwnd, buffer.as_mut_ptr(), buffer.len() as i32);
+        search.title = Some(String::from_utf16_lossy(&buffer[..copied as usize]));
+
+        0
+    }
+
+    let mut search = Search { pid, title: None };
+    unsafe {
+        EnumWindows(Some(callback), &mut search as *mut Search as LPARAM);
+    }
+
+    search.title
+}
+
+pub struct Injector;
+
+impl super::Injector for Injector {
+    fn inject(&self, pid: u32, dll_path: &Path) -> Result<usize, String> {
+        let process = OwnedProcess::from_pid(pid).map_err(|e| e.to_string())?;
+        Syringe::for_process(process)
+            .inject(dll_path)
+            .map(|module| module.handle() as usize)
+            .map_err(|e| e.to_string())
+    }
+
+    fn eject(&self, pid: u32, base: usize) -> Result<bool, String> {
+        let process = OwnedProcess::from_pid(pid).map_err(|e| e.to_string())?;
+        let syringe = Syringe::for_process(process);
+
+        // The handle is only trusted while the process still lists it, the address may
+        // have been reused since.
+        let loaded = syringe
+            .process()
+            .modules()
+            .map_err(|e| format!("Failed to list modules: {}", e))?
+            .into_iter()
+            .find(|module| module.handle() as usize == base);
+
+        match loaded {
+            Some(module) => syringe
+                .eject(module)
+                .map(|()| true)
+                .map_err(|e| e.to_string()),
+            None => Ok(false),
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use std::{collections::HashMap, fmt, time::Instant};
+
+use chrono::{DateTime, Local};
+
+use crate::platform;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessArch {
+    X86,
+    X64,
+    Unknown,
+}
+
+impl ProcessArch {
+    /// Architecture the loader itself was built for.
+    pub fn current() -> Self {
+        if cfg!(target_pointer_width = "64") {
+            ProcessArch::X64
+        } else {
+            ProcessArch::X86
+        }
+    }
+}
+
+impl fmt::Display for ProcessArch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProcessArch::X86 => write!(f, "x86"),
+            ProcessArch::X64 => write!(f, "x64"),
+            ProcessArch::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessInstance {
+    pub pid: u32,
+    pub name: String,
+    pub start_time: Option<DateTime<Local>>,
+    pub arch: ProcessArch,
+    pub window_title: Option<String>,
+}
+
+impl ProcessInstance {
+    pub fn label(&self) -> String {
+        let started = self
+            .start_time
+            .map(|t| t.format("%H:%M:%S").to_string())
+            .unwrap_or_else(|| "?".to_string());
+
+        match &self.window_title {
+            Some(title) => format!(
+                "{} [{}] started {} - {}",
+                self.pid, self.arch, started, title
+            ),
+            None => format!("{} [{}] started {}", self.pid, self.arch, started),
+        }
+    }
+}
+
+/// Lists every running process whose executable name matches `name`, oldest first.
+pub fn list_instances(name: &str) -> Vec<ProcessInstance> {
+    let mut instances = platform::processes().list(name);
+    instances.sort_by_key(|instance| (instance.start_time, instance.pid));
+    instances
+}
+
+/// Picks the requested instance of `name`, or the first match when no PID was chosen.
+/// A chosen instance that is gone is not swapped for another one.
+pub fn open_process(name: &str, pid: Option<u32>) -> Option<u32> {
+    match pid {
+        Some(pid) => match platform::processes().name(pid) {
+            Some(base_name) if base_name.eq_ignore_ascii_case(name) => Some(pid),
+            _ => {
+                log::warn!("Process {} ({}) is gone", name, pid);
+                None
+            }
+        },
+        None => platform::processes().find_all(name).first().copied(),
+    }
+}
+
+pub fn is_alive(pid: u32) -> bool {
+    platform::processes().name(pid).is_some()
+}
+
+/// Whether `pid` exited for sure. A process we may not open is assumed to still run.
+pub fn has_exited(pid: u32) -> bool {
+    platform::processes().alive(pid) == Some(false)
+}
+
+/// Cached instance lists per process name, refreshed on demand so the UI
+/// doesn't enumerate processes every frame.
+#[derive(Default)]
+pub struct ProcessList {
+    entries: HashMap<String, (Vec<ProcessInstance>, Instant)>,
+}
+
+impl ProcessList {
+    pub fn refresh(&mut self, process: &str) {
+        self.entries.insert(
+            process.to_lowercase(),
+            (list_instances(process), Instant::now()),
+        );
+    }
+
+    pub fn instances(&mut self, process: &str) -> Vec<ProcessInstance> {
+        let stale = self
+            .entries
+            .get(&process.to_lowercase())
+            .map(|(_, refreshed_at)| refreshed_at.elapsed().as_secs() >= 3)
+            .unwrap_or(true);
+
+        if stale {
+            self.refresh(process);
+        }
+
+        self.entries
+            .get(&process.to_lowercase())
+            .map(|(instances, _)| instances.clone())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::testing;
+
+    #[test]
+    fn lists_instances_oldest_first() {
+        let (processes, _) = testing::install();
+        processes.spawn(30, "game.exe", ProcessArch::X64, 2_000);
+        processes.spawn(10, "Game.exe", ProcessArch::X64, 3_000);
+        processes.spawn(20, "game.exe", ProcessArch::X86, 1_000);
+        processes.spawn(40, "other.exe", ProcessArch::X64, 500);
+
+        let pids: Vec<u32> = list_instances("GAME.EXE")
+            .iter()
+            .map(|instance| instance.pid)
+            .collect();
+        assert_eq!(pids, vec![20, 30, 10]);
+    }
+
+    #[test]
+    fn opens_the_chosen_instance() {
+        let (processes, _) = testing::install();
+        processes.spawn(10, "game.exe", ProcessArch::X64, 1_000);
+        processes.spawn(20, "game.exe", ProcessArch::X64, 2_000);
+
+        assert_eq!(open_process("game.exe", Some(20)), Some(20));
+        assert_eq!(open_process("game.exe", None), Some(10));
+        assert_eq!(open_process("missing.exe", None), None);
+        assert_eq!(open_process("other.exe", Some(20)), None);
+        assert!(is_alive(10));
+
+        processes.kill(10);
+        assert!(!is_alive(10));
+
+        // The chosen instance is gone, the other one must not be targeted instead.
+        processes.kill(20);
+        processes.spawn(30, "game.exe", ProcessArch::X64, 3_000);
+        assert_eq!(open_process("game.exe", Some(20)), None);
+        assert_eq!(open_process("game.exe", None), Some(30));
+    }
+
+    #[test]
+    fn cached_lists_refresh_on_demand() {
+        let (processes, _) = testing::install();
+        let mut list = ProcessList::default();
+        assert!(list.instances("game.exe").is_empty());
+
+        processes.spawn(10, "game.exe", ProcessArch::X64, 1_000);
+        assert!(list.instances("game.exe").is_empty());
+
+        list.refresh("game.exe");
+        assert_eq!(list.instances("game.exe").len(), 1);
+    }
+}
@@ -1,20 +1,13 @@
-use std::{collections::HashMap, fs, path::PathBuf};
+use std::{collections::HashMap, fs};
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct Statistics {
     pub opened_count: u64,
     pub inject_counts: HashMap<String, u64>,
-}
-
-impl Default for Statistics {
-    fn default() -> Self {
-        Statistics {
-            opened_count: 0,
-            inject_counts: HashMap::new(),
-        }
-    }
+    #[serde(default)]
+    pub crash_counts: HashMap<String, u64>,
 }
 
 impl Statistics {
@@ -24,15 +17,19 @@ impl Statistics {
         self.save();
     }
 
+    pub fn increment_crash_count(&mut self, hack_name: &str) {
+        let count = self.crash_counts.entry(hack_name.to_string()).or_insert(0);
+        *count += 1;
+        self.save();
+    }
+
     pub fn increment_opened_count(&mut self) {
         self.opened_count += 1;
         self.save();
     }
 
     pub fn load() -> Self {
-        let statistics_dir = dirs::config_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("unknproject");
+        let statistics_dir = crate::config_dir();
 
         fs::create_dir_all(&statistics_dir).ok();
         let statistics_path = statistics_dir.join("statistics.json");
@@ -45,9 +42,7 @@ impl Statistics {
     }
 
     pub fn save(&self) {
-        let statistics_dir = dirs::config_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("unknproject");
+        let statistics_dir = crate::config_dir();
 
         fs::create_dir_all(&statistics_dir).ok();
         let statistics_path = statistics_dir.join("statistics.json");
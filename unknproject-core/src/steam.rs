@@ -0,0 +1,353 @@
+use std::{collections::HashMap, fs, path::PathBuf, time::Instant};
+
+use vdf_reader::{entry::Table, Reader};
+
+use crate::platform;
+
+#[derive(Debug, Clone)]
+pub struct SteamAccount {
+    pub username: String,
+    pub name: String,
+    pub steam_id: u64,
+}
+
+/// Difference between a SteamID64 and the 32-bit account id used for `userdata` folders.
+const STEAM_ID64_BASE: u64 = 76561197960265728;
+
+impl SteamAccount {
+    fn locate_steam() -> Result<PathBuf, String> {
+        platform::steam().install_path()
+    }
+
+    fn parse_user() -> Result<Self, String> {
+        let path = Self::locate_steam()?.join("config/loginusers.vdf");
+        let raw =
+            fs::read_to_string(&path).map_err(|e| format!("Failed to read loginusers.vdf: {e}"))?;
+
+        let file = Table::load(&mut Reader::from(raw.as_str()))
+            .map_err(|e| format!("Failed to parse VDF: {e}"))?;
+        let users = file
+            .get("users")
+            .and_then(|u| u.as_table())
+            .ok_or("Missing or invalid users table")?;
+
+        users
+            .iter()
+            .find_map(|(steam_id, user_data)| {
+                let user_info = user_data.as_table()?;
+                let username = user_info.get("AccountName")?.as_str()?;
+                let name = user_info.get("PersonaName")?.as_str()?;
+
+                if user_info.get("MostRecent")?.as_str() == Some("1") {
+                    log::info!("Parsed steam user: {}", name);
+
+                    Some(Self {
+                        username: username.to_owned(),
+                        name: name.to_owned(),
+                        steam_id: steam_id.parse().unwrap_or_default(),
+                    })
+                } else {
+                    None
+                }
+            })
+            .ok_or_else(|| "No recent user found".to_string())
+    }
+
+    /// Asks the Steam client to start `app_id`, passing `options` as extra launch options.
+    pub fn launch_game(app_id: u32, options: &str) -> Result<(), String> {
+        Self::locate_steam()?;
+
+        let mut url = format!("steam://rungameid/{}", app_id);
+        let options = options.trim();
+        if !options.is_empty() {
+            url.push_str("//");
+            url.push_str(&encode_launch_options(options));
+        }
+
+        log::info!("Launching {}", url);
+        opener::open(&url).map_err(|e| format!("Failed to open {}: {}", url, e))
+    }
+
+    pub fn new() -> Result<Self, String> {
+        Self::parse_user()
+    }
+
+    /// Launch options this account has set for `app_id` in the Steam client.
+    pub fn launch_options(&self, app_id: u32) -> Result<String, String> {
+        if self.steam_id < STEAM_ID64_BASE {
+            return Err("No Steam account detected".to_string());
+        }
+
+        let path = Self::locate_steam()?
+            .join("userdata")
+            .join((self.steam_id - STEAM_ID64_BASE).to_string())
+            .join("config/localconfig.vdf");
+        let raw = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read localconfig.vdf: {e}"))?;
+
+        parse_launch_options(&raw, app_id)
+    }
+}
+
+impl Default for SteamAccount {
+    fn default() -> Self {
+        Self {
+            username: "unknown".to_string(),
+            name: "unknown".to_string(),
+            steam_id: 0,
+        }
+    }
+}
+
+/// Percent-encodes the characters Steam would otherwise treat as URL syntax.
+fn encode_launch_options(options: &str) -> String {
+    let mut encoded = String::with_capacity(options.len());
+    for c in options.chars() {
+        match c {
+            'A'..='Z' | 'a'..='z' | '0'..='9' | '-' | '+' | '_' | '.' | '=' => encoded.push(c),
+            c => {
+                let mut buf = [0; 4];
+                for byte in c.encode_utf8(&mut buf).bytes() {
+                    encoded.push_str(&format!("%{:02X}", byte));
+                }
+            }
+        }
+    }
+    encoded
+}
+
+/// Case-insensitive lookup, Steam isn't consistent about `apps` vs `Apps`.
+fn get_table<'a>(table: &'a Table, key: &str) -> Option<&'a Table> {
+    table
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(key))
+        .and_then(|(_, entry)| entry.as_table())
+}
+
+/// Reads `LaunchOptions` for `app_id` out of a `localconfig.vdf`. An app without
+/// launch options yields an empty string.
+pub fn parse_launch_options(raw: &str, app_id: u32) -> Result<String, String> {
+    let file =
+        Table::load(&mut Reader::from(raw)).map_err(|e| format!("Failed to parse VDF: {e}"))?;
+
+    let apps = ["UserLocalConfigStore", "Software", "Valve", "Steam", "apps"]
+        .iter()
+        .try_fold(&file, |table, key| get_table(table, key))
+        .ok_or("Missing apps table in localconfig.vdf")?;
+
+    Ok(get_table(apps, &app_id.to_string())
+        .and_then(|app| {
+            app.iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case("LaunchOptions"))
+                .and_then(|(_, entry)| entry.as_str())
+        })
+        .unwrap_or_default()
+        .to_string())
+}
+
+/// Required options (e.g. `-insecure`) that are not part of `configured`.
+pub fn missing_launch_options(configured: &str, required: &[String]) -> Vec<String> {
+    let configured = format!(
+        " {} ",
+        configured.split_whitespace().collect::<Vec<_>>().join(" ")
+    );
+
+    required
+        .iter()
+        .filter(|option| {
+            let option = option.split_whitespace().collect::<Vec<_>>().join(" ");
+            !configured.contains(&format!(" {} ", option))
+        })
+        .cloned()
+        .collect()
+}
+
+/// Cached launch options per app id, so the details panel doesn't reparse
+/// `localconfig.vdf` every frame.
+#[derive(Default)]
+pub struct LaunchOptionsCache {
+    entries: HashMap<u32, (Result<String, String>, Instant)>,
+}
+
+impl LaunchOptionsCache {
+    pub fn get(&mut self, account: &SteamAccount, app_id: u32) -> Result<String, String> {
+        let stale = self
+            .entries
+            .get(&app_id)
+            .map(|(_, refreshed_at)| refreshed_at.elapsed().as_secs() >= 10)
+            .unwrap_or(true);
+
+        if stale {
+            let options = account.launch_options(app_id);
+            if let Err(e) = &options {
+                log::debug!("Couldn't read launch options for {}: {}", app_id, e);
+            }
+            self.entries.insert(app_id, (options, Instant::now()));
+        }
+
+        self.entries
+            .get(&app_id)
+            .map(|(options, _)| options.clone())
+            .unwrap_or_else(|| Err("Launch options unavailable".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+    use crate::platform::testing;
+
+    const LOGIN_USERS: &str = r#"
+"users"
+{
+    "76561198000000001"
+    {
+        "AccountName"   "old_account"
+        "PersonaName"   "Old"
+        "MostRecent"    "0"
+    }
+    "76561198000000002"
+    {
+        "AccountName"   "player"
+        "PersonaName"   "Player One"
+        "MostRecent"    "1"
+    }
+}
+"#;
+
+    const LOCAL_CONFIG: &str = r#"
+"UserLocalConfigStore"
+{
+    "Software"
+    {
+        "Valve"
+        {
+            "Steam"
+            {
+                "apps"
+                {
+                    "730"
+                    {
+                        "LaunchOptions"     "-insecure -novid"
+                    }
+                }
+            }
+        }
+    }
+}
+"#;
+
+    /// Real `localconfig.vdf`s carry a lot more than launch options.
+    const MIXED_CASE_CONFIG: &str = r#"
+"UserLocalConfigStore"
+{
+    "friends"
+    {
+        "PersonaName"       "Player One"
+    }
+    "Software"
+    {
+        "Valve"
+        {
+            "Steam"
+            {
+                "Apps"
+                {
+                    "730"
+                    {
+                        "LastPlayed"        "1700000000"
+                        "launchoptions"     "-insecure"
+                    }
+                    "440"
+                    {
+                        "LastPlayed"        "1600000000"
+                    }
+                }
+            }
+        }
+    }
+}
+"#;
+
+    #[test]
+    fn parses_launch_options() {
+        assert_eq!(
+            parse_launch_options(LOCAL_CONFIG, 730).unwrap(),
+            "-insecure -novid"
+        );
+        assert_eq!(
+            parse_launch_options(MIXED_CASE_CONFIG, 730).unwrap(),
+            "-insecure"
+        );
+    }
+
+    #[test]
+    fn apps_without_launch_options_have_none() {
+        assert_eq!(parse_launch_options(MIXED_CASE_CONFIG, 440).unwrap(), "");
+        assert_eq!(parse_launch_options(MIXED_CASE_CONFIG, 570).unwrap(), "");
+    }
+
+    #[test]
+    fn missing_apps_table_is_an_error() {
+        let config = r#"
+"UserLocalConfigStore"
+{
+    "Software"
+    {
+        "Valve"
+        {
+            "Steam"
+            {
+                "LastUpdate"    "1"
+            }
+        }
+    }
+}
+"#;
+        assert_eq!(
+            parse_launch_options(config, 730).unwrap_err(),
+            "Missing apps table in localconfig.vdf"
+        );
+        assert!(parse_launch_options("", 730).is_err());
+    }
+
+    #[test]
+    fn finds_missing_launch_options() {
+        let required = vec!["-insecure".to_string(), "+exec  autoexec".to_string()];
+
+        assert_eq!(
+            missing_launch_options(&parse_launch_options(LOCAL_CONFIG, 730).unwrap(), &required),
+            vec!["+exec  autoexec".to_string()]
+        );
+        assert!(missing_launch_options("-novid  +exec autoexec -insecure", &required).is_empty());
+        // Options only count as whole words.
+        assert_eq!(
+            missing_launch_options("-insecure2 +exec autoexec", &required),
+            vec!["-insecure".to_string()]
+        );
+        assert_eq!(missing_launch_options("", &required), required);
+    }
+
+    #[test]
+    fn reads_the_account_from_the_steam_folder() {
+        let steam = env::temp_dir().join(format!("unknproject-steam-{}", std::process::id()));
+        let account_dir = steam.join("userdata/39734274/config");
+        fs::create_dir_all(steam.join("config")).unwrap();
+        fs::create_dir_all(&account_dir).unwrap();
+        fs::write(steam.join("config/loginusers.vdf"), LOGIN_USERS).unwrap();
+        fs::write(account_dir.join("localconfig.vdf"), LOCAL_CONFIG).unwrap();
+        testing::install_steam(steam.clone());
+
+        let account = SteamAccount::new().unwrap();
+        assert_eq!(account.username, "player");
+        assert_eq!(account.name, "Player One");
+        assert_eq!(account.steam_id, 76561198000000002);
+        assert_eq!(account.launch_options(730).unwrap(), "-insecure -novid");
+        assert_eq!(account.launch_options(440).unwrap(), "");
+
+        assert!(SteamAccount::default().launch_options(730).is_err());
+        fs::remove_dir_all(&steam).ok();
+    }
+}
@@ -0,0 +1,809 @@
+//! A small HTTP server for self-hosted catalogs. It serves a folder laid out like
+//! `resources/`, a catalog such as `Hacklist.html` next to a `hacks/` folder of DLLs, which
+//! is what the loader expects from a catalog source or CDN.
+
+use std::{
+    collections::HashMap,
+    fs,
+    io::{BufRead, BufReader, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    path::{Component, Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, SystemTime},
+};
+
+use ed25519_dalek::{Signer, SigningKey};
+use percent_encoding::percent_decode_str;
+use unknproject_core::{
+    catalog,
+    hacks::HackApiResponse,
+    hash::{sha256_file, sha256_hex, to_hex},
+};
+
+/// File listing the SHA-256 of everything served, in `sha256sum` format.
+pub const HASHES_FILE: &str = "SHA256SUMS";
+
+/// Upper bound for the request line and headers; the server only answers `GET` and `HEAD`.
+const MAX_REQUEST_BYTES: u64 = 16 * 1024;
+
+#[derive(Clone)]
+pub struct ServeOptions {
+    pub root: PathBuf,
+    /// File name of the catalog inside `root`.
+    pub catalog: String,
+    /// Fill in missing `sha256` and `size` fields of the catalog and publish `SHA256SUMS`.
+    pub hashes: bool,
+    /// Signs the catalog as served; the signature is published as `<catalog>.sig`.
+    pub signing_key: Option<SigningKey>,
+}
+
+pub struct CatalogServer {
+    addr: SocketAddr,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl CatalogServer {
+    pub fn start(addr: impl ToSocketAddrs, options: ServeOptions) -> Result<Self, String> {
+        let listener = TcpListener::bind(addr).map_err(|e| format!("Failed to bind: {}", e))?;
+        let addr = listener.local_addr().map_err(|e| e.to_string())?;
+        // Polled so dropping the server stops it, e.g. at the end of a test.
+        listener
+            .set_nonblocking(true)
+            .map_err(|e| format!("Failed to start the server: {}", e))?;
+
+        let root = options
+            .root
+            .canonicalize()
+            .map_err(|e| format!("Failed to open {}: {}", options.root.display(), e))?;
+        let state = Arc::new(State {
+            options: ServeOptions { root, ..options },
+            hashes: HashCache::default(),
+        });
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop);
+        let thread = thread::spawn(move || {
+            while !thread_stop.load(Ordering::SeqCst) {
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        let state = Arc::clone(&state);
+                        thread::spawn(move || handle(stream, &state));
+                    }
+                    Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                        thread::sleep(Duration::from_millis(50));
+                    }
+                    Err(e) => log::warn!("Connection failed: {}", e),
+                }
+            }
+        });
+
+        Ok(Self {
+            addr,
+            stop,
+            thread: Some(thread),
+        })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Base URL, e.g. `http://127.0.0.1:8080/`.
+    pub fn url(&self) -> String {
+        format!("http://{}/", self.addr)
+    }
+
+    /// Blocks for as long as the server runs.
+    pub fn wait(mut self) {
+        if let Some(thread) = self.thread.take() {
+            thread.join().ok();
+        }
+    }
+}
+
+impl Drop for CatalogServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            thread.join().ok();
+        }
+    }
+}
+
+/// What the connection threads share.
+struct State {
+    options: ServeOptions,
+    hashes: HashCache,
+}
+
+// MARK: Requests
+struct Request {
+    method: String,
+    path: String,
+    headers: HashMap<String, String>,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(String::as_str)
+    }
+}
+
+struct Response {
+    status: u16,
+    headers: Vec<(&'static str, String)>,
+    body: Vec<u8>,
+}
+
+impl Response {
+    fn error(status: u16) -> Self {
+        Self {
+            status,
+            headers: vec![("Content-Type", "text/plain; charset=utf-8".to_string())],
+            body: format!("{} {}\n", status, reason(status)).into_bytes(),
+        }
+    }
+}
+
+fn read_request(stream: &TcpStream) -> Result<Request, String> {
+    let mut reader = BufReader::new(stream.take(MAX_REQUEST_BYTES));
+    // Without a line ending the client either hung up or went past the limit.
+    let mut read_line = |line: &mut String| match reader.read_line(line) {
+        Ok(_) if line.ends_with('\n') => Ok(()),
+        Ok(_) => Err("incomplete or oversized request".to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+
+    let mut line = String::new();
+    read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err("malformed request line".to_string());
+    };
+    let path = target.split('?').next().unwrap_or(target).to_string();
+    let method = method.to_string();
+
+    let mut headers = HashMap::new();
+    loop {
+        let mut line = String::new();
+        read_line(&mut line)?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+        }
+    }
+
+    Ok(Request {
+        method,
+        path,
+        headers,
+    })
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        206 => "Partial Content",
+        304 => "Not Modified",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        416 => "Range Not Satisfiable",
+        _ => "Internal Server Error",
+    }
+}
+
+fn write_response(mut stream: &TcpStream, response: &Response, include_body: bool) {
+    let mut head = format!(
+        "HTTP/1.1 {} {}\r\n",
+        response.status,
+        reason(response.status)
+    );
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    // A 304 has no body of its own to describe.
+    if response.status != 304 {
+        head.push_str(&format!("Content-Length: {}\r\n", response.body.len()));
+    }
+    head.push_str("Connection: close\r\n\r\n");
+
+    let written = stream.write_all(head.as_bytes()).and_then(|()| {
+        if include_body {
+            stream.write_all(&response.body)
+        } else {
+            Ok(())
+        }
+    });
+    if let Err(e) = written {
+        log::debug!("Failed to answer request: {}", e);
+    }
+}
+
+fn handle(stream: TcpStream, state: &State) {
+    if stream.set_nonblocking(false).is_err()
+        || stream
+            .set_read_timeout(Some(Duration::from_secs(10)))
+            .is_err()
+    {
+        return;
+    }
+
+    let request = match read_request(&stream) {
+        Ok(request) => request,
+        Err(e) => {
+            log::debug!("Rejected request: {}", e);
+            write_response(&stream, &Response::error(400), true);
+            return;
+        }
+    };
+
+    let response = match request.method.as_str() {
+        "GET" | "HEAD" => respond(&request, state),
+        _ => {
+            let mut response = Response::error(405);
+            response.headers.push(("Allow", "GET, HEAD".to_string()));
+            response
+        }
+    };
+
+    log::info!("{} {} {}", request.method, request.path, response.status);
+    write_response(&stream, &response, request.method != "HEAD");
+}
+
+// MARK: Files
+struct Content {
+    body: Vec<u8>,
+    sha256: String,
+}
+
+impl Content {
+    fn generated(body: Vec<u8>) -> Self {
+        let sha256 = sha256_hex(&body);
+        Self { body, sha256 }
+    }
+}
+
+fn respond(request: &Request, state: &State) -> Response {
+    let Some(path) = percent_decode_str(&request.path)
+        .decode_utf8()
+        .ok()
+        .map(|path| path.trim_start_matches('/').to_string())
+    else {
+        return Response::error(400);
+    };
+
+    let Content { body, sha256 } = match content(&path, state) {
+        Ok(Some(content)) => content,
+        Ok(None) => return Response::error(404),
+        Err(e) => {
+            log::error!("Failed to serve {}: {}", path, e);
+            return Response::error(500);
+        }
+    };
+
+    let etag = format!("\"{}\"", sha256);
+    let mut headers = vec![
+        ("Content-Type", content_type(&path).to_string()),
+        ("ETag", etag.clone()),
+        ("Accept-Ranges", "bytes".to_string()),
+    ];
+
+    let matches_etag = |value: &str| {
+        value
+            .split(',')
+            .map(str::trim)
+            .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+    };
+    if request.header("if-none-match").is_some_and(matches_etag) {
+        return Response {
+            status: 304,
+            headers,
+            body: Vec::new(),
+        };
+    }
+
+    // A range of an older version of the file would corrupt a resumed download.
+    let range = match request.header("if-range") {
+        Some(tag) if tag != etag => None,
+        _ => request.header("range"),
+    };
+
+    match range.map_or(ByteRange::Full, |range| ByteRange::parse(range, body.len())) {
+        ByteRange::Full => Response {
+            status: 200,
+            headers,
+            body,
+        },
+        ByteRange::Partial(start, end) => {
+            headers.push((
+                "Content-Range",
+                format!("bytes {}-{}/{}", start, end, body.len()),
+            ));
+            Response {
+                status: 206,
+                headers,
+                body: body[start..=end].to_vec(),
+            }
+        }
+        ByteRange::Unsatisfiable => {
+            let mut response = Response::error(416);
+            response
+                .headers
+                .push(("Content-Range", format!("bytes */{}", body.len())));
+            response
+        }
+    }
+}
+
+/// Body of `path`, which is relative to the root. `None` if there is no such file.
+fn content(path: &str, state: &State) -> Result<Option<Content>, String> {
+    let options = &state.options;
+    if path == options.catalog {
+        return catalog_content(state).map(|body| Some(Content::generated(body)));
+    }
+
+    if path == format!("{}.sig", options.catalog) {
+        let Some(key) = &options.signing_key else {
+            return Ok(None);
+        };
+        let signature = key.sign(&catalog_content(state)?);
+        let body = format!("{}\n", to_hex(&signature.to_bytes())).into_bytes();
+        return Ok(Some(Content::generated(body)));
+    }
+
+    if path == HASHES_FILE && options.hashes {
+        return hashes_content(state).map(|body| Some(Content::generated(body)));
+    }
+
+    let Some(file) = resolve(&options.root, path) else {
+        return Ok(None);
+    };
+    let body = fs::read(&file).map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+    let sha256 = state.hashes.sha256(&file)?;
+    Ok(Some(Content { body, sha256 }))
+}
+
+/// Maps a request path to a file inside `root`, refusing anything that would leave it.
+fn resolve(root: &Path, path: &str) -> Option<PathBuf> {
+    let relative = Path::new(path);
+    if !relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)))
+    {
+        return None;
+    }
+
+    let file = root.join(relative).canonicalize().ok()?;
+    (file.starts_with(root) && file.is_file()).then_some(file)
+}
+
+/// The catalog as served: with `hashes`, entries get the hash and size of their DLL in
+/// `hacks/` unless they already publish one.
+fn catalog_content(state: &State) -> Result<Vec<u8>, String> {
+    let options = &state.options;
+    let path = options.root.join(&options.catalog);
+    let data = fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    if !options.hashes {
+        return Ok(data);
+    }
+
+    let mut entries = match serde_json::from_slice::<Vec<HackApiResponse>>(&data) {
+        Ok(entries) => entries,
+        Err(e) => {
+            log::warn!("Serving {} without hashes: {}", options.catalog, e);
+            return Ok(data);
+        }
+    };
+
+    let hacks_dir = catalog::default_hacks_dir(&path);
+    for entry in &mut entries {
+        let file = hacks_dir.join(&entry.file);
+        if entry.sha256.is_none() {
+            entry.sha256 = state.hashes.sha256(&file).ok();
+        }
+        if entry.size.is_none() {
+            entry.size = fs::metadata(&file).ok().map(|metadata| metadata.len());
+        }
+    }
+
+    catalog::to_json(&entries).map(String::into_bytes)
+}
+
+fn hashes_content(state: &State) -> Result<Vec<u8>, String> {
+    let options = &state.options;
+    let mut files = Vec::new();
+    collect_files(&options.root, &options.root, &mut files)?;
+    files.sort();
+
+    let mut lines = String::new();
+    for file in files {
+        let sha256 = if file == options.catalog {
+            sha256_hex(&catalog_content(state)?)
+        } else {
+            state.hashes.sha256(&options.root.join(&file))?
+        };
+        lines.push_str(&format!("{}  {}\n", sha256, file));
+    }
+    Ok(lines.into_bytes())
+}
+
+/// Every file below `dir`, relative to `root` and with `/` separators.
+fn collect_files(root: &Path, dir: &Path, files: &mut Vec<String>) -> Result<(), String> {
+    let entries =
+        fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(root, &path, files)?;
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            let relative = relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            files.push(relative);
+        }
+    }
+
+    Ok(())
+}
+
+fn content_type(path: &str) -> &'static str {
+    let extension = Path::new(path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+
+    match extension.as_str() {
+        "html" | "htm" => "text/html; charset=utf-8",
+        "json" => "application/json",
+        "sig" | "txt" | "toml" => "text/plain; charset=utf-8",
+        _ if path == HASHES_FILE => "text/plain; charset=utf-8",
+        _ => "application/octet-stream",
+    }
+}
+
+// MARK: Hashes
+/// Size and modification time a cached hash was computed for.
+type FileStamp = (u64, Option<SystemTime>);
+
+/// SHA-256 of served files, hashed again only once their size or modification time changes.
+#[derive(Default)]
+struct HashCache(Mutex<HashMap<PathBuf, (FileStamp, String)>>);
+
+impl HashCache {
+    fn sha256(&self, path: &Path) -> Result<String, String> {
+        let metadata =
+            fs::metadata(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let stamp = (metadata.len(), metadata.modified().ok());
+
+        if let Some((cached, sha256)) = self.0.lock().unwrap().get(path) {
+            if *cached == stamp && stamp.1.is_some() {
+                return Ok(sha256.clone());
+            }
+        }
+
+        let sha256 = sha256_file(path)?;
+        self.0
+            .lock()
+            .unwrap()
+            .insert(path.to_path_buf(), (stamp, sha256.clone()));
+        Ok(sha256)
+    }
+}
+
+// MARK: Ranges
+#[derive(Debug, PartialEq)]
+enum ByteRange {
+    Full,
+    /// First and last byte, inclusive.
+    Partial(usize, usize),
+    Unsatisfiable,
+}
+
+impl ByteRange {
+    /// Understands a single `bytes=` range. Anything else, including several ranges, is
+    /// answered with the whole file, which the spec allows.
+    fn parse(header: &str, len: usize) -> Self {
+        let Some(range) = header.trim().strip_prefix("bytes=") else {
+            return ByteRange::Full;
+        };
+        let Some((start, end)) = range.trim().split_once('-') else {
+            return ByteRange::Full;
+        };
+        if range.contains(',') {
+            return ByteRange::Full;
+        }
+
+        if start.is_empty() {
+            // `bytes=-500` is the last 500 bytes.
+            return match end.parse::<usize>() {
+                Ok(0) => ByteRange::Unsatisfiable,
+                Ok(_) if len == 0 => ByteRange::Unsatisfiable,
+                Ok(suffix) => ByteRange::Partial(len.saturating_sub(suffix), len - 1),
+                Err(_) => ByteRange::Full,
+            };
+        }
+
+        let Ok(start) = start.parse::<usize>() else {
+            return ByteRange::Full;
+        };
+        let end = match end {
+            "" => len.saturating_sub(1),
+            end => match end.parse::<usize>() {
+                Ok(end) => end.min(len.saturating_sub(1)),
+                Err(_) => return ByteRange::Full,
+            },
+        };
+
+        if start >= len {
+            ByteRange::Unsatisfiable
+        } else if end < start {
+            ByteRange::Full
+        } else {
+            ByteRange::Partial(start, end)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use unknproject_core::{config::Config, downloader::Downloader, http::UreqTransport};
+
+    use super::*;
+
+    struct Reply {
+        status: u16,
+        headers: HashMap<String, String>,
+        body: Vec<u8>,
+    }
+
+    impl Reply {
+        fn header(&self, name: &str) -> Option<&str> {
+            self.headers.get(name).map(String::as_str)
+        }
+    }
+
+    fn root(name: &str) -> PathBuf {
+        let root = env::temp_dir().join(format!("unknproject-serve-{}-{}", name, process::id()));
+        fs::remove_dir_all(&root).ok();
+        fs::create_dir_all(root.join("hacks")).unwrap();
+        fs::write(
+            root.join("Hacklist.html"),
+            r#"[{"id":1,"name":"Hack","description":"","author":"","status":"","file":"hack.dll","process":"game.exe","source":"","game":"Game"}]"#,
+        )
+        .unwrap();
+        fs::write(root.join("hacks/hack.dll"), (0..100u8).collect::<Vec<_>>()).unwrap();
+        root
+    }
+
+    fn serve(root: &Path, hashes: bool) -> CatalogServer {
+        CatalogServer::start(
+            "127.0.0.1:0",
+            ServeOptions {
+                root: root.to_path_buf(),
+                catalog: "Hacklist.html".to_string(),
+                hashes,
+                signing_key: None,
+            },
+        )
+        .unwrap()
+    }
+
+    fn send(server: &CatalogServer, request: &[u8]) -> Reply {
+        let mut stream = TcpStream::connect(server.addr()).unwrap();
+        stream.write_all(request).unwrap();
+        let mut data = Vec::new();
+        stream.read_to_end(&mut data).unwrap();
+
+        let split = data.windows(4).position(|w| w == b"\r\n\r\n").unwrap();
+        let head = String::from_utf8(data[..split].to_vec()).unwrap();
+        let mut lines = head.split("\r\n");
+        let status = lines
+            .next()
+            .unwrap()
+            .split(' ')
+            .nth(1)
+            .unwrap()
+            .parse()
+            .unwrap();
+        let headers = lines
+            .filter_map(|line| line.split_once(": "))
+            .map(|(name, value)| (name.to_ascii_lowercase(), value.to_string()))
+            .collect();
+        Reply {
+            status,
+            headers,
+            body: data[split + 4..].to_vec(),
+        }
+    }
+
+    fn get(server: &CatalogServer, path: &str, headers: &[(&str, &str)]) -> Reply {
+        let mut request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\n", path);
+        for (name, value) in headers {
+            request.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        request.push_str("\r\n");
+        send(server, request.as_bytes())
+    }
+
+    #[test]
+    fn parses_byte_ranges() {
+        assert_eq!(ByteRange::parse("bytes=0-9", 100), ByteRange::Partial(0, 9));
+        assert_eq!(
+            ByteRange::parse("bytes=90-", 100),
+            ByteRange::Partial(90, 99)
+        );
+        assert_eq!(
+            ByteRange::parse("bytes=-10", 100),
+            ByteRange::Partial(90, 99)
+        );
+        assert_eq!(
+            ByteRange::parse("bytes=-500", 100),
+            ByteRange::Partial(0, 99)
+        );
+        assert_eq!(
+            ByteRange::parse("bytes=50-500", 100),
+            ByteRange::Partial(50, 99)
+        );
+        assert_eq!(
+            ByteRange::parse("bytes=100-", 100),
+            ByteRange::Unsatisfiable
+        );
+        assert_eq!(ByteRange::parse("bytes=-0", 100), ByteRange::Unsatisfiable);
+        assert_eq!(ByteRange::parse("bytes=-10", 0), ByteRange::Unsatisfiable);
+        assert_eq!(ByteRange::parse("bytes=9-0", 100), ByteRange::Full);
+        assert_eq!(ByteRange::parse("bytes=0-1,5-6", 100), ByteRange::Full);
+        assert_eq!(ByteRange::parse("items=0-9", 100), ByteRange::Full);
+        assert_eq!(ByteRange::parse("bytes=a-b", 100), ByteRange::Full);
+    }
+
+    #[test]
+    fn serves_ranges_and_honours_etags() {
+        let root = root("ranges");
+        let server = serve(&root, false);
+        let dll: Vec<u8> = (0..100u8).collect();
+
+        let full = get(&server, "/hacks/hack.dll", &[]);
+        assert_eq!(full.status, 200);
+        assert_eq!(full.body, dll);
+        let etag = full.header("etag").unwrap().to_string();
+        assert_eq!(etag, format!("\"{}\"", sha256_hex(&dll)));
+
+        let partial = get(&server, "/hacks/hack.dll", &[("Range", "bytes=10-19")]);
+        assert_eq!(partial.status, 206);
+        assert_eq!(partial.body, dll[10..20]);
+        assert_eq!(partial.header("content-range"), Some("bytes 10-19/100"));
+
+        let current = get(
+            &server,
+            "/hacks/hack.dll",
+            &[("Range", "bytes=10-19"), ("If-Range", &etag)],
+        );
+        assert_eq!(current.status, 206);
+        let stale = get(
+            &server,
+            "/hacks/hack.dll",
+            &[("Range", "bytes=10-19"), ("If-Range", "\"stale\"")],
+        );
+        assert_eq!(stale.status, 200);
+        assert_eq!(stale.body, dll);
+
+        let unsatisfiable = get(&server, "/hacks/hack.dll", &[("Range", "bytes=500-")]);
+        assert_eq!(unsatisfiable.status, 416);
+        assert_eq!(unsatisfiable.header("content-range"), Some("bytes */100"));
+
+        let cached = get(&server, "/hacks/hack.dll", &[("If-None-Match", &etag)]);
+        assert_eq!(cached.status, 304);
+        assert!(cached.body.is_empty());
+
+        let head = send(&server, b"HEAD /hacks/hack.dll HTTP/1.1\r\n\r\n");
+        assert_eq!(head.status, 200);
+        assert_eq!(head.header("etag"), Some(etag.as_str()));
+        assert!(head.body.is_empty());
+
+        drop(server);
+        fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn etags_follow_changed_files() {
+        let root = root("etags");
+        let server = serve(&root, false);
+
+        let before = get(&server, "/hacks/hack.dll", &[]);
+        fs::write(root.join("hacks/hack.dll"), b"changed").unwrap();
+        let after = get(&server, "/hacks/hack.dll", &[]);
+        assert_eq!(after.body, b"changed");
+        assert_eq!(
+            after.header("etag"),
+            Some(format!("\"{}\"", sha256_hex(b"changed")).as_str())
+        );
+        assert_ne!(before.header("etag"), after.header("etag"));
+
+        drop(server);
+        fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn publishes_hashes() {
+        let root = root("hashes");
+        let server = serve(&root, true);
+        let sha256 = sha256_hex(&(0..100u8).collect::<Vec<_>>());
+
+        let catalog = get(&server, "/Hacklist.html", &[]);
+        let entries: Vec<HackApiResponse> = serde_json::from_slice(&catalog.body).unwrap();
+        assert_eq!(entries[0].sha256.as_deref(), Some(sha256.as_str()));
+        assert_eq!(entries[0].size, Some(100));
+
+        let sums = String::from_utf8(get(&server, "/SHA256SUMS", &[]).body).unwrap();
+        assert!(sums.contains(&format!("{}  hacks/hack.dll\n", sha256)));
+        assert!(sums.contains(&format!("{}  Hacklist.html\n", sha256_hex(&catalog.body))));
+
+        drop(server);
+        fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn the_loader_downloads_from_the_server() {
+        let root = root("loader");
+        let server = serve(&root, true);
+        let base = format!("http://{}/", server.addr());
+        let config = Config {
+            // Nothing is served under `missing/`, so every file comes from the fallback.
+            cdn_endpoint: format!("{}missing/", base),
+            cdn_fallback_endpoint: base.clone(),
+            ..Config::default()
+        };
+        let downloader = Downloader::new(Arc::new(UreqTransport::default()), &config);
+
+        let entries: Vec<HackApiResponse> = downloader.fetch_json("Hacklist.html").unwrap();
+        let destination = root.join("downloaded.dll");
+        let url = downloader
+            .download_file(
+                &format!("hacks/{}", entries[0].file),
+                destination.to_str().unwrap(),
+            )
+            .unwrap();
+        assert_eq!(url, format!("{}hacks/hack.dll", base));
+        assert_eq!(
+            entries[0].sha256.as_deref(),
+            Some(sha256_hex(&fs::read(&destination).unwrap()).as_str())
+        );
+
+        drop(server);
+        fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn rejects_oversized_and_escaping_requests() {
+        let root = root("limits");
+        let server = serve(&root, false);
+
+        // A header that never ends, cut at the limit so the server reads all of it and the
+        // reply isn't lost to a reset.
+        let mut request = b"GET /Hacklist.html HTTP/1.1\r\nX-Padding: ".to_vec();
+        request.resize(MAX_REQUEST_BYTES as usize, b'a');
+        assert_eq!(send(&server, &request).status, 400);
+
+        assert_eq!(get(&server, "/../Hacklist.html", &[]).status, 404);
+        assert_eq!(get(&server, "/hacks/%2E%2E/Hacklist.html", &[]).status, 404);
+        assert_eq!(get(&server, "/Hacklist.html", &[]).status, 200);
+
+        drop(server);
+        fs::remove_dir_all(&root).ok();
+    }
+}
@@ -0,0 +1,190 @@
+use std::{
+    env,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::PathBuf,
+    process,
+};
+
+use ed25519_dalek::SigningKey;
+use rand_core::OsRng;
+use simplelog::{ColorChoice, LevelFilter, TermLogger, TerminalMode};
+use unknproject_core::hash::to_hex;
+use unknproject_serve::{CatalogServer, ServeOptions, HASHES_FILE};
+
+const USAGE: &str = "\
+Usage: unknproject-serve [options] [<dir>]
+       unknproject-serve --keygen <key file>
+
+Serves a catalog folder, resources/ by default, for the loader to use as a catalog
+source or CDN: the catalog at /Hacklist.html and the DLLs below /hacks/.
+
+Options:
+  --bind <address>     Address to listen on (default 127.0.0.1)
+  --port <port>        Port to listen on (default 8080)
+  --catalog <name>     File name of the catalog (default Hacklist.html)
+  --hashes             Fill in missing sha256 and size fields and publish SHA256SUMS
+  --sign <key file>    Sign the catalog with an ed25519 key, served as <catalog>.sig
+  --keygen <key file>  Create a signing key and print its public key
+  --verbose            Log every request";
+
+struct Args {
+    root: PathBuf,
+    bind: String,
+    port: u16,
+    catalog: String,
+    hashes: bool,
+    sign: Option<PathBuf>,
+    keygen: Option<PathBuf>,
+    verbose: bool,
+}
+
+fn parse_args(args: &[String]) -> Result<Args, String> {
+    let mut parsed = Args {
+        root: PathBuf::from("resources"),
+        bind: "127.0.0.1".to_string(),
+        port: 8080,
+        catalog: "Hacklist.html".to_string(),
+        hashes: false,
+        sign: None,
+        keygen: None,
+        verbose: false,
+    };
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .cloned()
+                .ok_or_else(|| format!("Missing value for {}.", arg))
+        };
+
+        match arg.as_str() {
+            "--bind" => parsed.bind = value()?,
+            "--port" => {
+                parsed.port = value()?
+                    .parse()
+                    .map_err(|_| "--port expects a number.".to_string())?
+            }
+            "--catalog" => parsed.catalog = value()?,
+            "--hashes" => parsed.hashes = true,
+            "--sign" => parsed.sign = Some(PathBuf::from(value()?)),
+            "--keygen" => parsed.keygen = Some(PathBuf::from(value()?)),
+            "--verbose" => parsed.verbose = true,
+            option if option.starts_with("--") => {
+                return Err(format!("Unknown option {}.", option))
+            }
+            dir => parsed.root = PathBuf::from(dir),
+        }
+    }
+
+    Ok(parsed)
+}
+
+/// Key files hold the 32-byte secret key as hex.
+fn load_key(path: &PathBuf) -> Result<SigningKey, String> {
+    let data = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let data = data.trim();
+
+    let bytes = (0..data.len())
+        .step_by(2)
+        .map(|i| {
+            data.get(i..i + 2)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        })
+        .collect::<Option<Vec<_>>>()
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .ok_or_else(|| format!("{} is not a signing key.", path.display()))?;
+
+    Ok(SigningKey::from_bytes(&bytes))
+}
+
+fn keygen(path: &PathBuf) -> Result<(), String> {
+    let key = SigningKey::generate(&mut OsRng);
+
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    let mut file = options.open(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::AlreadyExists => format!("{} already exists.", path.display()),
+        _ => format!("Failed to create {}: {}", path.display(), e),
+    })?;
+    writeln!(file, "{}", to_hex(&key.to_bytes()))
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    println!("Wrote {}, keep it private.", path.display());
+    println!("Public key: {}", to_hex(key.verifying_key().as_bytes()));
+    Ok(())
+}
+
+fn run(args: Args) -> Result<(), String> {
+    if let Some(path) = &args.keygen {
+        return keygen(path);
+    }
+
+    let signing_key = args.sign.as_ref().map(load_key).transpose()?;
+    if !args.root.join(&args.catalog).is_file() {
+        return Err(format!("{} has no {}.", args.root.display(), args.catalog));
+    }
+
+    let server = CatalogServer::start(
+        (args.bind.as_str(), args.port),
+        ServeOptions {
+            root: args.root.clone(),
+            catalog: args.catalog.clone(),
+            hashes: args.hashes,
+            signing_key: signing_key.clone(),
+        },
+    )?;
+
+    let url = server.url();
+    println!("Serving {} on {}", args.root.display(), url);
+    println!("Catalog source: {}{}", url, args.catalog);
+    println!("CDN endpoint:   {}hacks/", url);
+    if args.hashes {
+        println!("Hashes:         {}{}", url, HASHES_FILE);
+    }
+    if let Some(key) = signing_key {
+        println!("Signature:      {}{}.sig", url, args.catalog);
+        println!("Public key:     {}", to_hex(key.verifying_key().as_bytes()));
+    }
+
+    server.wait();
+    Ok(())
+}
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    if args.iter().any(|arg| arg == "--help" || arg == "-h") {
+        println!("{}", USAGE);
+        return;
+    }
+
+    let args = match parse_args(&args) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            process::exit(2);
+        }
+    };
+
+    TermLogger::init(
+        if args.verbose {
+            LevelFilter::Info
+        } else {
+            LevelFilter::Warn
+        },
+        simplelog::Config::default(),
+        TerminalMode::Stderr,
+        ColorChoice::Auto,
+    )
+    .ok();
+
+    if let Err(e) = run(args) {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}